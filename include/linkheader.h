@@ -0,0 +1,63 @@
+/*
+ * Copyright 2019 Arnau Siches
+ *
+ * Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+ * This file may not be copied, modified, or distributed except
+ * according to those terms.
+ */
+
+/*
+ * C API of the linkheader library, built with the `ffi` feature.
+ *
+ * Strings are UTF-8 and NUL-terminated. Every returned string is owned by the
+ * caller and released with linkheader_string_free. Functions return NULL
+ * when there is nothing to return or on failure, in which case
+ * linkheader_last_error explains why.
+ */
+
+#ifndef LINKHEADER_H
+#define LINKHEADER_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A parsed Link header, released with linkheader_free. */
+typedef struct LinkHeader LinkHeader;
+
+/* A link borrowed from its header. */
+typedef struct Link Link;
+
+/* Parses a Link header field value. The context URL can be NULL. */
+LinkHeader *linkheader_parse(const char *input, const char *context);
+
+/* The message of the last failure in the current thread, if any. */
+char *linkheader_last_error(void);
+
+void linkheader_free(LinkHeader *header);
+void linkheader_string_free(char *text);
+
+size_t linkheader_len(const LinkHeader *header);
+
+/* The link at the given position, valid as long as the header. */
+const Link *linkheader_get(const LinkHeader *header, size_t index);
+
+/* Serialises the header as a Link header field value. */
+char *linkheader_to_string(const LinkHeader *header);
+
+char *linkheader_link_target(const Link *link);
+char *linkheader_link_rel(const Link *link);
+
+/* The effective context URL, if known. */
+char *linkheader_link_context(const Link *link);
+
+/* The first value of the given attribute or param, e.g. "title". */
+char *linkheader_link_param(const Link *link, const char *name);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
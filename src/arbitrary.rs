@@ -0,0 +1,141 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Structured fuzzing inputs.
+//!
+//! Generated values stick to what the parser reads back: a generated header
+//! with links serialises to a value that parses to the same links. Links have no
+//! context and header links are numbered as the parser numbers link-values.
+
+use crate::header::Header;
+use crate::link::{Link, Relation};
+use crate::param::{Encoding, Param, Value};
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+const TOKEN: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$&+-.^_`|~";
+const TARGET: &str = "abcdefghijklmnopqrstuvwxyz0123456789/?#=&.-_~:%@";
+const RELATION: &str = "abcdefghijklmnopqrstuvwxyz0123456789.-_:/";
+const LANGUAGE: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ-";
+const TEXT: &str =
+    " abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$%&'()*+,-./:;<=>?@[]^_`{|}~";
+const UNICODE: &str = " abcXYZ019äéñçøß€中文日本語Ωж";
+
+/// Names kept as attributes, i.e. not params, by the parser.
+const RESERVED: [&str; 6] = ["rel", "anchor", "title", "hreflang", "media", "type"];
+
+/// A string of `min` or more characters from the alphabet.
+fn text(u: &mut Unstructured, alphabet: &str, min: usize) -> Result<String> {
+    let chars: Vec<char> = alphabet.chars().collect();
+    let len = min + u.arbitrary_len::<u8>()?;
+
+    (0..len).map(|_| u.choose(&chars).copied()).collect()
+}
+
+impl<'a> Arbitrary<'a> for Value {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Value> {
+        if u.arbitrary()? {
+            Ok(Value::Compound {
+                encoding: Encoding::Utf8,
+                language: Some(text(u, LANGUAGE, 1)?),
+                value: text(u, UNICODE, 1)?,
+            })
+        } else {
+            Ok(Value::Simple(text(u, TEXT, 0)?))
+        }
+    }
+}
+
+impl<'a> Arbitrary<'a> for Param {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Param> {
+        let mut name = text(u, TOKEN, 1)?;
+
+        if RESERVED
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(&name))
+        {
+            name.insert_str(0, "x-");
+        }
+
+        Ok(Param::new(name, u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Link {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Link> {
+        let simple = |u: &mut Unstructured<'a>, alphabet| -> Result<Option<Value>> {
+            Ok(if u.arbitrary()? {
+                Some(Value::Simple(text(u, alphabet, 1)?))
+            } else {
+                None
+            })
+        };
+        let relation = if u.arbitrary()? {
+            Some(Relation::from(text(u, RELATION, 1)?))
+        } else {
+            None
+        };
+        let lang = (0..u.arbitrary_len::<String>()?)
+            .map(|_| Ok(Value::Simple(text(u, LANGUAGE, 1)?)))
+            .collect::<Result<_>>()?;
+
+        Ok(Link {
+            target: text(u, TARGET, 0)?.into(),
+            context: Default::default(),
+            relation,
+            title: u.arbitrary()?,
+            lang,
+            media: simple(u, TOKEN)?,
+            content_type: simple(u, TOKEN)?,
+            params: u.arbitrary()?,
+            group: None,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Header {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Header> {
+        let mut links: Vec<Link> = u.arbitrary()?;
+
+        for (group, link) in links.iter_mut().enumerate() {
+            link.group = Some(group);
+        }
+
+        Ok(Header::from(links))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn parse_roundtrip() {
+        let mut state = 0x2545_f491u32;
+        let mut non_empty = 0;
+
+        for _ in 0..200 {
+            let data: Vec<u8> = (0..512)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    state as u8
+                })
+                .collect();
+            let header = Header::arbitrary(&mut Unstructured::new(&data)).unwrap();
+
+            if header.is_empty() {
+                continue;
+            }
+
+            non_empty += 1;
+            assert_eq!(parse(&header.to_string(), None).unwrap(), header);
+        }
+
+        assert!(non_empty > 50);
+    }
+}
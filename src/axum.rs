@@ -0,0 +1,183 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Extracting and emitting `Link` headers in `axum` services.
+
+use crate::header::Header;
+use crate::http::uri_context;
+use axum_core::extract::{FromRequestParts, OptionalFromRequestParts};
+use axum_core::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
+use http::request::Parts;
+use http::StatusCode;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Deref;
+
+/// The `Link` header of a request or a response.
+///
+/// As an extractor, it parses and merges every `Link` field of the request.
+/// The request URI is the context when it is absolute, otherwise relative
+/// targets are kept as they are. A request without `Link` fields extracts an
+/// empty header; use `Option<Links>` to tell both cases apart.
+///
+/// As a response part, it sets the `Link` field of the response.
+///
+/// ```
+/// use axum_core::extract::FromRequestParts;
+/// use axum_core::response::IntoResponse;
+/// use linkheader::axum::Links;
+/// use linkheader::parse;
+///
+/// let request = http::Request::get("/items?page=2")
+///     .header("link", r#"</items?page=1>; rel="prev""#)
+///     .body(())
+///     .unwrap();
+/// let (mut parts, _) = request.into_parts();
+/// let Links(header) =
+///     futures_executor::block_on(Links::from_request_parts(&mut parts, &())).unwrap();
+///
+/// assert_eq!(header.prev().unwrap().target.as_str(), "/items?page=1");
+///
+/// let header = parse(r#"</items?page=3>; rel="next""#, None).unwrap();
+/// let response = (Links(header), "page 2").into_response();
+///
+/// assert_eq!(response.headers()["link"], r#"</items?page=3>; rel="next""#);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Links(pub Header);
+
+impl Deref for Links {
+    type Target = Header;
+
+    fn deref(&self) -> &Header {
+        &self.0
+    }
+}
+
+impl From<Header> for Links {
+    fn from(header: Header) -> Links {
+        Links(header)
+    }
+}
+
+impl<S: Sync> FromRequestParts<S> for Links {
+    type Rejection = LinkRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Links, LinkRejection> {
+        Header::from_header_map(&parts.headers, uri_context(&parts.uri))
+            .map(Links)
+            .map_err(LinkRejection)
+    }
+}
+
+impl<S: Sync> OptionalFromRequestParts<S> for Links {
+    type Rejection = LinkRejection;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> Result<Option<Links>, LinkRejection> {
+        if parts.headers.contains_key(http::header::LINK) {
+            <Links as FromRequestParts<S>>::from_request_parts(parts, state)
+                .await
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl IntoResponseParts for Links {
+    type Error = InvalidLinks;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, InvalidLinks> {
+        let value = http::HeaderValue::try_from(&self.0).map_err(InvalidLinks)?;
+
+        res.headers_mut().insert(http::header::LINK, value);
+
+        Ok(res)
+    }
+}
+
+impl IntoResponse for Links {
+    fn into_response(self) -> Response {
+        (self, ()).into_response()
+    }
+}
+
+/// Rejects a request with a malformed `Link` header as `400 Bad Request`.
+#[derive(Debug)]
+pub struct LinkRejection(pub failure::Error);
+
+impl fmt::Display for LinkRejection {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Invalid Link header: {}", self.0)
+    }
+}
+
+impl IntoResponse for LinkRejection {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
+
+/// A header that cannot be sent as a header value, responded as
+/// `500 Internal Server Error`.
+#[derive(Debug)]
+pub struct InvalidLinks(pub failure::Error);
+
+impl fmt::Display for InvalidLinks {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Invalid Link header value: {}", self.0)
+    }
+}
+
+impl IntoResponse for InvalidLinks {
+    fn into_response(self) -> Response {
+        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(request: http::Request<()>) -> Result<Option<Links>, LinkRejection> {
+        let (mut parts, _) = request.into_parts();
+
+        futures_executor::block_on(<Links as OptionalFromRequestParts<()>>::from_request_parts(
+            &mut parts,
+            &(),
+        ))
+    }
+
+    #[test]
+    fn optional_extraction() {
+        let request = http::Request::get("/").body(()).unwrap();
+
+        assert!(extract(request).unwrap().is_none());
+
+        let request = http::Request::get("https://example.org/items")
+            .header("link", r#"<?page=2>; rel="next""#)
+            .body(())
+            .unwrap();
+        let links = extract(request).unwrap().unwrap();
+        let next = links.next().unwrap().resolve_target(None).unwrap();
+
+        assert_eq!(next.as_str(), "https://example.org/items?page=2");
+    }
+
+    #[test]
+    fn reject_malformed() {
+        let request = http::Request::get("/")
+            .header("link", "nonsense")
+            .body(())
+            .unwrap();
+        let rejection = extract(request).unwrap_err();
+
+        assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+}
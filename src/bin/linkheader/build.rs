@@ -0,0 +1,112 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! `linkheader build`: prints the Link header value described by a JSON or
+//! TOML document.
+//!
+//! The JSON description is the output of `linkheader parse`. The TOML one has
+//! the same links as an array of `link` tables:
+//!
+//! ```toml
+//! [[link]]
+//! href = "/page/2"
+//! rel = "next"
+//! params = [{ name = "title", value = "Page 2" }, { name = "nopush" }]
+//! ```
+
+use crate::{json, read_stdin};
+use clap::ValueEnum;
+use failure::format_err;
+use linkheader::error::Result;
+use linkheader::{parse, Header};
+use serde_json::Value as Json;
+use std::fs;
+use std::path::PathBuf;
+
+/// Build a Link header value from a JSON or TOML description of its links.
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The description to read. Defaults to stdin.
+    file: Option<PathBuf>,
+    /// The format of the description. Defaults to the file extension or
+    /// else JSON.
+    #[arg(long, value_enum)]
+    from: Option<Format>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Json,
+    Toml,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let extension = args.file.as_ref().and_then(|file| file.extension());
+    let format = match (args.from, extension) {
+        (Some(format), _) => format,
+        (None, Some(extension)) if extension == "toml" => Format::Toml,
+        (None, _) => Format::Json,
+    };
+    let input = match &args.file {
+        Some(file) => fs::read_to_string(file)?,
+        None => read_stdin()?,
+    };
+
+    println!("{}", build(&input, format)?);
+
+    Ok(())
+}
+
+fn build(input: &str, format: Format) -> Result<Header> {
+    let description = match format {
+        Format::Json => serde_json::from_str(input)?,
+        Format::Toml => {
+            let mut document: Json = toml::from_str(input)?;
+
+            document
+                .get_mut("link")
+                .map(Json::take)
+                .unwrap_or_else(|| Json::Array(vec![]))
+        }
+    };
+    let header = json::to_header(&description)?;
+
+    // Targets and param names are written as given so make sure the result
+    // is a valid header value.
+    parse(&header.to_string(), None)
+        .map_err(|err| format_err!("the links do not make a valid header value: {}", err))?;
+
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_description() {
+        let input = r#"
+            [[link]]
+            href = "/page/2"
+            rel = "next"
+            params = [{ name = "title", value = "Seite 2", language = "de" }, { name = "nopush" }]
+
+            [[link]]
+            href = "/"
+            rel = "index"
+        "#;
+
+        assert_eq!(
+            build(input, Format::Toml).unwrap().to_string(),
+            r#"</page/2>; rel="next"; title*=UTF-8'de'Seite%202; nopush, </>; rel="index""#
+        );
+        assert!(build(
+            r#"[{"href": "/", "params": [{"name": "a b"}]}]"#,
+            Format::Json
+        )
+        .is_err());
+    }
+}
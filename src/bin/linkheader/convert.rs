@@ -0,0 +1,88 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! `linkheader convert`: translates links between serialisations.
+
+use crate::{read_header, read_stdin};
+use clap::ValueEnum;
+use linkheader::error::Result;
+use linkheader::{html, link_format, linkset, Header};
+
+/// Convert links read from stdin from one serialisation to another.
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The serialisation of the input.
+    #[arg(long, value_enum, default_value_t = Serialisation::Header)]
+    from: Serialisation,
+    /// The serialisation of the output.
+    #[arg(long, value_enum)]
+    to: Serialisation,
+    /// The URL of the resource, used as the link context.
+    #[arg(long, value_name = "URL")]
+    context: Option<url::Url>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Serialisation {
+    /// A Link header value or a raw HTTP response head.
+    Header,
+    /// An `application/linkset` document (RFC9264).
+    Linkset,
+    /// An `application/linkset+json` document (RFC9264).
+    LinksetJson,
+    /// A CoRE Link Format document (RFC6690).
+    LinkFormat,
+    /// HTML `<link>` elements.
+    Html,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let header = read(&read_stdin()?, args.from, args.context)?;
+
+    println!("{}", write(&header, args.to)?);
+
+    Ok(())
+}
+
+fn read(input: &str, format: Serialisation, context: Option<url::Url>) -> Result<Header> {
+    match format {
+        Serialisation::Header => read_header(input, context),
+        Serialisation::Linkset => linkset::parse(input, context),
+        Serialisation::LinksetJson => linkset::parse_json(input, context),
+        Serialisation::LinkFormat => link_format::parse(input, context),
+        Serialisation::Html => Ok(html::parse(input, context)),
+    }
+}
+
+fn write(header: &Header, format: Serialisation) -> Result<String> {
+    let output = match format {
+        Serialisation::Header => header.to_string(),
+        Serialisation::Linkset => header.to_linkset().to_string(),
+        Serialisation::LinksetJson => serde_json::to_string_pretty(&header.to_linkset_json())?,
+        Serialisation::LinkFormat => header.to_link_format().to_string(),
+        Serialisation::Html => header.to_html().to_string(),
+    };
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_to_header() {
+        let context = url::Url::parse("https://example.org/").ok();
+        let document =
+            r#"<link rel="alternate" type="application/atom+xml" href="/feed.atom" title="Posts">"#;
+        let header = read(document, Serialisation::Html, context).unwrap();
+
+        assert_eq!(
+            write(&header, Serialisation::Header).unwrap(),
+            r#"</feed.atom>; rel="alternate"; title="Posts"; type="application/atom+xml""#
+        );
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! `linkheader follow`: walks the `rel="next"` links of a paginated
+//! collection.
+
+use futures_util::StreamExt;
+use linkheader::error::Result;
+use linkheader::follow::{follow_with_limit, MAX_PAGES};
+use std::time::Duration;
+
+/// Fetch a URL and follow its rel="next" links to the last page, printing
+/// the URL of every page. Requires building with the `cli-fetch` feature.
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The URL of the first page.
+    url: url::Url,
+    /// The maximum number of pages to fetch.
+    #[arg(long, default_value_t = MAX_PAGES)]
+    max_pages: usize,
+    /// The milliseconds to wait before fetching each page after the first.
+    #[arg(long, value_name = "MILLISECONDS", default_value_t = 0)]
+    delay: u64,
+    /// Print the body of every page instead of its URL.
+    #[arg(long)]
+    body: bool,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(walk(args))
+}
+
+async fn walk(args: Args) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .user_agent(crate::USER_AGENT)
+        .build()?;
+    let delay = Duration::from_millis(args.delay);
+    let first = client
+        .get(args.url.as_str())
+        .send()
+        .await?
+        .error_for_status()?;
+    let fetch = |url: url::Url| {
+        let request = client.get(url.as_str());
+
+        async move {
+            tokio::time::sleep(delay).await;
+            request.send().await?.error_for_status()
+        }
+    };
+    let mut pages = Box::pin(follow_with_limit(first, fetch, args.max_pages));
+
+    while let Some(page) = pages.next().await {
+        let page = page?;
+
+        if args.body {
+            println!("{}", page.text().await?);
+        } else {
+            println!("{}", page.url());
+        }
+    }
+
+    Ok(())
+}
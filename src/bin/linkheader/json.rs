@@ -0,0 +1,203 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! The JSON form of links printed by the tool.
+//!
+//! ```json
+//! [{
+//!     "href": "/page/2",
+//!     "anchor": "https://example.org/",
+//!     "rel": "next",
+//!     "params": [
+//!         { "name": "title", "value": "Page 2" },
+//!         { "name": "title", "value": "Seite 2", "language": "de" },
+//!         { "name": "nopush", "value": null }
+//!     ]
+//! }]
+//! ```
+//!
+//! "anchor" is the resolved link context when known and "rel" is left out
+//! for links with no relation type. Compound values carry their language, if
+//! any.
+//!
+//! When read, a missing "value" is the same as `null` and values with
+//! non-ASCII characters are written as star params.
+
+use failure::format_err;
+use linkheader::error::Result;
+use linkheader::parser::LinkBuilder;
+use linkheader::{Encoding, Header, Link, Param, Value};
+use serde_json::{json, Map, Value as Json};
+
+pub fn from_header(header: &Header) -> Json {
+    Json::Array(header.iter().map(from_link).collect())
+}
+
+pub fn from_link(link: &Link) -> Json {
+    let mut object = Map::new();
+
+    object.insert("href".into(), link.target.as_str().into());
+
+    let anchor = match link.context.url() {
+        Some(url) => Some(url.as_str()),
+        None => link.context.anchor(),
+    };
+
+    if let Some(anchor) = anchor {
+        object.insert("anchor".into(), anchor.into());
+    }
+
+    if let Some(relation) = &link.relation {
+        object.insert("rel".into(), relation.as_str().into());
+    }
+
+    let params: Vec<Json> = link
+        .attributes()
+        .into_iter()
+        .map(|param| {
+            let name = param.name().to_string();
+
+            match param.into_value() {
+                None => json!({ "name": name, "value": null }),
+                Some(Value::Simple(value)) => json!({ "name": name, "value": value }),
+                Some(Value::Compound {
+                    language: Some(language),
+                    value,
+                    ..
+                }) => json!({ "name": name, "value": value, "language": language }),
+                Some(Value::Compound { value, .. }) => json!({ "name": name, "value": value }),
+            }
+        })
+        .collect();
+
+    object.insert("params".into(), params.into());
+
+    Json::Object(object)
+}
+
+pub fn to_header(input: &Json) -> Result<Header> {
+    let links = input
+        .as_array()
+        .ok_or_else(|| format_err!("expected an array of links"))?;
+    let mut result = vec![];
+
+    for (group, object) in links.iter().enumerate() {
+        let object = object
+            .as_object()
+            .ok_or_else(|| format_err!("expected a link object"))?;
+        let mut builder = LinkBuilder::new(None);
+        builder.set_group(group);
+        collect_link(&mut builder, object)?;
+        result.extend(builder.build());
+    }
+
+    Ok(Header::from(result))
+}
+
+fn collect_link(builder: &mut LinkBuilder, object: &Map<String, Json>) -> Result<()> {
+    let href =
+        text(object, "href")?.ok_or_else(|| format_err!("expected a link with an \"href\""))?;
+
+    builder.set_target(href);
+
+    if let Some(anchor) = text(object, "anchor")? {
+        builder.set_anchor(Value::Simple(anchor.into()));
+    }
+
+    if let Some(relation) = text(object, "rel")? {
+        builder.set_rel(Value::Simple(relation.into()));
+    }
+
+    let params = match object.get("params") {
+        Some(params) => params
+            .as_array()
+            .ok_or_else(|| format_err!("expected \"params\" to be an array"))?
+            .as_slice(),
+        None => &[],
+    };
+
+    for param in params {
+        let param = param
+            .as_object()
+            .ok_or_else(|| format_err!("expected a param object"))?;
+        let name =
+            text(param, "name")?.ok_or_else(|| format_err!("expected a param with a \"name\""))?;
+        let value = to_value(text(param, "value")?, text(param, "language")?);
+
+        match (name.to_lowercase().as_str(), value) {
+            ("anchor", Some(value)) => builder.set_anchor(value),
+            ("rel", Some(value)) => builder.set_rel(value),
+            ("title", Some(value)) => builder.set_title(value),
+            ("hreflang", Some(value)) => builder.set_lang(value),
+            ("media", Some(value)) => builder.set_media(value),
+            ("type", Some(value)) => builder.set_type(value),
+            (_, value) => builder.add_param(Param::new(name, value)),
+        }
+    }
+
+    Ok(())
+}
+
+fn text<'a>(object: &'a Map<String, Json>, name: &str) -> Result<Option<&'a str>> {
+    match object.get(name) {
+        None | Some(Json::Null) => Ok(None),
+        Some(Json::String(value)) => Ok(Some(value)),
+        Some(_) => Err(format_err!("expected {:?} to be a string", name)),
+    }
+}
+
+fn to_value(value: Option<&str>, language: Option<&str>) -> Option<Value> {
+    let value = value?;
+
+    if language.is_none() && value.is_ascii() {
+        Some(Value::Simple(value.into()))
+    } else {
+        Some(Value::Compound {
+            encoding: Encoding::Utf8,
+            language: language.map(String::from),
+            value: value.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linkheader::parse;
+
+    #[test]
+    fn links_as_json() {
+        let context = url::Url::parse("https://example.org/").ok();
+        let input = r#"</2>; rel="next"; title*=UTF-8'de'Seite%202; nopush, </about>"#;
+        let header = parse(input, context).unwrap();
+
+        assert_eq!(
+            from_header(&header),
+            json!([
+                {
+                    "href": "/2",
+                    "anchor": "https://example.org/",
+                    "rel": "next",
+                    "params": [
+                        { "name": "title", "value": "Seite 2", "language": "de" },
+                        { "name": "nopush", "value": null },
+                    ],
+                },
+                { "href": "/about", "anchor": "https://example.org/", "params": [] },
+            ])
+        );
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let input = r#"</2>; rel="next"; title*=UTF-8'de'Seite%202; hreflang="de"; nopush, </about>; anchor="/x""#;
+        let header = parse(input, None).unwrap();
+        let actual = to_header(&from_header(&header)).unwrap();
+
+        assert_eq!(actual.to_string(), header.to_string());
+        assert!(to_header(&json!([{ "rel": "next" }])).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! `linkheader lint`: reports the issues found in a Link header and fails
+//! when there is any violation.
+
+use crate::{read_header, read_stdin};
+use clap::ValueEnum;
+use failure::format_err;
+use linkheader::error::Result;
+use linkheader::lint::{Check, Linter, Report};
+use linkheader::Header;
+
+/// Check a Link header value, or a raw HTTP response head, read from stdin.
+///
+/// Exits with an error when there is any error, or any warning with
+/// `--deny-warnings`. Checking a URL with `--url` requires building with the
+/// `cli-fetch` feature.
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Fetch the URL and check its Link header instead of reading stdin.
+    #[cfg(feature = "reqwest")]
+    #[arg(long)]
+    url: Option<url::Url>,
+    /// The URL of the resource, used as the link context.
+    #[arg(long, value_name = "URL")]
+    context: Option<url::Url>,
+    /// A check to skip. Can be repeated.
+    #[arg(long, value_enum, value_name = "CHECK")]
+    disable: Vec<Rule>,
+    /// Fail on warnings too.
+    #[arg(long)]
+    deny_warnings: bool,
+}
+
+/// The built-in checks, named as reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Rule {
+    DuplicateSingleton,
+    ReverseRelation,
+    UnregisteredRelation,
+    InvalidType,
+}
+
+impl From<Rule> for Check {
+    fn from(rule: Rule) -> Check {
+        match rule {
+            Rule::DuplicateSingleton => Check::DuplicateSingleton,
+            Rule::ReverseRelation => Check::ReverseRelation,
+            Rule::UnregisteredRelation => Check::UnregisteredRelation,
+            Rule::InvalidType => Check::InvalidType,
+        }
+    }
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let header = read(&args)?;
+    let linter = args
+        .disable
+        .iter()
+        .fold(Linter::new(), |linter, rule| linter.disable((*rule).into()));
+    let report = linter.run(&header);
+
+    print!("{}", report);
+
+    verdict(&report, args.deny_warnings)
+}
+
+#[cfg(feature = "reqwest")]
+fn read(args: &Args) -> Result<Header> {
+    match &args.url {
+        Some(url) => fetch(url),
+        None => read_header(&read_stdin()?, args.context.clone()),
+    }
+}
+
+#[cfg(not(feature = "reqwest"))]
+fn read(args: &Args) -> Result<Header> {
+    read_header(&read_stdin()?, args.context.clone())
+}
+
+/// Reads the `Link` header of the response to a GET request, failing when the
+/// response has none.
+#[cfg(feature = "reqwest")]
+fn fetch(url: &url::Url) -> Result<Header> {
+    use linkheader::reqwest::ResponseExt;
+
+    let client = reqwest::Client::builder()
+        .user_agent(crate::USER_AGENT)
+        .build()?;
+    let response = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(client.get(url.as_str()).send())?;
+
+    response
+        .link_header()?
+        .ok_or_else(|| format_err!("no Link header in the response from {}", url))
+}
+
+fn verdict(report: &Report, deny_warnings: bool) -> Result<()> {
+    let errors = report.errors().count();
+    let warnings = report.warnings().count();
+
+    if errors > 0 || (deny_warnings && warnings > 0) {
+        Err(format_err!(
+            "{} error(s) and {} warning(s) found",
+            errors,
+            warnings
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linkheader::parse;
+
+    #[test]
+    fn warnings_pass_unless_denied() {
+        let header = parse(r#"</a>; rel="sidebar""#, None).unwrap();
+        let report = Linter::new().run(&header);
+
+        assert!(verdict(&report, false).is_ok());
+        assert!(verdict(&report, true).is_err());
+        assert!(verdict(&Report::default(), true).is_ok());
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! The `linkheader` command line tool.
+
+use clap::{Parser, Subcommand};
+use linkheader::error::Result;
+use linkheader::{head, linkset, Header};
+use std::io::{self, Read};
+use std::process;
+
+mod build;
+mod convert;
+#[cfg(all(feature = "reqwest", feature = "follow"))]
+mod follow;
+mod json;
+mod lint;
+mod parse;
+mod query;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "linkheader",
+    version,
+    about = "Read and write HTTP Link headers"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    Build(build::Args),
+    Convert(convert::Args),
+    #[cfg(all(feature = "reqwest", feature = "follow"))]
+    Follow(follow::Args),
+    Lint(lint::Args),
+    Parse(parse::Args),
+    Query(query::Args),
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Build(args) => build::run(args),
+        Command::Convert(args) => convert::run(args),
+        #[cfg(all(feature = "reqwest", feature = "follow"))]
+        Command::Follow(args) => follow::run(args),
+        Command::Lint(args) => lint::run(args),
+        Command::Parse(args) => parse::run(args),
+        Command::Query(args) => query::run(args),
+    };
+
+    if let Err(err) = result {
+        eprintln!("linkheader: {}", err);
+        process::exit(1);
+    }
+}
+
+/// The `User-Agent` sent when fetching, required by APIs such as GitHub's.
+#[cfg(feature = "reqwest")]
+const USER_AGENT: &str = concat!("linkheader/", env!("CARGO_PKG_VERSION"));
+
+/// Reads the standard input to the end.
+fn read_stdin() -> Result<String> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    Ok(input)
+}
+
+/// Reads the links from either a `Link` header value or a raw HTTP response
+/// head, e.g. the output of `curl -i`.
+///
+/// The input is taken as a header value when it starts with `<`, in which
+/// case link-values and params can be split across lines.
+fn read_header(input: &str, context: Option<url::Url>) -> Result<Header> {
+    if input.trim_start().starts_with('<') {
+        linkset::parse(input, context)
+    } else {
+        head::parse(input, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_value_or_head() {
+        let value = read_header("</1>; rel=\"prev\",\n</3>; rel=\"next\"\n", None).unwrap();
+        let head = read_header(
+            "HTTP/1.1 200 OK\r\nLink: </1>; rel=\"prev\"\r\nLink: </3>; rel=\"next\"\r\n\r\n",
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(value.len(), 2);
+        assert_eq!(value, head);
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! `linkheader parse`: prints the links read from the standard input as JSON.
+
+use crate::{json, read_header, read_stdin};
+use linkheader::error::Result;
+
+/// Parse a Link header value, or a raw HTTP response head, from stdin and
+/// print its links as JSON.
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The URL of the resource, used as the link context.
+    #[arg(long, value_name = "URL")]
+    context: Option<url::Url>,
+    /// Print the JSON in a single line.
+    #[arg(long)]
+    compact: bool,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let header = read_header(&read_stdin()?, args.context)?;
+    let output = json::from_header(&header);
+
+    if args.compact {
+        println!("{}", output);
+    } else {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    }
+
+    Ok(())
+}
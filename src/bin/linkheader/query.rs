@@ -0,0 +1,108 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! `linkheader query`: prints the links matching some criteria, e.g.
+//! `linkheader query --rel next --format url`.
+
+use crate::{json, read_header, read_stdin};
+use clap::ValueEnum;
+use linkheader::error::Result;
+use linkheader::{Link, Query};
+
+/// Select links from a Link header value, or a raw HTTP response head, read
+/// from stdin.
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The relation type the links must have.
+    #[arg(long)]
+    rel: Option<String>,
+    /// The media type the links must have, e.g. `image/*`.
+    #[arg(long = "type", value_name = "MEDIA_TYPE")]
+    media_type: Option<String>,
+    /// The language the links must have.
+    #[arg(long)]
+    hreflang: Option<String>,
+    /// The URL of the resource, used as the link context.
+    #[arg(long, value_name = "URL")]
+    context: Option<url::Url>,
+    /// Print the first matching link only.
+    #[arg(long)]
+    first: bool,
+    /// How to print the links.
+    #[arg(long, value_enum, default_value_t = Format::Link)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// The target URL, resolved against the context when possible.
+    Url,
+    /// The link-value.
+    Link,
+    /// The JSON form of the link, one per line.
+    Json,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let header = read_header(&read_stdin()?, args.context.clone())?;
+    let query = query(&args);
+    let links = header.select(&query);
+    let limit = if args.first { 1 } else { links.len() };
+
+    for link in links.into_iter().take(limit) {
+        println!("{}", format(link, args.format));
+    }
+
+    Ok(())
+}
+
+fn query(args: &Args) -> Query {
+    let mut query = Query::new();
+
+    if let Some(relation) = &args.rel {
+        query = query.rel(relation);
+    }
+
+    if let Some(media_type) = &args.media_type {
+        query = query.media_type(media_type);
+    }
+
+    if let Some(lang) = &args.hreflang {
+        query = query.hreflang(lang);
+    }
+
+    query
+}
+
+fn format(link: &Link, format: Format) -> String {
+    match format {
+        Format::Url => match link.resolve_target(None) {
+            Ok(url) => url.to_string(),
+            Err(_) => link.target.to_string(),
+        },
+        Format::Link => link.to_string(),
+        Format::Json => json::from_link(link).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linkheader::parse;
+
+    #[test]
+    fn urls_resolve_when_possible() {
+        let context = url::Url::parse("https://example.org/items?page=2").ok();
+        let with_context = parse(r#"<?page=3>; rel="next""#, context).unwrap();
+        let without = parse(r#"<?page=3>; rel="next""#, None).unwrap();
+
+        assert_eq!(
+            format(&with_context.links()[0], Format::Url),
+            "https://example.org/items?page=3"
+        );
+        assert_eq!(format(&without.links()[0], Format::Url), "?page=3");
+    }
+}
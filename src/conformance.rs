@@ -0,0 +1,299 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A conformance corpus of `Link` header field values and their expected
+//! outcomes.
+//!
+//! The cases come from the RFC8288 Section 3.5 examples and from the
+//! greenbytes HTTP Link header test cases
+//! (<http://test.greenbytes.de/tech/tc/httplink/>). Expectations are plain
+//! data so other implementations can run the same suite:
+//!
+//! ```
+//! use linkheader::conformance::CASES;
+//! use linkheader::parse;
+//!
+//! for case in CASES {
+//!     let context = case.context.and_then(|context| url::Url::parse(context).ok());
+//!     let header = parse(case.input, context).ok();
+//!
+//!     assert!(case.matches(header.as_ref()), "{}", case.name);
+//! }
+//! ```
+
+use crate::header::Header;
+use crate::link::{Link, Relation};
+use crate::param::Value;
+
+/// Where a case comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// RFC8288 Section 3.5.
+    Rfc8288,
+    /// The greenbytes HTTP Link header test cases.
+    Greenbytes,
+}
+
+/// A conformance case.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Case {
+    pub name: &'static str,
+    pub source: Source,
+    /// The header field value.
+    pub input: &'static str,
+    /// The URL of the resource the header comes from, if any.
+    pub context: Option<&'static str>,
+    pub outcome: Outcome,
+}
+
+/// The expected outcome of parsing a case.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    /// The links, in order, one per relation type.
+    Links(&'static [Expected]),
+    /// The value must be rejected.
+    Invalid,
+}
+
+/// An expected link.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Expected {
+    /// The target as given.
+    pub target: &'static str,
+    pub rel: Option<&'static str>,
+    /// The effective context URL, if known.
+    pub context: Option<&'static str>,
+    /// The decoded title, preferring "title*".
+    pub title: Option<&'static str>,
+}
+
+impl Case {
+    /// Whether the outcome of parsing the case, `None` when rejected, is the
+    /// expected one.
+    pub fn matches(&self, header: Option<&Header>) -> bool {
+        match (self.outcome, header) {
+            (Outcome::Invalid, None) => true,
+            (Outcome::Links(expected), Some(header)) => {
+                expected.len() == header.len()
+                    && expected
+                        .iter()
+                        .zip(header.iter())
+                        .all(|(expected, link)| expected.matches(link))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Expected {
+    pub fn matches(&self, link: &Link) -> bool {
+        link.target.as_str() == self.target
+            && link.relation.as_ref().map(Relation::as_str) == self.rel
+            && link.context.url().map(url::Url::as_str) == self.context
+            && link.title.as_ref().map(Value::text) == self.title
+    }
+}
+
+const fn link(target: &'static str, rel: &'static str) -> Expected {
+    Expected {
+        target,
+        rel: Some(rel),
+        context: None,
+        title: None,
+    }
+}
+
+const fn titled(target: &'static str, rel: &'static str, title: &'static str) -> Expected {
+    Expected {
+        target,
+        rel: Some(rel),
+        context: None,
+        title: Some(title),
+    }
+}
+
+/// Every case, RFC8288 first.
+pub const CASES: &[Case] = &[
+    Case {
+        name: "rfc8288-1",
+        source: Source::Rfc8288,
+        input: r#"<http://example.com/TheBook/chapter2>; rel="previous"; title="previous chapter""#,
+        context: None,
+        outcome: Outcome::Links(&[titled(
+            "http://example.com/TheBook/chapter2",
+            "previous",
+            "previous chapter",
+        )]),
+    },
+    Case {
+        name: "rfc8288-2",
+        source: Source::Rfc8288,
+        input: r#"</>; rel="http://example.net/foo""#,
+        context: None,
+        outcome: Outcome::Links(&[link("/", "http://example.net/foo")]),
+    },
+    Case {
+        name: "rfc8288-3",
+        source: Source::Rfc8288,
+        input: r##"</terms>; rel="copyright"; anchor="#foo""##,
+        context: Some("http://example.org/"),
+        outcome: Outcome::Links(&[Expected {
+            target: "/terms",
+            rel: Some("copyright"),
+            context: Some("http://example.org/#foo"),
+            title: None,
+        }]),
+    },
+    Case {
+        name: "rfc8288-4",
+        source: Source::Rfc8288,
+        input: r#"</TheBook/chapter2>; rel="previous"; title*=UTF-8'de'letztes%20Kapitel, </TheBook/chapter4>; rel="next"; title*=UTF-8'de'n%c3%a4chstes%20Kapitel"#,
+        context: None,
+        outcome: Outcome::Links(&[
+            titled("/TheBook/chapter2", "previous", "letztes Kapitel"),
+            titled("/TheBook/chapter4", "next", "nächstes Kapitel"),
+        ]),
+    },
+    Case {
+        name: "rfc8288-5",
+        source: Source::Rfc8288,
+        input: r#"<http://example.org/>; rel="start http://example.net/relation/other""#,
+        context: None,
+        outcome: Outcome::Links(&[
+            link("http://example.org/", "start"),
+            link("http://example.org/", "http://example.net/relation/other"),
+        ]),
+    },
+    Case {
+        name: "simplecss",
+        source: Source::Greenbytes,
+        input: "<simple.css>; rel=stylesheet",
+        context: None,
+        outcome: Outcome::Links(&[link("simple.css", "stylesheet")]),
+    },
+    Case {
+        name: "simplecss-whitespace",
+        source: Source::Greenbytes,
+        input: "<simple.css> ; rel = stylesheet",
+        context: None,
+        outcome: Outcome::Links(&[link("simple.css", "stylesheet")]),
+    },
+    Case {
+        name: "simplecss-single-quotes",
+        source: Source::Greenbytes,
+        input: "<simple.css>; rel='stylesheet'",
+        context: None,
+        outcome: Outcome::Links(&[link("simple.css", "'stylesheet'")]),
+    },
+    Case {
+        name: "simplecss-title",
+        source: Source::Greenbytes,
+        input: r#"<simple.css>; rel=stylesheet; title="A simple CSS stylesheet""#,
+        context: None,
+        outcome: Outcome::Links(&[titled(
+            "simple.css",
+            "stylesheet",
+            "A simple CSS stylesheet",
+        )]),
+    },
+    Case {
+        name: "simplecss-title-comma",
+        source: Source::Greenbytes,
+        input: r#"<simple.css>; title="a, b"; rel=stylesheet"#,
+        context: None,
+        outcome: Outcome::Links(&[titled("simple.css", "stylesheet", "a, b")]),
+    },
+    Case {
+        name: "simplecss-title-5987",
+        source: Source::Greenbytes,
+        input: "<simple.css>; rel=stylesheet; title*=UTF-8''stylesheet-%E2%82%AC",
+        context: None,
+        outcome: Outcome::Links(&[titled("simple.css", "stylesheet", "stylesheet-€")]),
+    },
+    Case {
+        name: "simplecss-title-5987-no-encoding",
+        source: Source::Greenbytes,
+        input: "<simple.css>; rel=stylesheet; title*=''stylesheet",
+        context: None,
+        outcome: Outcome::Invalid,
+    },
+    Case {
+        name: "simplecss-two-relations",
+        source: Source::Greenbytes,
+        input: r#"<simple.css>; rel="foo stylesheet""#,
+        context: None,
+        outcome: Outcome::Links(&[link("simple.css", "foo"), link("simple.css", "stylesheet")]),
+    },
+    Case {
+        name: "simplecss-repeated-rel",
+        source: Source::Greenbytes,
+        input: r#"<simple.css>; rel="stylesheet"; rel="foo""#,
+        context: None,
+        outcome: Outcome::Links(&[link("simple.css", "stylesheet")]),
+    },
+    Case {
+        name: "simplecss-anchor",
+        source: Source::Greenbytes,
+        input: r#"<simple.css>; anchor="http://example.com/"; rel=stylesheet"#,
+        context: Some("http://example.org/a/"),
+        outcome: Outcome::Links(&[Expected {
+            target: "simple.css",
+            rel: Some("stylesheet"),
+            context: Some("http://example.com/"),
+            title: None,
+        }]),
+    },
+    Case {
+        name: "simplecss-after-other",
+        source: Source::Greenbytes,
+        input: "<other.css>;rel=alternate,<simple.css>;rel=stylesheet",
+        context: Some("http://example.org/a/"),
+        outcome: Outcome::Links(&[
+            Expected {
+                target: "other.css",
+                rel: Some("alternate"),
+                context: Some("http://example.org/a/"),
+                title: None,
+            },
+            Expected {
+                target: "simple.css",
+                rel: Some("stylesheet"),
+                context: Some("http://example.org/a/"),
+                title: None,
+            },
+        ]),
+    },
+    Case {
+        name: "no-angle-brackets",
+        source: Source::Greenbytes,
+        input: "simple.css; rel=stylesheet",
+        context: None,
+        outcome: Outcome::Invalid,
+    },
+    Case {
+        name: "unterminated-quoted-string",
+        source: Source::Greenbytes,
+        input: r#"<simple.css>; rel=stylesheet; title="unterminated"#,
+        context: None,
+        outcome: Outcome::Invalid,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn rejections_do_not_match_links() {
+        let case = CASES[0];
+        let header = parse(case.input, None).unwrap();
+
+        assert!(case.matches(Some(&header)));
+        assert!(!case.matches(None));
+        assert!(!CASES.last().unwrap().matches(Some(&header)));
+    }
+}
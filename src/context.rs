@@ -0,0 +1,248 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+#[cfg(feature = "std")]
+use url;
+
+/// The context of a link, i.e. the resource the link is from.
+///
+/// By default the context is the URL given to the parser (RFC8288 Section
+/// 3.2) but an "anchor" param overrides it.
+///
+/// ```
+/// use linkheader::{parse, Context};
+///
+/// let context = url::Url::parse("https://www.example.org/").ok();
+/// let header = parse("</terms>; rel=\"copyright\"; anchor=\"#foo\"", context).unwrap();
+/// let link_context = &header.links()[0].context;
+///
+/// assert_eq!(link_context.anchor(), Some("#foo"));
+/// assert_eq!(link_context.url().unwrap().as_str(), "https://www.example.org/#foo");
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Context {
+    /// There is no known context.
+    #[default]
+    Absent,
+    /// The default context given to the parser.
+    Inherited(url::Url),
+    /// The context set by an "anchor" param.
+    Anchored {
+        /// The default context, if any.
+        base: Option<url::Url>,
+        /// The "anchor" value as given.
+        anchor: String,
+        /// The anchor resolved against the base. `None` when the anchor is
+        /// relative and there is no base or the anchor is not a valid URL.
+        resolved: Option<url::Url>,
+    },
+}
+
+/// The context of a link, i.e. the resource the link is from.
+///
+/// Without the `std` feature there is no URL to resolve a context against, so
+/// the context is always absent and "anchor" params are kept as ordinary
+/// params.
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Context {
+    /// There is no known context.
+    #[default]
+    Absent,
+}
+
+#[cfg(not(feature = "std"))]
+impl Context {
+    /// The "anchor" value as given, never set without `std`.
+    pub fn anchor(&self) -> Option<&str> {
+        None
+    }
+
+    pub fn is_absent(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "std")]
+impl Context {
+    /// Creates an anchored context resolving the anchor against the base.
+    pub fn anchored(base: Option<url::Url>, anchor: impl Into<String>) -> Context {
+        let anchor = anchor.into();
+        let resolved = match &base {
+            Some(base) => base.join(&anchor).ok(),
+            None => url::Url::parse(&anchor).ok(),
+        };
+
+        Context::Anchored {
+            base,
+            anchor,
+            resolved,
+        }
+    }
+
+    /// The effective context URL.
+    pub fn url(&self) -> Option<&url::Url> {
+        match self {
+            Context::Absent => None,
+            Context::Inherited(url) => Some(url),
+            Context::Anchored { resolved, .. } => resolved.as_ref(),
+        }
+    }
+
+    /// The "anchor" value as given, if any.
+    pub fn anchor(&self) -> Option<&str> {
+        match self {
+            Context::Anchored { anchor, .. } => Some(anchor),
+            _ => None,
+        }
+    }
+
+    /// The default context regardless of any anchor.
+    pub fn base(&self) -> Option<&url::Url> {
+        match self {
+            Context::Absent => None,
+            Context::Inherited(url) => Some(url),
+            Context::Anchored { base, .. } => base.as_ref(),
+        }
+    }
+
+    pub fn is_absent(&self) -> bool {
+        matches!(self, Context::Absent)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Option<url::Url>> for Context {
+    fn from(url: Option<url::Url>) -> Context {
+        match url {
+            Some(url) => Context::Inherited(url),
+            None => Context::Absent,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<url::Url> for Context {
+    fn from(url: url::Url) -> Context {
+        Context::Inherited(url)
+    }
+}
+
+/// Infers the default context of the links in a response (RFC8288 Section
+/// 3.2), i.e. the URL of the representation they come with.
+///
+/// Follows RFC7231 Section 3.1.4.1: a `Content-Location` identifies the
+/// representation, otherwise a `201 Created` response describes the resource
+/// given in `Location`, otherwise the representation is assumed to be of the
+/// request URL. Relative values are resolved against the request URL and
+/// invalid ones are ignored.
+///
+/// The inferred URL is the link context only. The request URL stays the base
+/// URI relative targets resolve against: `Content-Location` no longer sets it
+/// (RFC7231 Appendix B).
+///
+/// ```
+/// use linkheader::context::infer;
+/// use linkheader::parse;
+///
+/// let request_url = url::Url::parse("https://example.org/books/1").unwrap();
+/// let context = infer(&request_url, 200, Some("/books/1.json"), None);
+/// let header = parse(r#"<?v=2>; rel="latest-version""#, Some(context.clone())).unwrap();
+/// let link = &header.links()[0];
+/// let target = link.resolve_target(Some(&request_url)).unwrap();
+///
+/// assert_eq!(link.context.url(), Some(&context));
+/// assert_eq!(target.as_str(), "https://example.org/books/1?v=2");
+///
+/// let created = infer(&request_url, 201, None, Some("/books/2"));
+///
+/// assert_eq!(created.as_str(), "https://example.org/books/2");
+/// ```
+#[cfg(feature = "std")]
+pub fn infer(
+    request_url: &url::Url,
+    status: u16,
+    content_location: Option<&str>,
+    location: Option<&str>,
+) -> url::Url {
+    let resolve = |value: Option<&str>| value.and_then(|value| request_url.join(value.trim()).ok());
+
+    resolve(content_location)
+        .or_else(|| {
+            if status == 201 {
+                resolve(location)
+            } else {
+                None
+            }
+        })
+        .unwrap_or_else(|| request_url.clone())
+}
+
+/// The serialised form of a context: the default context URL, the "anchor"
+/// as given and the URL it resolves to, if any.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "Context"))]
+struct ContextRepr {
+    base: Option<String>,
+    anchor: Option<String>,
+    resolved: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Context {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let resolved = match self {
+            Context::Anchored { resolved, .. } => resolved.as_ref(),
+            _ => None,
+        };
+
+        ContextRepr {
+            base: self.base().map(|url| url.to_string()),
+            anchor: self.anchor().map(|anchor| anchor.to_string()),
+            resolved: resolved.map(|url| url.to_string()),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Context {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Context, D::Error> {
+        use serde::de::Error;
+
+        let repr = ContextRepr::deserialize(deserializer)?;
+        let parse = |input: Option<String>| {
+            input
+                .map(|input| url::Url::parse(&input).map_err(D::Error::custom))
+                .transpose()
+        };
+        let base = parse(repr.base)?;
+
+        Ok(match repr.anchor {
+            Some(anchor) => Context::Anchored {
+                base,
+                anchor,
+                resolved: parse(repr.resolved)?,
+            },
+            None => base.into(),
+        })
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Context {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        ContextRepr::schema_name()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        ContextRepr::json_schema(generator)
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Deprecation notices: `rel="deprecation"` (RFC9745), `rel="sunset"`
+//! (RFC8594) and `rel="successor-version"` (RFC5829) links.
+
+use crate::header::Header;
+use crate::link::Link;
+
+impl Header {
+    /// The first `rel="deprecation"` link, i.e. the documentation of the
+    /// deprecation of the resource.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<https://developer.example.com/deprecation>; rel="deprecation"; type="text/html", <https://developer.example.com/sunset>; rel="sunset", </v2/friends>; rel="successor-version""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert!(header.has_deprecation_notice());
+    /// assert_eq!(header.deprecation().unwrap().target.as_str(), "https://developer.example.com/deprecation");
+    /// assert_eq!(header.sunset().unwrap().target.as_str(), "https://developer.example.com/sunset");
+    /// assert_eq!(header.successor_version().unwrap().target.as_str(), "/v2/friends");
+    /// ```
+    pub fn deprecation(&self) -> Option<&Link> {
+        self.first_by_relation("deprecation")
+    }
+
+    /// The first `rel="sunset"` link, i.e. the documentation of the planned
+    /// removal of the resource.
+    pub fn sunset(&self) -> Option<&Link> {
+        self.first_by_relation("sunset")
+    }
+
+    /// The first `rel="successor-version"` link, i.e. the version replacing
+    /// the resource.
+    pub fn successor_version(&self) -> Option<&Link> {
+        self.first_by_relation("successor-version")
+    }
+
+    /// Whether the header has a `rel="deprecation"` or `rel="sunset"` link.
+    pub fn has_deprecation_notice(&self) -> bool {
+        self.deprecation().is_some() || self.sunset().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse;
+
+    #[test]
+    fn successor_alone_is_no_notice() {
+        let header = parse(r#"</v2>; rel="successor-version""#, None).unwrap();
+
+        assert!(!header.has_deprecation_notice());
+        assert!(header.successor_version().is_some());
+        assert!(parse(r#"</s>; rel="Sunset""#, None)
+            .unwrap()
+            .has_deprecation_notice());
+    }
+}
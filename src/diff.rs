@@ -0,0 +1,169 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Structured differences between headers.
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::param::Param;
+
+/// The changes needed to go from one header to another.
+///
+/// Links are paired by relation type, context and equivalent target (see
+/// `Header::dedup`); paired links with different attributes are reported as
+/// modified.
+///
+/// ```
+/// use linkheader::parse;
+///
+/// let hints = parse(r#"</style.css>; rel="preload"; as="style", </font.woff2>; rel="preload"; as="font""#, None).unwrap();
+/// let response = parse(r#"</style.css>; rel="preload"; as="style"; nopush, </app.js>; rel="preload"; as="script""#, None).unwrap();
+/// let diff = hints.diff(&response);
+///
+/// assert_eq!(diff.added[0].target.as_str(), "/app.js");
+/// assert_eq!(diff.removed[0].target.as_str(), "/font.woff2");
+/// assert_eq!(diff.modified[0].changes[0].name, "nopush");
+/// assert!(diff.modified[0].changes[0].is_added());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    /// Links only in the new header.
+    pub added: Vec<Link>,
+    /// Links only in the old header.
+    pub removed: Vec<Link>,
+    /// Links in both headers with different attributes.
+    pub modified: Vec<Modification>,
+}
+
+/// A link present in both headers with different attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Modification {
+    pub old: Link,
+    pub new: Link,
+    /// The changes by param name in order of first appearance.
+    pub changes: Vec<ParamChange>,
+}
+
+/// The change of every param with a given name, including the "title",
+/// "hreflang", "media" and "type" attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamChange {
+    /// The lowercased param name.
+    pub name: String,
+    pub old: Vec<Param>,
+    pub new: Vec<Param>,
+}
+
+impl ParamChange {
+    pub fn is_added(&self) -> bool {
+        self.old.is_empty()
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.new.is_empty()
+    }
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+impl Header {
+    /// Computes the changes from `self` to `other`.
+    pub fn diff(&self, other: &Header) -> Diff {
+        let mut diff = Diff::default();
+        let mut unmatched: Vec<Option<&Link>> = self.links().iter().map(Some).collect();
+
+        for new in other {
+            let key = new.equivalence_key();
+            let found = unmatched
+                .iter_mut()
+                .find(|old| old.is_some_and(|old| old.equivalence_key() == key))
+                .and_then(Option::take);
+
+            match found {
+                Some(old) => {
+                    let changes = param_changes(old, new);
+
+                    if !changes.is_empty() {
+                        diff.modified.push(Modification {
+                            old: old.clone(),
+                            new: new.clone(),
+                            changes,
+                        });
+                    }
+                }
+                None => diff.added.push(new.clone()),
+            }
+        }
+
+        diff.removed = unmatched.into_iter().flatten().cloned().collect();
+
+        diff
+    }
+}
+
+fn param_changes(old: &Link, new: &Link) -> Vec<ParamChange> {
+    let old = old.attributes();
+    let new = new.attributes();
+    let mut names: Vec<String> = vec![];
+
+    for param in old.iter().chain(new.iter()) {
+        let name = param.name().to_lowercase();
+
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let select = |params: &[Param]| -> Vec<Param> {
+                params
+                    .iter()
+                    .filter(|param| param.name().eq_ignore_ascii_case(&name))
+                    .cloned()
+                    .collect()
+            };
+            let old = select(&old);
+            let new = select(&new);
+
+            if old == new {
+                None
+            } else {
+                Some(ParamChange { name, old, new })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse;
+
+    #[test]
+    fn param_level_changes() {
+        let old = parse(r#"</a>; rel="alternate"; hreflang="de"; title="A""#, None).unwrap();
+        let new = parse(
+            r#"</a>; rel="alternate"; hreflang="de"; hreflang="de-CH""#,
+            None,
+        )
+        .unwrap();
+        let diff = old.diff(&new);
+        let changes = &diff.modified[0].changes;
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].name, "title");
+        assert!(changes[0].is_removed());
+        assert_eq!(changes[1].name, "hreflang");
+        assert_eq!(changes[1].old.len(), 1);
+        assert_eq!(changes[1].new.len(), 2);
+        assert!(old.diff(&old).is_empty());
+    }
+}
@@ -5,18 +5,154 @@
 // according to those terms.
 
 //! Error types and utilities.
+//!
+//! Errors are `failure` errors. Without the `std` feature they are an
+//! `Error` holding the message instead.
+
+// The `Fail` derive expands to impls nested in anonymous constants.
+#![allow(non_local_definitions)]
 
 use crate::parser::Rule;
+use crate::uri::UriRef;
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
 pub use failure::Error;
+#[cfg(feature = "std")]
 use failure::*;
 
-/// Either `Ok(T)` or `Err(failure::Error)`.
-pub type Result<T> = ::std::result::Result<T, failure::Error>;
+/// Either `Ok(T)` or `Err(Error)`.
+pub type Result<T> = ::core::result::Result<T, Error>;
+
+/// A failure without `std`, i.e. its message.
+#[cfg(not(feature = "std"))]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Error(String);
+
+#[cfg(not(feature = "std"))]
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for Error {}
+
+#[cfg(not(feature = "std"))]
+impl From<ParserError> for Error {
+    fn from(err: ParserError) -> Error {
+        Error(err.to_string())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl From<AmbiguousRelation> for Error {
+    fn from(err: AmbiguousRelation) -> Error {
+        Error(err.to_string())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl From<pest::error::Error<Rule>> for Error {
+    fn from(err: pest::error::Error<Rule>) -> Error {
+        Error(err.to_string())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl From<core::str::Utf8Error> for Error {
+    fn from(err: core::str::Utf8Error) -> Error {
+        Error(err.to_string())
+    }
+}
 
 /// A parser error.
-#[derive(Clone, Eq, PartialEq, Debug, Fail)]
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(Fail))]
 pub enum ParserError {
     /// Given invalid `Rule` variant to a parser collector function.
-    #[fail(display = "Expected a rule of type {} but given {} instead", _0, _1)]
     InvalidRule(Rule, Rule),
+
+    /// Given a target that is not a valid URI reference in strict mode.
+    InvalidTarget(String),
+
+    /// Given a target with a scheme not in the allowed list.
+    DisallowedScheme(String),
+
+    /// Given a host that is not a valid internationalized domain name.
+    InvalidHost(String),
+
+    /// Given a malformed or invalid language tag (RFC5646).
+    InvalidLanguageTag(String),
+
+    /// Given a linkset document (RFC9264) that does not follow the expected
+    /// structure.
+    InvalidLinkset(String),
+
+    /// Given an HTTP Archive (HAR) document that does not follow the expected
+    /// structure.
+    InvalidHar(String),
+
+    /// Given a HAL `_links` object that does not follow the expected
+    /// structure.
+    InvalidHal(String),
+
+    /// Given a WebFinger JRD document (RFC7033) that does not follow the
+    /// expected structure.
+    InvalidJrd(String),
+
+    /// Given a Structured Field Value (RFC9651), e.g. a `Link-Template`
+    /// field, that does not follow the expected syntax or structure.
+    InvalidStructuredField(String),
+}
+
+impl fmt::Display for ParserError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParserError::InvalidRule(expected, given) => write!(
+                formatter,
+                "Expected a rule of type {} but given {} instead",
+                expected, given
+            ),
+            ParserError::InvalidTarget(target) => write!(formatter, "Invalid target {:?}", target),
+            ParserError::DisallowedScheme(target) => {
+                write!(formatter, "Target {:?} has a disallowed scheme", target)
+            }
+            ParserError::InvalidHost(host) => write!(formatter, "Invalid host {:?}", host),
+            ParserError::InvalidLanguageTag(tag) => {
+                write!(formatter, "Invalid language tag {:?}", tag)
+            }
+            ParserError::InvalidLinkset(reason) => write!(formatter, "Invalid linkset: {}", reason),
+            ParserError::InvalidHar(reason) => write!(formatter, "Invalid HAR: {}", reason),
+            ParserError::InvalidHal(reason) => write!(formatter, "Invalid HAL links: {}", reason),
+            ParserError::InvalidJrd(reason) => write!(formatter, "Invalid JRD: {}", reason),
+            ParserError::InvalidStructuredField(reason) => {
+                write!(formatter, "Invalid structured field: {}", reason)
+            }
+        }
+    }
+}
+
+/// More than one distinct link share a relation type expected to be unique.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(Fail))]
+pub struct AmbiguousRelation {
+    /// The relation type.
+    pub relation: String,
+    /// The conflicting targets in order of appearance.
+    pub targets: Vec<UriRef>,
+}
+
+impl fmt::Display for AmbiguousRelation {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Relation {:?} has more than one distinct link",
+            self.relation
+        )
+    }
 }
@@ -0,0 +1,97 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Feed discovery over `rel="alternate"` links, from the `Link` header or
+//! HTML `<link>` elements (see `html::parse`).
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::query::Query;
+
+/// A syndication feed format, in order of preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Format {
+    /// `application/atom+xml` (RFC4287).
+    Atom,
+    /// `application/feed+json` ([JSON Feed](https://www.jsonfeed.org/)).
+    Json,
+    /// `application/rss+xml`.
+    Rss,
+}
+
+impl Format {
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            Format::Atom => "application/atom+xml",
+            Format::Json => "application/feed+json",
+            Format::Rss => "application/rss+xml",
+        }
+    }
+}
+
+impl Link {
+    /// The feed format of a `rel="alternate"` link, if its "type" is a feed
+    /// media type.
+    pub fn feed_format(&self) -> Option<Format> {
+        [Format::Atom, Format::Json, Format::Rss]
+            .iter()
+            .find(|format| {
+                Query::new()
+                    .rel("alternate")
+                    .media_type(format.media_type())
+                    .matches(self)
+            })
+            .copied()
+    }
+}
+
+impl Header {
+    /// The feeds, preferring Atom, then JSON Feed and then RSS. Feeds of the
+    /// same format keep their order.
+    ///
+    /// ```
+    /// use linkheader::feed::Format;
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</feed.rss>; rel="alternate"; type="application/rss+xml", </index.de.html>; rel="alternate"; hreflang="de", </feed.json>; rel="alternate"; type="application/feed+json", </feed.atom>; rel="alternate"; type="application/atom+xml"; title="Posts""#;
+    /// let header = parse(input, None).unwrap();
+    /// let feeds = header.feeds();
+    ///
+    /// assert_eq!(feeds.len(), 3);
+    /// assert_eq!(feeds[0].target.as_str(), "/feed.atom");
+    /// assert_eq!(feeds[2].feed_format(), Some(Format::Rss));
+    /// ```
+    pub fn feeds(&self) -> Vec<&Link> {
+        let mut feeds: Vec<(Format, &Link)> = self
+            .iter()
+            .filter_map(|link| Some((link.feed_format()?, link)))
+            .collect();
+
+        feeds.sort_by_key(|(format, _)| *format);
+        feeds.into_iter().map(|(_, link)| link).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse;
+
+    #[test]
+    fn stable_order_within_format() {
+        let input = r#"</b.rss>; rel="alternate"; type="application/rss+xml", </comments.atom>; rel="alternate"; type="application/atom+xml", </a.rss>; rel="alternate"; type="application/rss+xml; charset=utf-8", </posts.atom>; rel="alternate"; type="Application/Atom+XML""#;
+        let header = parse(input, None).unwrap();
+        let targets: Vec<_> = header
+            .feeds()
+            .iter()
+            .map(|link| link.target.as_str())
+            .collect();
+
+        assert_eq!(
+            targets,
+            vec!["/comments.atom", "/posts.atom", "/b.rss", "/a.rss"]
+        );
+    }
+}
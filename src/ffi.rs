@@ -0,0 +1,327 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A C API over the parser, declared in `include/linkheader.h`.
+//!
+//! A parsed header is an opaque handle owned by the caller and released with
+//! `linkheader_free`. Links are borrowed from their header. Every returned
+//! string is owned by the caller and released with `linkheader_string_free`.
+//! Functions return `NULL` when there is nothing to return, e.g. a link with
+//! no relation type, or on failure, in which case `linkheader_last_error`
+//! explains why. Panics never cross the boundary: they are failures too.
+//!
+//! The library is a C shared library when built as a `cdylib`:
+//!
+//! ```sh
+//! cargo rustc --lib --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! ```c
+//! LinkHeader *header = linkheader_parse("</2>; rel=\"next\"", "https://example.org/");
+//!
+//! for (size_t i = 0; i < linkheader_len(header); i++) {
+//!     const Link *link = linkheader_get(header, i);
+//!     char *target = linkheader_link_target(link);
+//!     puts(target);
+//!     linkheader_string_free(target);
+//! }
+//!
+//! linkheader_free(header);
+//! ```
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::parser;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(into_c_string(message)));
+}
+
+/// Runs the body of an exported function, returning `fallback` and setting
+/// the last error if it panics.
+fn guard<T>(fallback: T, body: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".into());
+
+        set_last_error(format!("Panicked: {}", message));
+        fallback
+    })
+}
+
+/// Converts to a C string, dropping any interior NUL.
+fn into_c_string(text: String) -> CString {
+    CString::new(text.replace('\0', "")).expect("NULs removed")
+}
+
+fn into_raw(text: Option<String>) -> *mut c_char {
+    text.map_or(ptr::null_mut(), |text| into_c_string(text).into_raw())
+}
+
+/// Reads a UTF-8 C string. `NULL` is `None`.
+unsafe fn read(input: *const c_char, name: &str) -> Result<Option<&str>, String> {
+    if input.is_null() {
+        return Ok(None);
+    }
+
+    CStr::from_ptr(input)
+        .to_str()
+        .map(Some)
+        .map_err(|_| format!("{} is not valid UTF-8", name))
+}
+
+/// Parses a `Link` header field value with an optional context URL.
+///
+/// Returns `NULL` on failure.
+///
+/// # Safety
+///
+/// `input` must be a NUL-terminated string and `context` either `NULL` or a
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn linkheader_parse(
+    input: *const c_char,
+    context: *const c_char,
+) -> *mut Header {
+    guard(ptr::null_mut(), || {
+        let result = read(input, "input").and_then(|input| {
+            let input = input.ok_or_else(|| "input is NULL".to_string())?;
+            let context = match read(context, "context")? {
+                Some(context) => Some(
+                    url::Url::parse(context).map_err(|err| format!("Invalid context: {}", err))?,
+                ),
+                None => None,
+            };
+
+            parser::parse(input, context).map_err(|err| err.to_string())
+        });
+
+        match result {
+            Ok(header) => Box::into_raw(Box::new(header)),
+            Err(message) => {
+                set_last_error(message);
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// The message of the last failure in the current thread, if any.
+#[no_mangle]
+pub extern "C" fn linkheader_last_error() -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        LAST_ERROR.with(|last| {
+            into_raw(
+                last.borrow()
+                    .as_ref()
+                    .map(|message| message.to_string_lossy().into_owned()),
+            )
+        })
+    })
+}
+
+/// Releases a header. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `header` must come from `linkheader_parse` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn linkheader_free(header: *mut Header) {
+    guard((), || {
+        if !header.is_null() {
+            drop(Box::from_raw(header));
+        }
+    })
+}
+
+/// Releases a string returned by this API. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `text` must come from this API and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn linkheader_string_free(text: *mut c_char) {
+    guard((), || {
+        if !text.is_null() {
+            drop(CString::from_raw(text));
+        }
+    })
+}
+
+/// The number of links in the header.
+///
+/// # Safety
+///
+/// `header` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn linkheader_len(header: *const Header) -> usize {
+    guard(0, || header.as_ref().map_or(0, Header::len))
+}
+
+/// The link at the given position, borrowed from the header.
+///
+/// # Safety
+///
+/// `header` must be a live handle. The link is valid as long as the header.
+#[no_mangle]
+pub unsafe extern "C" fn linkheader_get(header: *const Header, index: usize) -> *const Link {
+    guard(ptr::null(), || {
+        header
+            .as_ref()
+            .and_then(|header| header.links().get(index))
+            .map_or(ptr::null(), |link| link as *const Link)
+    })
+}
+
+/// Serialises the header as a `Link` header field value.
+///
+/// # Safety
+///
+/// `header` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn linkheader_to_string(header: *const Header) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        into_raw(header.as_ref().map(Header::to_string))
+    })
+}
+
+/// The target as given.
+///
+/// # Safety
+///
+/// `link` must come from `linkheader_get` on a live header.
+#[no_mangle]
+pub unsafe extern "C" fn linkheader_link_target(link: *const Link) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        into_raw(link.as_ref().map(|link| link.target.to_string()))
+    })
+}
+
+/// The relation type, if any.
+///
+/// # Safety
+///
+/// `link` must come from `linkheader_get` on a live header.
+#[no_mangle]
+pub unsafe extern "C" fn linkheader_link_rel(link: *const Link) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        into_raw(
+            link.as_ref()
+                .and_then(|link| link.relation.as_ref())
+                .map(|relation| relation.as_str().into()),
+        )
+    })
+}
+
+/// The effective context URL, if known.
+///
+/// # Safety
+///
+/// `link` must come from `linkheader_get` on a live header.
+#[no_mangle]
+pub unsafe extern "C" fn linkheader_link_context(link: *const Link) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        into_raw(
+            link.as_ref()
+                .and_then(|link| link.context.url())
+                .map(|url| url.to_string()),
+        )
+    })
+}
+
+/// The text of the first value of the given attribute or param, e.g.
+/// "title" or "type".
+///
+/// # Safety
+///
+/// `link` must come from `linkheader_get` on a live header and `name` must be
+/// a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn linkheader_link_param(
+    link: *const Link,
+    name: *const c_char,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let name = match read(name, "name") {
+            Ok(Some(name)) => name,
+            Ok(None) => return ptr::null_mut(),
+            Err(message) => {
+                set_last_error(message);
+                return ptr::null_mut();
+            }
+        };
+
+        into_raw(link.as_ref().and_then(|link| {
+            link.param_values(name)
+                .first()
+                .map(|value| value.text().to_string())
+        }))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(text: *mut c_char) -> String {
+        let result = unsafe { CStr::from_ptr(text) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { linkheader_string_free(text) };
+        result
+    }
+
+    #[test]
+    fn parse_and_read() {
+        let input = CString::new(r#"</2>; rel="next"; title="Two", </1>; rel="prev""#).unwrap();
+        let context = CString::new("https://example.org/").unwrap();
+        let title = CString::new("Title").unwrap();
+        let kind = CString::new("type").unwrap();
+
+        unsafe {
+            let header = linkheader_parse(input.as_ptr(), context.as_ptr());
+            let link = linkheader_get(header, 0);
+
+            assert_eq!(linkheader_len(header), 2);
+            assert_eq!(read(linkheader_link_target(link)), "/2");
+            assert_eq!(read(linkheader_link_rel(link)), "next");
+            assert_eq!(read(linkheader_link_context(link)), "https://example.org/");
+            assert_eq!(read(linkheader_link_param(link, title.as_ptr())), "Two");
+            assert!(linkheader_link_param(link, kind.as_ptr()).is_null());
+            assert!(linkheader_get(header, 2).is_null());
+
+            linkheader_free(header);
+        }
+    }
+
+    #[test]
+    fn panics_are_failures() {
+        assert_eq!(guard(7, || panic!("boom")), 7);
+        assert_eq!(read(linkheader_last_error()), "Panicked: boom");
+        assert_eq!(guard(7, || 1), 1);
+    }
+
+    #[test]
+    fn last_error() {
+        let input = CString::new("<").unwrap();
+
+        assert!(unsafe { linkheader_parse(input.as_ptr(), ptr::null()) }.is_null());
+        assert!(!read(linkheader_last_error()).is_empty());
+    }
+}
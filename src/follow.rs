@@ -0,0 +1,226 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Following `rel="next"` links across paginated responses.
+
+use crate::error::Result;
+use crate::header::Header;
+use futures_util::stream::{self, Stream};
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
+
+/// The default maximum number of pages to yield.
+pub const MAX_PAGES: usize = 100;
+
+/// A page in a paginated collection.
+pub trait Page {
+    /// The URL of the page, if known. Used to detect loops.
+    fn url(&self) -> Option<url::Url> {
+        None
+    }
+
+    /// The absolute URL of the next page, if any.
+    fn next_url(&self) -> Result<Option<url::Url>>;
+}
+
+/// A header is a page on its own: the next page is its first `rel="next"`
+/// link resolved against the link context.
+impl Page for Header {
+    fn url(&self) -> Option<url::Url> {
+        self.links()
+            .iter()
+            .find_map(|link| link.context.base().cloned())
+    }
+
+    fn next_url(&self) -> Result<Option<url::Url>> {
+        self.next()
+            .map(|link| link.resolve_target(None))
+            .transpose()
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Page for ::reqwest::Response {
+    fn url(&self) -> Option<url::Url> {
+        url::Url::parse(self.url().as_str()).ok()
+    }
+
+    fn next_url(&self) -> Result<Option<url::Url>> {
+        use crate::reqwest::ResponseExt;
+
+        match self.link_header()? {
+            Some(header) => header.next_url(),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Why following stopped short.
+#[derive(Debug)]
+pub enum FollowError<E> {
+    /// The fetch function failed.
+    Fetch(E),
+    /// The `Link` header of a page could not be read.
+    Header(failure::Error),
+    /// A "next" link pointed to an already visited page.
+    Loop(url::Url),
+}
+
+impl<E: fmt::Display> fmt::Display for FollowError<E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FollowError::Fetch(err) => write!(formatter, "Failed to fetch page: {}", err),
+            FollowError::Header(err) => write!(formatter, "Invalid Link header: {}", err),
+            FollowError::Loop(url) => write!(formatter, "Pagination loop at {}", url),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for FollowError<E> {}
+
+enum Next<R, E> {
+    Page(R),
+    Url(url::Url),
+    Failed(FollowError<E>),
+    Done,
+}
+
+struct State<R, F, E> {
+    next: Next<R, E>,
+    fetch: F,
+    visited: HashSet<url::Url>,
+    pages: usize,
+    max_pages: usize,
+}
+
+/// Yields the given page and every page reached by following `rel="next"`
+/// links with the fetch function, up to `MAX_PAGES`.
+///
+/// The stream ends after the last page, after `max_pages` pages or after
+/// yielding an error, e.g. when a "next" link points to a visited page.
+///
+/// ```
+/// use futures_util::StreamExt;
+/// use linkheader::{follow, parse, Header};
+///
+/// async fn fetch(url: url::Url) -> Result<Header, failure::Error> {
+///     let page: u32 = url.query().unwrap()[5..].parse()?;
+///     let input = if page < 3 { format!("<?page={}>; rel=\"next\"", page + 1) } else { "</>; rel=\"first\"".into() };
+///
+///     parse(&input, Some(url))
+/// }
+///
+/// let first = parse(r#"<?page=2>; rel="next""#, url::Url::parse("https://example.org/items?page=1").ok()).unwrap();
+/// let pages: Vec<_> = futures_executor::block_on(follow::follow(first, fetch).collect());
+///
+/// assert_eq!(pages.len(), 3);
+/// assert!(pages.iter().all(|page| page.is_ok()));
+/// ```
+pub fn follow<R, F, Fut, E>(
+    first: R,
+    fetch: F,
+) -> impl Stream<Item = std::result::Result<R, FollowError<E>>>
+where
+    R: Page,
+    F: FnMut(url::Url) -> Fut,
+    Fut: Future<Output = std::result::Result<R, E>>,
+{
+    follow_with_limit(first, fetch, MAX_PAGES)
+}
+
+/// Same as `follow` with the given maximum number of pages.
+pub fn follow_with_limit<R, F, Fut, E>(
+    first: R,
+    fetch: F,
+    max_pages: usize,
+) -> impl Stream<Item = std::result::Result<R, FollowError<E>>>
+where
+    R: Page,
+    F: FnMut(url::Url) -> Fut,
+    Fut: Future<Output = std::result::Result<R, E>>,
+{
+    let state = State {
+        next: Next::Page(first),
+        fetch,
+        visited: HashSet::new(),
+        pages: 0,
+        max_pages,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        let page = match std::mem::replace(&mut state.next, Next::Done) {
+            Next::Done => return None,
+            Next::Failed(err) => return Some((Err(err), state)),
+            Next::Page(page) => page,
+            Next::Url(url) => match (state.fetch)(url).await {
+                Ok(page) => page,
+                Err(err) => return Some((Err(FollowError::Fetch(err)), state)),
+            },
+        };
+
+        state.pages += 1;
+
+        if let Some(url) = page.url() {
+            state.visited.insert(url);
+        }
+
+        if state.pages < state.max_pages {
+            state.next = match page.next_url() {
+                Ok(Some(url)) if state.visited.contains(&url) => {
+                    Next::Failed(FollowError::Loop(url))
+                }
+                Ok(Some(url)) => {
+                    state.visited.insert(url.clone());
+                    Next::Url(url)
+                }
+                Ok(None) => Next::Done,
+                Err(err) => Next::Failed(FollowError::Header(err)),
+            };
+        }
+
+        Some((Ok(page), state))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use futures_util::StreamExt;
+
+    fn page(url: &str, next: &str) -> Header {
+        parse(
+            &format!("<{}>; rel=\"next\"", next),
+            url::Url::parse(url).ok(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn detect_loops() {
+        let first = page("https://example.org/1", "/2");
+        let fetch = |url: url::Url| async move { Ok::<_, ()>(page(url.as_str(), "/1")) };
+        let pages: Vec<_> = futures_executor::block_on(follow(first, fetch).collect());
+
+        assert_eq!(pages.len(), 3);
+        assert!(matches!(&pages[2], Err(FollowError::Loop(url)) if url.path() == "/1"));
+    }
+
+    #[test]
+    fn max_pages() {
+        let first = page("https://example.org/0", "/1");
+        let fetch = |url: url::Url| async move {
+            let idx: u32 = url.path()[1..].parse().unwrap();
+
+            Ok::<_, ()>(page(url.as_str(), &format!("/{}", idx + 1)))
+        };
+        let pages: Vec<_> =
+            futures_executor::block_on(follow_with_limit(first, fetch, 5).collect());
+
+        assert_eq!(pages.len(), 5);
+    }
+}
@@ -0,0 +1,322 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Conversions from and to HAL `_links` objects
+//! ([draft-kelly-json-hal](https://tools.ietf.org/html/draft-kelly-json-hal)).
+
+use crate::error::{ParserError, Result};
+use crate::header::Header;
+use crate::link::Link;
+use crate::param::{Param, Value};
+use crate::parser::LinkBuilder;
+use serde_json::{json, Map, Value as Json};
+
+/// A compact URI for relation types, e.g. `ea` for
+/// `http://example.com/docs/rels/{rel}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Curie {
+    pub name: String,
+    /// A URI template with a `{rel}` variable.
+    pub href: String,
+}
+
+impl Curie {
+    pub fn new(name: impl Into<String>, href: impl Into<String>) -> Curie {
+        Curie {
+            name: name.into(),
+            href: href.into(),
+        }
+    }
+
+    /// Expands a `name:reference` relation type, if it uses this curie.
+    fn expand(&self, relation: &str) -> Option<String> {
+        let reference = relation.strip_prefix(&self.name)?.strip_prefix(':')?;
+
+        Some(self.href.replace("{rel}", reference))
+    }
+
+    /// Compacts a relation type URI, if it matches this curie.
+    fn compact(&self, relation: &str) -> Option<String> {
+        let (prefix, suffix) = self.href.split_once("{rel}")?;
+        let reference = relation.strip_prefix(prefix)?.strip_suffix(suffix)?;
+
+        if reference.is_empty() {
+            None
+        } else {
+            Some(format!("{}:{}", self.name, reference))
+        }
+    }
+}
+
+/// Reads the `_links` object of a HAL resource.
+///
+/// Relation types using a curie are expanded to their URI. "title",
+/// "hreflang" and "type" map to their target attributes and any other
+/// property, e.g. "name", "profile" or "deprecation", is kept as a param.
+/// `"templated": true` is kept as a valueless "templated" param as the
+/// target is a URI template.
+///
+/// ```
+/// use linkheader::hal;
+///
+/// let input = r#"{
+///   "_links": {
+///     "self": {"href": "/orders"},
+///     "curies": [{"name": "ea", "href": "http://example.com/docs/rels/{rel}", "templated": true}],
+///     "next": {"href": "/orders?page=2"},
+///     "ea:admin": [{"href": "/admins/2", "title": "Fred"}, {"href": "/admins/5", "title": "Kate"}],
+///     "ea:find": {"href": "/orders{?id}", "templated": true}
+///   },
+///   "total": 14
+/// }"#;
+/// let context = url::Url::parse("https://example.com/").ok();
+/// let header = hal::parse(input, context).unwrap();
+///
+/// assert_eq!(header.len(), 5);
+/// assert_eq!(header.by_relation("http://example.com/docs/rels/admin").len(), 2);
+/// assert!(header.by_relation("http://example.com/docs/rels/find")[0].param("templated").is_some());
+/// ```
+pub fn parse(input: &str, context: Option<url::Url>) -> Result<Header> {
+    let document: Json = serde_json::from_str(input)?;
+    let links = document
+        .get("_links")
+        .ok_or_else(|| invalid("expected a \"_links\" object"))?;
+
+    from_links(links, context)
+}
+
+/// Reads a HAL `_links` object. See `parse`.
+pub fn from_links(links: &Json, context: Option<url::Url>) -> Result<Header> {
+    let links = links
+        .as_object()
+        .ok_or_else(|| invalid("expected \"_links\" to be an object"))?;
+    let curies = match links.get("curies") {
+        Some(curies) => link_objects(curies)?
+            .into_iter()
+            .map(|curie| {
+                let name = curie.get("name").and_then(Json::as_str);
+                let href = curie.get("href").and_then(Json::as_str);
+
+                name.zip(href)
+                    .map(|(name, href)| Curie::new(name, href))
+                    .ok_or_else(|| invalid("expected a curie with \"name\" and \"href\" strings"))
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => vec![],
+    };
+    let mut result = vec![];
+    let mut group = 0;
+
+    for (relation, objects) in links.iter().filter(|(name, _)| *name != "curies") {
+        let relation = curies
+            .iter()
+            .find_map(|curie| curie.expand(relation))
+            .unwrap_or_else(|| relation.clone());
+
+        for object in link_objects(objects)? {
+            let mut builder = LinkBuilder::new(context.clone());
+            builder.set_group(group);
+            builder.set_rel(Value::Simple(relation.clone()));
+            collect_link(&mut builder, object)?;
+            result.extend(builder.build());
+            group += 1;
+        }
+    }
+
+    Ok(Header::from(result))
+}
+
+/// A single link object or an array of them.
+fn link_objects(value: &Json) -> Result<Vec<&Map<String, Json>>> {
+    let objects = match value {
+        Json::Array(items) => items.iter().collect(),
+        item => vec![item],
+    };
+
+    objects
+        .into_iter()
+        .map(|object| {
+            object
+                .as_object()
+                .ok_or_else(|| invalid("expected a link object"))
+        })
+        .collect()
+}
+
+fn collect_link(builder: &mut LinkBuilder, object: &Map<String, Json>) -> Result<()> {
+    let href = object
+        .get("href")
+        .and_then(Json::as_str)
+        .ok_or_else(|| invalid("expected a link object with an \"href\" string"))?;
+
+    builder.set_target(href);
+
+    for (name, value) in object.iter().filter(|(name, _)| *name != "href") {
+        let text = match value {
+            Json::String(text) => Value::Simple(text.clone()),
+            Json::Bool(true) if name == "templated" => {
+                builder.add_param(Param::new(name.clone(), None));
+                continue;
+            }
+            Json::Bool(false) => continue,
+            _ => {
+                return Err(invalid(&format!(
+                    "expected property {:?} to be a string",
+                    name
+                )))
+            }
+        };
+
+        match &name[..] {
+            "title" => builder.set_title(text),
+            "hreflang" => builder.set_lang(text),
+            "type" => builder.set_type(text),
+            _ => builder.add_param(Param::new(name.clone(), Some(text))),
+        }
+    }
+
+    Ok(())
+}
+
+fn invalid(message: &str) -> failure::Error {
+    ParserError::InvalidHal(message.into()).into()
+}
+
+impl Header {
+    /// Converts the header into a HAL `_links` object.
+    ///
+    /// Relation types matching one of the given curies are compacted and the
+    /// curies are listed under "curies". A relation with one link is written
+    /// as an object and one with several as an array. Links with no relation
+    /// type or with an "anchor" are left out as HAL links are always from the
+    /// resource.
+    ///
+    /// ```
+    /// use linkheader::hal::Curie;
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</orders?page=2>; rel="next", </admins/2>; rel="http://example.com/docs/rels/admin"; title="Fred""#;
+    /// let header = parse(input, None).unwrap();
+    /// let curies = vec![Curie::new("ea", "http://example.com/docs/rels/{rel}")];
+    /// let links = header.to_hal_links(&curies);
+    ///
+    /// assert_eq!(links["next"]["href"], "/orders?page=2");
+    /// assert_eq!(links["ea:admin"]["title"], "Fred");
+    /// assert_eq!(links["curies"][0]["templated"], true);
+    /// ```
+    pub fn to_hal_links(&self, curies: &[Curie]) -> Json {
+        let mut object = Map::new();
+        let mut used = vec![];
+
+        for link in self.iter().filter(|link| link.context.anchor().is_none()) {
+            let relation = match &link.relation {
+                Some(relation) => relation.as_str(),
+                None => continue,
+            };
+            let compacted = curies
+                .iter()
+                .find_map(|curie| curie.compact(relation).map(|compact| (compact, curie)));
+            let relation = match compacted {
+                Some((compact, curie)) => {
+                    if !used.contains(&curie) {
+                        used.push(curie);
+                    }
+
+                    compact
+                }
+                None => relation.to_string(),
+            };
+
+            let item = link.to_hal_link();
+
+            match object.get_mut(&relation) {
+                Some(Json::Array(items)) => items.push(item),
+                Some(current) => *current = json!([current.take(), item]),
+                None => {
+                    object.insert(relation, item);
+                }
+            }
+        }
+
+        if !used.is_empty() {
+            let curies: Vec<Json> = used
+                .iter()
+                .map(|curie| json!({ "name": curie.name, "href": curie.href, "templated": true }))
+                .collect();
+
+            object.insert("curies".into(), curies.into());
+        }
+
+        Json::Object(object)
+    }
+}
+
+impl Link {
+    /// Converts the link into a HAL link object, with no relation type nor
+    /// context. See `Header::to_hal_links`.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let header = parse(r#"</orders{?id}>; rel="search"; templated; title="Find""#, None).unwrap();
+    /// let object = header.links()[0].to_hal_link();
+    ///
+    /// assert_eq!(object, serde_json::json!({"href": "/orders{?id}", "title": "Find", "templated": true}));
+    /// ```
+    pub fn to_hal_link(&self) -> Json {
+        let mut object = Map::new();
+        object.insert("href".into(), self.target.as_str().into());
+
+        for param in self.attributes() {
+            let name = param.name().trim_end_matches('*').to_string();
+
+            if object.contains_key(&name) {
+                continue;
+            }
+
+            match (param.value(), &name[..]) {
+                (None, "templated") => object.insert(name, true.into()),
+                (None, _) => continue,
+                (Some(value), _) => object.insert(name, value.text().into()),
+            };
+        }
+
+        Json::Object(object)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let input = r#"{"_links": {
+            "curies": [{"name": "acme", "href": "https://acme.example/rels/{rel}", "templated": true}],
+            "acme:widgets": [{"href": "/widgets{?q}", "templated": true}, {"href": "/w", "hreflang": "en", "type": "text/html", "name": "w"}],
+            "self": {"href": "/"}
+        }}"#;
+        let header = parse(input, None).unwrap();
+        let curies = vec![Curie::new("acme", "https://acme.example/rels/{rel}")];
+        let document = json!({ "_links": header.to_hal_links(&curies) });
+        let other = parse(&document.to_string(), None).unwrap();
+
+        assert_eq!(header, other);
+        assert_eq!(document["_links"]["acme:widgets"][0]["templated"], true);
+        assert_eq!(document["_links"]["acme:widgets"][1]["name"], "w");
+    }
+
+    #[test]
+    fn invalid_links() {
+        assert!(parse(r#"{"total": 1}"#, None).is_err());
+        assert!(parse(r#"{"_links": {"next": {"title": "Next"}}}"#, None).is_err());
+        assert!(parse(
+            r#"{"_links": {"next": [{"href": "/2", "title": 2}]}}"#,
+            None
+        )
+        .is_err());
+    }
+}
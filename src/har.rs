@@ -0,0 +1,151 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Links from HTTP Archive (HAR) documents, e.g. recorded by a browser.
+
+use crate::context::infer;
+use crate::error::{ParserError, Result};
+use crate::header::{Header, MergePolicy};
+use crate::parser;
+use serde_json::Value as Json;
+
+/// The links of a response recorded in a HAR document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// The request URL.
+    pub url: url::Url,
+    /// The response status. `0` when the request did not complete.
+    pub status: u16,
+    /// Every `Link` field of the response merged in order.
+    pub header: Header,
+}
+
+/// Reads the links of every entry in a HAR document, in order.
+///
+/// The context of each header is inferred from the entry URL, the status and
+/// the `Content-Location` and `Location` fields (see `context::infer`).
+/// Targets are resolved against the entry URL, the base URI of the response.
+/// Entries with no `Link` field have an empty header.
+///
+/// ```
+/// use linkheader::har;
+///
+/// let input = r#"{"log": {"entries": [
+///   {
+///     "request": {"url": "https://example.org/"},
+///     "response": {"status": 200, "headers": [
+///       {"name": "link", "value": "</app.css>; rel=\"preload\"; as=\"style\""},
+///       {"name": "link", "value": "</app.js>; rel=\"preload\"; as=\"script\""}
+///     ]}
+///   },
+///   {
+///     "request": {"url": "https://example.org/app.css"},
+///     "response": {"status": 200, "headers": []}
+///   }
+/// ]}}"#;
+/// let entries = har::parse(input).unwrap();
+/// let preloads = &entries[0].header;
+///
+/// assert_eq!(entries.len(), 2);
+/// assert_eq!(preloads.by_relation("preload").len(), 2);
+/// assert!(entries[1].header.is_empty());
+/// ```
+pub fn parse(input: &str) -> Result<Vec<Entry>> {
+    let document: Json = serde_json::from_str(input)?;
+    let entries = document
+        .pointer("/log/entries")
+        .and_then(Json::as_array)
+        .ok_or_else(|| invalid("expected a \"log\" object with an \"entries\" array"))?;
+
+    entries.iter().map(collect_entry).collect()
+}
+
+fn collect_entry(entry: &Json) -> Result<Entry> {
+    let url = entry
+        .pointer("/request/url")
+        .and_then(Json::as_str)
+        .ok_or_else(|| invalid("expected an entry with a request \"url\" string"))?;
+    let url = url::Url::parse(url)?;
+    let status = entry
+        .pointer("/response/status")
+        .and_then(Json::as_u64)
+        .ok_or_else(|| invalid("expected an entry with a response \"status\" number"))?;
+    let fields = entry
+        .pointer("/response/headers")
+        .and_then(Json::as_array)
+        .ok_or_else(|| invalid("expected an entry with a response \"headers\" array"))?
+        .iter()
+        .map(|field| {
+            let name = field.get("name").and_then(Json::as_str);
+            let value = field.get("value").and_then(Json::as_str);
+
+            name.zip(value)
+                .ok_or_else(|| invalid("expected a header with \"name\" and \"value\" strings"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    };
+    let status = status as u16;
+    let context = infer(&url, status, field("content-location"), field("location"));
+    let mut header = Header::new();
+
+    for (_, value) in fields
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("link"))
+    {
+        let other = parser::parse(value, Some(context.clone()))
+            .map_err(|err| format_err!("Invalid Link header for {}: {}", url, err))?;
+
+        header.merge(other, MergePolicy::KeepAll);
+    }
+
+    header.resolve_targets(&url);
+
+    Ok(Entry {
+        url,
+        status,
+        header,
+    })
+}
+
+fn invalid(message: &str) -> failure::Error {
+    ParserError::InvalidHar(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_resolve_against_the_entry_url() {
+        let input = r#"{"log": {"entries": [{
+          "request": {"url": "https://example.org/books"},
+          "response": {"status": 201, "headers": [
+            {"name": "Location", "value": "/books/2"},
+            {"name": "Link", "value": "<?v=1>; rel=\"version-history\""}
+          ]}
+        }]}}"#;
+        let entries = parse(input).unwrap();
+        let link = &entries[0].header.links()[0];
+
+        assert_eq!(link.target.as_str(), "https://example.org/books?v=1");
+        assert_eq!(
+            link.context.url().unwrap().as_str(),
+            "https://example.org/books/2"
+        );
+    }
+
+    #[test]
+    fn invalid_structure() {
+        assert!(parse(r#"{"entries": []}"#).is_err());
+        assert!(parse(r#"{"log": {"entries": [{"request": {}, "response": {}}]}}"#).is_err());
+        assert!(parse(r#"{"log": {"entries": []}}"#).unwrap().is_empty());
+    }
+}
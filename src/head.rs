@@ -0,0 +1,185 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Links from raw HTTP response heads, e.g. the output of `curl -i`.
+
+use crate::context::infer;
+use crate::error::Result;
+use crate::header::{Header, MergePolicy};
+use crate::parser;
+
+/// A status line and its header fields.
+#[derive(Debug, Default)]
+struct Head {
+    status: Option<u16>,
+    fields: Vec<(String, String)>,
+    /// The `Location` of every redirect followed to get to this head.
+    redirects: Vec<String>,
+}
+
+impl Head {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Reads the final response head in the input.
+///
+/// The status line is optional. Interim (`1xx`) and redirect heads, as
+/// printed by `curl -i -L`, are skipped in favour of the last one. Anything
+/// after the last head, i.e. the body, is ignored.
+fn read(input: &str) -> Head {
+    let mut head = Head::default();
+    let mut lines = input.lines().map(|line| line.trim_end_matches('\r'));
+
+    while let Some(line) = lines.next() {
+        if let Some(status_line) = line.strip_prefix("HTTP/") {
+            let location = match head.status {
+                Some(300..=399) => head.field("location").map(String::from),
+                _ => None,
+            };
+            let mut redirects = head.redirects;
+            redirects.extend(location);
+
+            head = Head {
+                status: status_line
+                    .split_whitespace()
+                    .nth(1)
+                    .and_then(|code| code.parse().ok()),
+                fields: vec![],
+                redirects,
+            };
+        } else if line.is_empty() {
+            let is_final = match head.status {
+                Some(status) => !(100..200).contains(&status) && !(300..400).contains(&status),
+                None => true,
+            };
+
+            match lines.clone().next() {
+                Some(next) if !is_final && next.starts_with("HTTP/") => continue,
+                _ => break,
+            }
+        } else if line.starts_with([' ', '\t']) {
+            // Obsolete line folding (RFC7230 Section 3.2.4).
+            if let Some((_, value)) = head.fields.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            head.fields
+                .push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    head
+}
+
+/// The raw `Link` field values of the final response head, in order.
+///
+/// ```
+/// use linkheader::head::link_values;
+///
+/// let input = "HTTP/1.1 200 OK\r\nLink: </1>; rel=\"prev\"\r\nlink: </3>;\r\n rel=\"next\"\r\n\r\nbody";
+///
+/// assert_eq!(link_values(input), vec![r#"</1>; rel="prev""#, r#"</3>; rel="next""#]);
+/// ```
+pub fn link_values(input: &str) -> Vec<String> {
+    read(input)
+        .fields
+        .into_iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("link"))
+        .map(|(_, value)| value)
+        .collect()
+}
+
+/// Parses and merges every `Link` field of the final response head.
+///
+/// Given the request URL, the context is inferred from the status and the
+/// `Content-Location` and `Location` fields (see `context::infer`) after
+/// following any redirect in the input. Targets are resolved against the
+/// request URL after redirects, the base URI of the final response.
+///
+/// ```
+/// use linkheader::head;
+///
+/// let input = "HTTP/1.1 301 Moved Permanently
+/// Location: https://example.org/items
+///
+/// HTTP/2 200
+/// content-type: application/json
+/// link: <?page=2>; rel=\"next\"
+///
+/// []
+/// ";
+/// let request_url = url::Url::parse("http://example.org/items").ok();
+/// let header = head::parse(input, request_url).unwrap();
+/// let next = header.next().unwrap().resolve_target(None).unwrap();
+///
+/// assert_eq!(next.as_str(), "https://example.org/items?page=2");
+/// ```
+pub fn parse(input: &str, request_url: Option<url::Url>) -> Result<Header> {
+    let head = read(input);
+    let request_url = request_url.map(|url| {
+        head.redirects
+            .iter()
+            .fold(url, |url, location| url.join(location).unwrap_or(url))
+    });
+    let context = request_url.as_ref().map(|url| {
+        infer(
+            url,
+            head.status.unwrap_or(200),
+            head.field("content-location"),
+            head.field("location"),
+        )
+    });
+    let mut header = Header::new();
+
+    for (name, value) in &head.fields {
+        if name.eq_ignore_ascii_case("link") {
+            let other = parser::parse(value, context.clone())?;
+
+            header.merge(other, MergePolicy::KeepAll);
+        }
+    }
+
+    if let Some(base) = &request_url {
+        header.resolve_targets(base);
+    }
+
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interim_heads() {
+        let input = "HTTP/1.1 100 Continue\n\nHTTP/1.1 201 Created\nLocation: /items/7\nLink: <?v=1>; rel=\"version-history\"\n\nHTTP/1.1 is not a head";
+        let request_url = url::Url::parse("https://example.org/items").ok();
+        let header = parse(input, request_url).unwrap();
+        let link = &header.links()[0];
+
+        assert_eq!(link.target.as_str(), "https://example.org/items?v=1");
+        assert_eq!(
+            link.context.url().unwrap().as_str(),
+            "https://example.org/items/7"
+        );
+    }
+
+    #[test]
+    fn headers_only() {
+        let input = "Content-Type: text/html\nLink: </style.css>; rel=\"stylesheet\"\n";
+
+        assert_eq!(
+            link_values(input),
+            vec![r#"</style.css>; rel="stylesheet""#]
+        );
+    }
+}
@@ -4,10 +4,1137 @@
 // This file may not be copied, modified, or distributed except
 // according to those terms.
 
-use crate::link::Link;
+#[cfg(feature = "std")]
+use crate::context::Context;
+#[cfg(feature = "std")]
+use crate::error::{AmbiguousRelation, Result};
+use crate::link::{Link, Relation};
+use crate::param::Value;
+use crate::uri::UriRef;
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use core::cell::OnceCell as OnceLock;
+use core::fmt::{self, Display};
+use core::iter::FromIterator;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+/// The positions of the links by lowercased relation type.
+#[cfg(feature = "std")]
+type RelationIndex = HashMap<String, Vec<usize>>;
+#[cfg(not(feature = "std"))]
+type RelationIndex = BTreeMap<String, Vec<usize>>;
 
 /// A collection of links.
-#[derive(Debug, PartialEq)]
+///
+/// Relation type lookups (e.g. `by_relation`) use an index built on first
+/// use and discarded whenever the links are mutated. Without the `std`
+/// feature the index is not thread-safe, so headers are not `Sync`.
+#[derive(Clone, Default)]
 pub struct Header {
-    pub links: Vec<Link>,
+    /// Mutate through `links_mut` so the index is discarded.
+    pub(crate) links: Vec<Link>,
+    pub(crate) index: OnceLock<RelationIndex>,
+}
+
+/// The strategy to resolve conflicting links when merging headers.
+///
+/// A conflict happens when more than one link share a singleton relation type
+/// (e.g. `canonical`) for the same context.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep every link, conflicting or not.
+    KeepAll,
+    /// Keep the first conflicting link and drop the rest.
+    #[default]
+    KeepFirst,
+    /// Keep the last conflicting link and drop the rest.
+    KeepLast,
+}
+
+impl Header {
+    pub fn new() -> Header {
+        Header::default()
+    }
+
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    /// Gives mutable access to the links, discarding the relation index.
+    pub fn links_mut(&mut self) -> &mut Vec<Link> {
+        self.index = OnceLock::new();
+
+        &mut self.links
+    }
+
+    pub fn into_links(self) -> Vec<Link> {
+        self.links
+    }
+
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Link> {
+        self.links.iter()
+    }
+
+    /// The positions of the links by lowercased relation type.
+    fn relation_index(&self) -> &RelationIndex {
+        self.index.get_or_init(|| {
+            let mut index = RelationIndex::new();
+
+            for (idx, link) in self.links.iter().enumerate() {
+                if let Some(relation) = &link.relation {
+                    index
+                        .entry(relation.as_str().to_ascii_lowercase())
+                        .or_default()
+                        .push(idx);
+                }
+            }
+
+            index
+        })
+    }
+
+    pub fn push(&mut self, link: Link) {
+        self.links_mut().push(link);
+    }
+
+    /// Inserts a link at the given position.
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, link: Link) {
+        self.links_mut().insert(index, link);
+    }
+
+    /// Removes every link with the given relation type and returns them.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<https://example.org/1>; rel="prev", <https://example.org/3>; rel="next", <https://example.org/5>; rel="NEXT""#;
+    /// let mut header = parse(input, None).unwrap();
+    /// let removed = header.remove_by_rel("next");
+    ///
+    /// assert_eq!(removed.len(), 2);
+    /// assert_eq!(header.links().len(), 1);
+    /// ```
+    pub fn remove_by_rel(&mut self, relation: &str) -> Vec<Link> {
+        let (removed, rest) = self
+            .links_mut()
+            .drain(..)
+            .partition(|link| link.has_relation(relation));
+
+        self.links = rest;
+
+        removed
+    }
+
+    /// Removes every link with any of the given relation types and returns
+    /// them.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</internal/debug>; rel="x-debug", </next>; rel="next", </trace>; rel="X-Trace""#;
+    /// let mut header = parse(input, None).unwrap();
+    /// let removed = header.strip_relations(&["x-debug", "x-trace"]);
+    ///
+    /// assert_eq!(removed.len(), 2);
+    /// assert_eq!(header.to_string(), r#"</next>; rel="next""#);
+    /// ```
+    pub fn strip_relations(&mut self, relations: &[&str]) -> Vec<Link> {
+        relations
+            .iter()
+            .flat_map(|relation| self.remove_by_rel(relation))
+            .collect()
+    }
+
+    /// Removes the given params, compared case-insensitively, from every link
+    /// and returns how many were removed.
+    ///
+    /// See `Link::remove_param`.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</next>; rel="next"; x-upstream="node-3"; title="Next", </prev>; rel="prev"; X-Upstream="node-1""#;
+    /// let mut header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(header.strip_params(&["x-upstream"]), 2);
+    /// assert_eq!(header.to_string(), r#"</next>; rel="next"; title="Next", </prev>; rel="prev""#);
+    /// ```
+    pub fn strip_params(&mut self, names: &[&str]) -> usize {
+        let mut count = 0;
+
+        for link in self.links_mut() {
+            for name in names {
+                count += link.remove_param(name).len();
+            }
+        }
+
+        count
+    }
+
+    /// Keeps only the links for which the predicate returns `true`.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</style.css>; rel="preload"; as="style", </app.js>; rel="preload"; as="script""#;
+    /// let mut header = parse(input, None).unwrap();
+    ///
+    /// header.retain(|link| link.target.path().ends_with(".css"));
+    ///
+    /// assert_eq!(header.to_string(), r#"</style.css>; rel="preload"; as="style""#);
+    /// ```
+    pub fn retain(&mut self, predicate: impl FnMut(&Link) -> bool) {
+        self.links_mut().retain(predicate);
+    }
+
+    /// Returns the links whose target or context do not share origin with the
+    /// given URL (see `Link::is_same_origin`).
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let base = url::Url::parse("https://example.org/").unwrap();
+    /// let input = r#"</terms>; rel="copyright"; anchor="https://bank.example.com/", </about>; rel="about""#;
+    /// let header = parse(input, Some(base.clone())).unwrap();
+    /// let links = header.cross_origin_links(&base);
+    ///
+    /// assert_eq!(links.len(), 1);
+    /// assert_eq!(links[0].target.as_str(), "/terms");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn cross_origin_links(&self, url: &url::Url) -> Vec<&Link> {
+        self.links
+            .iter()
+            .filter(|link| !link.is_same_origin(url))
+            .collect()
+    }
+
+    /// Removes the links whose target scheme is not one of the given ones and
+    /// returns them.
+    ///
+    /// Relative targets take the scheme from the link context and are kept
+    /// when there is no context.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<javascript:alert(1)>; rel="help", <data:text/css,body{}>; rel="stylesheet", <https://example.org/>; rel="home", </about>; rel="about""#;
+    /// let mut header = parse(input, None).unwrap();
+    /// let removed = header.filter_schemes(&["http", "https"]);
+    ///
+    /// assert_eq!(removed.len(), 2);
+    /// assert_eq!(header.links().len(), 2);
+    /// ```
+    pub fn filter_schemes(&mut self, schemes: &[&str]) -> Vec<Link> {
+        let (kept, removed) = self
+            .links_mut()
+            .drain(..)
+            .partition(|link| link.has_scheme_in(schemes));
+
+        self.links = kept;
+
+        removed
+    }
+
+    /// Rewrites every link target with the given function.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<http://upstream.internal/items?page=2>; rel="next", </items?page=1>; rel="prev""#;
+    /// let mut header = parse(input, None).unwrap();
+    ///
+    /// header.map_targets(|target| {
+    ///     target
+    ///         .as_str()
+    ///         .replacen("http://upstream.internal/", "https://api.example.org/v1/", 1)
+    ///         .into()
+    /// });
+    ///
+    /// assert_eq!(
+    ///     header.to_string(),
+    ///     r#"<https://api.example.org/v1/items?page=2>; rel="next", </items?page=1>; rel="prev""#
+    /// );
+    /// ```
+    pub fn map_targets(&mut self, mut f: impl FnMut(&UriRef) -> UriRef) {
+        for link in self.links_mut() {
+            link.target = f(&link.target);
+        }
+    }
+
+    /// Resolves every target and "anchor" against `from` and re-expresses
+    /// the ones under `from` under `to` instead.
+    ///
+    /// A URL is under `from` when it starts with `from`, ignoring query and
+    /// fragment, at a path segment boundary. Other targets and anchors are left
+    /// resolved against `from`. The header is left untouched on error.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</api/items?page=2>; rel="next", <https://cdn.example.net/app.js>; rel="preload""#;
+    /// let mut header = parse(input, None).unwrap();
+    /// let from = url::Url::parse("http://backend.internal:8080/api").unwrap();
+    /// let to = url::Url::parse("https://example.org/public/v1").unwrap();
+    ///
+    /// header.rebase(&from, &to).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.to_string(),
+    ///     r#"<https://example.org/public/v1/items?page=2>; rel="next", <https://cdn.example.net/app.js>; rel="preload""#
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn rebase(&mut self, from: &url::Url, to: &url::Url) -> Result<()> {
+        let without_query = |url: &url::Url| {
+            let mut url = url.clone();
+            url.set_query(None);
+            url.set_fragment(None);
+            url.into_string()
+        };
+        let prefix = without_query(from);
+        let replacement = without_query(to);
+        let rebase = |uri: &UriRef| -> Result<String> {
+            let url = uri.to_url(Some(from))?;
+
+            Ok(match url.as_str().strip_prefix(&prefix[..]) {
+                Some(rest)
+                    if prefix.ends_with('/')
+                        || rest.is_empty()
+                        || rest.starts_with(['/', '?', '#']) =>
+                {
+                    let rest = match rest.strip_prefix('/') {
+                        Some(rest) if replacement.ends_with('/') => rest,
+                        _ => rest,
+                    };
+
+                    format!("{}{}", replacement, rest)
+                }
+                _ => url.into_string(),
+            })
+        };
+        let mut rebased = Vec::with_capacity(self.links.len());
+
+        for link in &self.links {
+            let anchor = match &link.context {
+                Context::Anchored { base, anchor, .. } => Some(Context::anchored(
+                    base.clone(),
+                    rebase(&anchor.as_str().into())?,
+                )),
+                _ => None,
+            };
+
+            rebased.push((rebase(&link.target)?, anchor));
+        }
+
+        for (link, (target, anchor)) in self.links_mut().iter_mut().zip(rebased) {
+            link.target = target.into();
+
+            if let Some(context) = anchor {
+                link.context = context;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves every target against the base URI of the response, e.g. when
+    /// the link context comes from `Content-Location`. Targets that cannot be
+    /// resolved are left as given.
+    #[cfg(feature = "std")]
+    pub(crate) fn resolve_targets(&mut self, base: &url::Url) {
+        for link in self.links_mut() {
+            if let Ok(target) = link.resolve_target(Some(base)) {
+                link.target = target.as_str().into();
+            }
+        }
+    }
+
+    /// Returns a copy of the header with every target converted to an ASCII
+    /// URI (see `UriRef::to_ascii`), safe to emit where IRIs are not
+    /// expected.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let header = parse(r#"<https://bücher.example/àbac>; rel="alternate""#, None).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.to_ascii().unwrap().to_string(),
+    ///     r#"<https://xn--bcher-kva.example/%C3%A0bac>; rel="alternate""#
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_ascii(&self) -> Result<Header> {
+        let mut result = self.clone();
+
+        for link in result.links_mut() {
+            link.target = link.target.to_ascii()?;
+        }
+
+        Ok(result)
+    }
+
+    /// Appends the links from `other` resolving conflicts with the given policy.
+    ///
+    /// Conflicts are resolved across the whole resulting collection, not just
+    /// between `self` and `other`.
+    ///
+    /// ```
+    /// use linkheader::{parse, MergePolicy};
+    ///
+    /// let mut header = parse(r#"<https://example.org/a>; rel="canonical""#, None).unwrap();
+    /// let other = parse(r#"<https://example.org/b>; rel="canonical""#, None).unwrap();
+    ///
+    /// header.merge(other, MergePolicy::KeepLast);
+    ///
+    /// assert_eq!(header.links().len(), 1);
+    /// assert_eq!(header.links()[0].target, "https://example.org/b".into());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn merge(&mut self, other: Header, policy: MergePolicy) {
+        self.append(other);
+        self.resolve_conflicts(policy);
+    }
+
+    /// Merges all headers in order into a single one.
+    #[cfg(feature = "std")]
+    pub fn merge_all(headers: impl IntoIterator<Item = Header>, policy: MergePolicy) -> Header {
+        let mut result = Header::default();
+
+        for header in headers {
+            result.append(header);
+        }
+
+        result.resolve_conflicts(policy);
+
+        result
+    }
+
+    /// Groups links by relation type preserving their relative order.
+    ///
+    /// Links with no relation type are left out.
+    ///
+    /// ```
+    /// use linkheader::{parse, Relation};
+    ///
+    /// let input = r#"<https://example.org/1>; rel="prev", <https://example.org/3>; rel="next", <https://example.org/ca>; rel="alternate""#;
+    /// let header = parse(input, None).unwrap();
+    /// let groups = header.group_by_relation();
+    ///
+    /// assert_eq!(groups.len(), 3);
+    /// assert_eq!(groups[&Relation::from("next")].len(), 1);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn group_by_relation(&self) -> HashMap<Relation, Vec<&Link>> {
+        let mut groups: HashMap<Relation, Vec<&Link>> = HashMap::new();
+
+        for link in &self.links {
+            if let Some(relation) = &link.relation {
+                groups.entry(relation.clone()).or_default().push(link);
+            }
+        }
+
+        groups
+    }
+
+    /// Returns the links with the given relation type in order.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</1>; rel="prev", </3>; rel="next", </5>; rel="NEXT""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(header.by_relation("next").len(), 2);
+    /// ```
+    pub fn by_relation(&self, relation: &str) -> Vec<&Link> {
+        self.relation_index()
+            .get(&relation.to_ascii_lowercase())
+            .map_or(vec![], |indices| {
+                indices.iter().map(|&idx| &self.links[idx]).collect()
+            })
+    }
+
+    /// The first `rel="next"` link, if any.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let header = parse(r#"</1>; rel="prev", </3>; rel="next""#, None).unwrap();
+    ///
+    /// assert_eq!(header.next().unwrap().target.as_str(), "/3");
+    /// assert_eq!(header.prev().unwrap().target.as_str(), "/1");
+    /// ```
+    pub fn next(&self) -> Option<&Link> {
+        self.first_by_relation("next")
+    }
+
+    /// The first `rel="prev"` or `rel="previous"` link, if any.
+    pub fn prev(&self) -> Option<&Link> {
+        let index = self.relation_index();
+        let idx = ["prev", "previous"]
+            .iter()
+            .filter_map(|relation| index.get(*relation)?.first())
+            .min()?;
+
+        Some(&self.links[*idx])
+    }
+
+    pub(crate) fn first_by_relation(&self, relation: &str) -> Option<&Link> {
+        let idx = self
+            .relation_index()
+            .get(&relation.to_ascii_lowercase())?
+            .first()?;
+
+        Some(&self.links[*idx])
+    }
+
+    /// Returns the only link with the given relation type, if any.
+    ///
+    /// Meant for relation types expected to appear once such as `canonical`
+    /// or `self`. Links sharing context and an equivalent target are not
+    /// considered conflicting; otherwise an `AmbiguousRelation` error is
+    /// returned instead of silently picking one.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<https://example.org/a>; rel="canonical", <https://example.org/b>; rel="canonical""#;
+    /// let header = parse(input, None).unwrap();
+    /// let err = header.find_unique("canonical").unwrap_err();
+    ///
+    /// assert_eq!(err.targets.len(), 2);
+    /// assert_eq!(header.find_unique("self"), Ok(None));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn find_unique(
+        &self,
+        relation: &str,
+    ) -> std::result::Result<Option<&Link>, AmbiguousRelation> {
+        let links = self.by_relation(relation);
+        let mut seen = HashSet::new();
+        let targets: Vec<_> = links
+            .iter()
+            .filter(|link| seen.insert((link.target.normalize(), link.context.url())))
+            .map(|link| link.target.clone())
+            .collect();
+
+        if targets.len() > 1 {
+            return Err(AmbiguousRelation {
+                relation: relation.into(),
+                targets,
+            });
+        }
+
+        Ok(links.into_iter().next())
+    }
+
+    /// Returns every value of the given param across all link-values in order.
+    /// A link-value with a multi-token "rel" counts once.
+    ///
+    /// See `Link::param_values`.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</app.js>; rel="preload"; as="script"; integrity="sha384-abc", </feed>; rel="alternate"; type="application/atom+xml", </lib.js>; rel="preload"; as="script"; integrity="sha384-def""#;
+    /// let header = parse(input, None).unwrap();
+    /// let hashes: Vec<_> = header.param_values("integrity").iter().map(|value| value.text()).collect();
+    ///
+    /// assert_eq!(hashes, vec!["sha384-abc", "sha384-def"]);
+    /// assert_eq!(header.param_values("type").len(), 1);
+    /// ```
+    pub fn param_values(&self, name: &str) -> Vec<&Value> {
+        self.groups()
+            .into_iter()
+            .flat_map(|group| group[0].param_values(name))
+            .collect()
+    }
+
+    /// Returns the first `rel="canonical"` link (RFC6596), if any.
+    ///
+    /// See `find_unique` to detect conflicting canonical links.
+    pub fn canonical(&self) -> Option<&Link> {
+        self.first_by_relation("canonical")
+    }
+
+    /// Resolves the canonical link target against the given base or, when
+    /// missing, against the link context.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let context = url::Url::parse("https://example.org/products?id=1&utm_source=feed").ok();
+    /// let header = parse(r#"</products/1>; rel="canonical""#, context).unwrap();
+    /// let canonical = header.canonical_url(None).unwrap();
+    ///
+    /// assert_eq!(canonical.unwrap().as_str(), "https://example.org/products/1");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn canonical_url(&self, base: Option<&url::Url>) -> Result<Option<url::Url>> {
+        self.canonical()
+            .map(|link| link.resolve_target(base))
+            .transpose()
+    }
+
+    /// Splits the links by the link-value they were collected from.
+    ///
+    /// Links with no group are considered a link-value on their own.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<http://example.org/>; rel="start http://example.net/relation/other", </terms>; rel="copyright""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(header.links().len(), 3);
+    /// assert_eq!(header.groups().len(), 2);
+    /// ```
+    pub fn groups(&self) -> Vec<&[Link]> {
+        let mut result = vec![];
+        let mut start = 0;
+
+        for idx in 1..=self.links.len() {
+            if idx == self.links.len() || !self.links[start].is_sibling(&self.links[idx]) {
+                result.push(&self.links[start..idx]);
+                start = idx;
+            }
+        }
+
+        result
+    }
+
+    /// Moves the links from `other` shifting their groups so they never clash
+    /// with the existing ones.
+    #[cfg(feature = "std")]
+    fn append(&mut self, other: Header) {
+        let offset = self
+            .links
+            .iter()
+            .filter_map(|link| link.group)
+            .max()
+            .map_or(0, |group| group + 1);
+
+        self.links_mut().extend(other.into_iter().map(|mut link| {
+            link.group = link.group.map(|group| group + offset);
+            link
+        }));
+    }
+
+    #[cfg(feature = "std")]
+    fn resolve_conflicts(&mut self, policy: MergePolicy) {
+        let mut seen = HashSet::new();
+        let mut keep: Vec<bool> = vec![true; self.links.len()];
+        let indices: Vec<usize> = match policy {
+            MergePolicy::KeepAll => return,
+            MergePolicy::KeepFirst => (0..self.links.len()).collect(),
+            MergePolicy::KeepLast => (0..self.links.len()).rev().collect(),
+        };
+
+        for idx in indices {
+            let link = &self.links[idx];
+
+            if let Some(relation) = &link.relation {
+                if relation.is_singleton() {
+                    keep[idx] = seen.insert((relation.clone(), link.context.url().cloned()));
+                }
+            }
+        }
+
+        let mut flags = keep.into_iter();
+        self.links_mut().retain(|_| flags.next().unwrap_or(true));
+    }
+
+    /// Returns the links in `self` followed by the links in `other` with no
+    /// equivalent in `self`.
+    ///
+    /// Links are equivalent when they share relation type, context and an
+    /// equivalent target (see `dedup`).
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let a = parse(r#"</1>; rel="prev", </3>; rel="next""#, None).unwrap();
+    /// let b = parse(r#"</3>; rel="next"; title="Next", </5>; rel="last""#, None).unwrap();
+    ///
+    /// assert_eq!(a.union(&b).to_string(), r#"</1>; rel="prev", </3>; rel="next", </5>; rel="last""#);
+    /// assert_eq!(a.intersection(&b).to_string(), r#"</3>; rel="next""#);
+    /// assert_eq!(a.difference(&b).to_string(), r#"</1>; rel="prev""#);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn union(&self, other: &Header) -> Header {
+        let mut header = self.clone();
+
+        header.append(other.difference(self));
+
+        header
+    }
+
+    /// Returns the links in `self` with an equivalent in `other`.
+    #[cfg(feature = "std")]
+    pub fn intersection(&self, other: &Header) -> Header {
+        let keys = other.equivalence_keys();
+
+        self.filtered(|link| keys.contains(&link.equivalence_key()))
+    }
+
+    /// Returns the links in `self` with no equivalent in `other`.
+    #[cfg(feature = "std")]
+    pub fn difference(&self, other: &Header) -> Header {
+        let keys = other.equivalence_keys();
+
+        self.filtered(|link| !keys.contains(&link.equivalence_key()))
+    }
+
+    #[cfg(feature = "std")]
+    fn equivalence_keys(&self) -> HashSet<(UriRef, Option<Relation>, Option<url::Url>)> {
+        self.links.iter().map(Link::equivalence_key).collect()
+    }
+
+    #[cfg(feature = "std")]
+    fn filtered(&self, predicate: impl FnMut(&&Link) -> bool) -> Header {
+        self.links.iter().filter(predicate).cloned().collect()
+    }
+
+    /// Removes duplicate links keeping the first occurrence.
+    ///
+    /// Two links are considered duplicates when they share relation type,
+    /// context and an equivalent target (see `UriRef::equivalent`) regardless
+    /// of any other attribute.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<https://example.org/2>; rel="next", <https://example.org/2>; rel="next"; title="Next""#;
+    /// let mut header = parse(input, None).unwrap();
+    ///
+    /// header.dedup();
+    ///
+    /// assert_eq!(header.links().len(), 1);
+    /// assert_eq!(header.links()[0].title, None);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::new();
+
+        self.links_mut()
+            .retain(|link| seen.insert(link.equivalence_key()));
+    }
+}
+
+impl PartialEq for Header {
+    fn eq(&self, other: &Header) -> bool {
+        self.links == other.links()
+    }
+}
+
+impl fmt::Debug for Header {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Header")
+            .field("links", &self.links)
+            .finish()
+    }
+}
+
+impl From<Vec<Link>> for Header {
+    fn from(links: Vec<Link>) -> Header {
+        Header {
+            links,
+            index: OnceLock::new(),
+        }
+    }
+}
+
+impl From<Header> for Vec<Link> {
+    fn from(header: Header) -> Vec<Link> {
+        header.links
+    }
+}
+
+impl FromIterator<Link> for Header {
+    fn from_iter<I: IntoIterator<Item = Link>>(iter: I) -> Header {
+        Header::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl IntoIterator for Header {
+    type Item = Link;
+    type IntoIter = alloc::vec::IntoIter<Link>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.links.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Header {
+    type Item = &'a Link;
+    type IntoIter = core::slice::Iter<'a, Link>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.links.iter()
+    }
+}
+
+/// Serialises the header as a header field value for human-readable formats
+/// (e.g. JSON) and as a list of structured links otherwise.
+///
+/// ```
+/// use linkheader::parse;
+///
+/// let header = parse(r#"</TheBook/chapter4>; rel="next""#, None).unwrap();
+/// let json = serde_json::to_string(&header).unwrap();
+///
+/// assert_eq!(json, r#""</TheBook/chapter4>; rel=\"next\"""#);
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for Header {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            self.links.serialize(serializer)
+        }
+    }
+}
+
+/// Deserialises either a header field value or a list of structured links.
+/// Binary formats only accept the structured form.
+///
+/// ```
+/// use linkheader::Header;
+///
+/// let from_text: Header = serde_json::from_str(r#""</TheBook/chapter4>; rel=\"next\"""#).unwrap();
+/// let structured = serde_json::to_value(from_text.links()).unwrap();
+/// let from_links: Header = serde_json::from_value(structured).unwrap();
+///
+/// assert_eq!(from_text, from_links);
+/// ```
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Header {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Header, D::Error> {
+        if !deserializer.is_human_readable() {
+            return Vec::<Link>::deserialize(deserializer).map(Header::from);
+        }
+
+        struct HeaderVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for HeaderVisitor {
+            type Value = Header;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a Link header field value or a list of links")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> std::result::Result<Header, E> {
+                crate::parser::parse(value, None).map_err(E::custom)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                seq: A,
+            ) -> std::result::Result<Header, A::Error> {
+                let links: Vec<Link> = serde::Deserialize::deserialize(
+                    serde::de::value::SeqAccessDeserializer::new(seq),
+                )?;
+
+                Ok(Header::from(links))
+            }
+        }
+
+        deserializer.deserialize_any(HeaderVisitor)
+    }
+}
+
+/// The JSON schema of the serialised header: a header field value or, as
+/// accepted when deserialising, a list of structured links.
+///
+/// ```
+/// use linkheader::Header;
+///
+/// let schema = serde_json::to_value(schemars::schema_for!(Header)).unwrap();
+///
+/// assert_eq!(schema["anyOf"][0]["type"], "string");
+/// ```
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Header {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Header".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "A Link header field value (RFC8288).",
+            "anyOf": [
+                { "type": "string" },
+                generator.subschema_for::<Vec<Link>>(),
+            ],
+        })
+    }
+}
+
+/// Serialises the header as a list of link-values.
+///
+/// Consecutive links collected from the same link-value are serialised back
+/// together with a multi-token "rel".
+///
+/// ```
+/// use linkheader::parse;
+///
+/// let input = r#"<http://example.org/>; rel="start http://example.net/relation/other"; title="Start""#;
+/// let header = parse(input, None).unwrap();
+///
+/// assert_eq!(header.to_string(), input);
+/// ```
+impl Display for Header {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_separator(formatter, ", ")
+    }
+}
+
+impl Header {
+    /// Writes the link-values separated by the given separator.
+    pub(crate) fn fmt_with_separator(
+        &self,
+        formatter: &mut fmt::Formatter,
+        separator: &str,
+    ) -> fmt::Result {
+        for (idx, group) in self.groups().into_iter().enumerate() {
+            if idx > 0 {
+                write!(formatter, "{}", separator)?;
+            }
+
+            let relations: Vec<&Relation> = group
+                .iter()
+                .filter_map(|link| link.relation.as_ref())
+                .collect();
+
+            group[0].fmt_with_relations(formatter, &relations)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::parser::parse;
+    use crate::parser::parse_without_context;
+    #[cfg(not(feature = "std"))]
+    use alloc::string::ToString;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn dedup_ignores_relation_case() {
+        let input = r#"<https://example.org/2>; rel="next", <https://example.org/2>; rel="NEXT""#;
+        let mut header = parse(input, None).expect("Expect a valid header");
+
+        header.dedup();
+
+        assert_eq!(header.links().len(), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn dedup_equivalent_targets() {
+        let input =
+            r#"<https://example.org/a/../2>; rel="next", <HTTPS://example.org:443/2>; rel="next""#;
+        let mut header = parse(input, None).expect("Expect a valid header");
+
+        header.dedup();
+
+        assert_eq!(header.links().len(), 1);
+        assert_eq!(
+            header.links()[0].target,
+            "https://example.org/a/../2".into()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn dedup_keeps_different_contexts() {
+        let input = "</terms>; rel=\"copyright\", </terms>; rel=\"copyright\"; anchor=\"#foo\"";
+        let context = url::Url::parse("https://www.example.org/").ok();
+        let mut header = parse(input, context).expect("Expect a valid header");
+
+        header.dedup();
+
+        assert_eq!(header.links().len(), 2);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input = r#"</TheBook/chapter2>; rel="previous"; title*=UTF-8'de'letztes%20Kapitel, </TheBook/chapter4>; rel="next"; title*=UTF-8'de'n%C3%A4chstes%20Kapitel"#;
+        let header = parse_without_context(input).expect("Expect a valid header");
+
+        assert_eq!(header.to_string(), input);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn roundtrip_anchor() {
+        let input = r##"</terms>; rel="copyright"; anchor="#foo"; anchor="#bar""##;
+        let context = url::Url::parse("https://www.example.org/").ok();
+        let header = parse(input, context).expect("Expect a valid header");
+
+        assert_eq!(header.to_string(), input);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn merge_keeps_groups_apart() {
+        let mut header = parse(r#"<https://example.org>; rel="a""#, None).unwrap();
+        let other = parse(r#"<https://example.org>; rel="b""#, None).unwrap();
+
+        header.merge(other, MergePolicy::KeepAll);
+
+        assert_eq!(
+            header.to_string(),
+            r#"<https://example.org>; rel="a", <https://example.org>; rel="b""#
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn merge_all_keeps_non_singleton_relations() {
+        let headers = vec![
+            parse(r#"<https://a.example.org>; rel="alternate""#, None).unwrap(),
+            parse(r#"<https://b.example.org>; rel="alternate""#, None).unwrap(),
+            parse(r#"<https://a.example.org>; rel="self""#, None).unwrap(),
+            parse(r#"<https://b.example.org>; rel="self""#, None).unwrap(),
+        ];
+
+        let header = Header::merge_all(headers, MergePolicy::KeepFirst);
+        let targets: Vec<_> = header.links().iter().map(|link| &link.target).collect();
+
+        assert_eq!(
+            targets,
+            vec![
+                &"https://a.example.org".into(),
+                &"https://b.example.org".into(),
+                &"https://a.example.org".into(),
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn find_unique_ignores_equivalent_targets() {
+        let input = r#"<https://Example.org/a>; rel="canonical", <https://example.org:443/a>; rel="canonical""#;
+        let header = parse(input, None).unwrap();
+        let link = header.find_unique("canonical").unwrap().unwrap();
+
+        assert_eq!(link.target.as_str(), "https://Example.org/a");
+    }
+
+    #[test]
+    fn param_values_once_per_link_value() {
+        let header =
+            parse_without_context(r#"</a>; rel="next prev"; foo="x", </b>; foo="y""#).unwrap();
+        let values: Vec<_> = header
+            .param_values("foo")
+            .iter()
+            .map(|value| value.text())
+            .collect();
+
+        assert_eq!(values, vec!["x", "y"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn rebase_segment_boundary() {
+        let input = r#"</apis>; rel="a", </api>; rel="b", <../x>; rel="c", </api/items>; rel="d""#;
+        let mut header = parse(input, None).unwrap();
+        let from = url::Url::parse("http://backend.internal/api").unwrap();
+        let to = url::Url::parse("https://example.org/").unwrap();
+
+        header.rebase(&from, &to).unwrap();
+
+        assert_eq!(
+            header.to_string(),
+            r#"<http://backend.internal/apis>; rel="a", <https://example.org/>; rel="b", <http://backend.internal/x>; rel="c", <https://example.org/items>; rel="d""#
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn rebase_anchors_or_nothing() {
+        let from = url::Url::parse("http://backend.internal/api").unwrap();
+        let to = url::Url::parse("https://example.org/").unwrap();
+        let input = r#"</api/a>; rel="next"; anchor="http://backend.internal/api/x""#;
+        let mut header = parse(input, None).unwrap();
+
+        header.rebase(&from, &to).unwrap();
+
+        assert_eq!(
+            header.to_string(),
+            r#"<https://example.org/a>; rel="next"; anchor="https://example.org/x""#
+        );
+
+        let input = r#"</api/a>; rel="next", <http://[bad>; rel="prev""#;
+        let mut header = parse(input, None).unwrap();
+
+        assert!(header.rebase(&from, &to).is_err());
+        assert_eq!(header.to_string(), input);
+    }
+
+    #[test]
+    fn relation_index_follows_mutations() {
+        let mut header = parse_without_context(r#"</1>; rel="next""#).unwrap();
+
+        assert_eq!(header.by_relation("next").len(), 1);
+
+        header.push(header.links()[0].clone());
+        header.links_mut()[0].set_relation("prev");
+
+        assert_eq!(header.by_relation("next").len(), 1);
+        assert_eq!(header.prev().unwrap().target.as_str(), "/1");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn binary_serde_roundtrip() {
+        let context = url::Url::parse("https://example.org/").ok();
+        let input = r##"</a>; rel="next"; title*=UTF-8'de'n%c3%a4chstes; anchor="#top"; nopush"##;
+        let header = parse(input, context).unwrap();
+        let bytes = bincode::serialize(&header).unwrap();
+        let actual: Header = bincode::deserialize(&bytes).unwrap();
+
+        assert_eq!(actual, header);
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn structured_links_schema() {
+        let schema = serde_json::to_value(schemars::schema_for!(Header)).unwrap();
+        let link = &schema["$defs"]["Link"]["properties"];
+
+        assert_eq!(schema["anyOf"][1]["items"]["$ref"], "#/$defs/Link");
+        assert_eq!(schema["$defs"]["UriRef"]["type"], "string");
+        assert_eq!(link["target"]["$ref"], "#/$defs/UriRef");
+        assert_eq!(link["context"]["$ref"], "#/$defs/Context");
+    }
 }
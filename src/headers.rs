@@ -0,0 +1,66 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Typed header support for the `headers` crate.
+
+use crate::header::{Header, MergePolicy};
+use crate::http::decode;
+use crate::parser::parse;
+use ::http::{HeaderName, HeaderValue};
+use std::convert::TryFrom;
+
+/// The `Link` header as a typed header, e.g. for `TypedHeader` extractors.
+///
+/// Decoding merges every `Link` field in order and has no context, so
+/// relative targets are kept as they are.
+///
+/// ```
+/// use headers::HeaderMapExt;
+/// use linkheader::Header;
+///
+/// let mut map = http::HeaderMap::new();
+/// map.append(http::header::LINK, r#"</1>; rel="prev""#.parse().unwrap());
+/// map.append(http::header::LINK, r#"</3>; rel="next""#.parse().unwrap());
+///
+/// let header: Header = map.typed_get().unwrap();
+/// let mut other = http::HeaderMap::new();
+/// other.typed_insert(header);
+///
+/// assert_eq!(other[http::header::LINK], r#"</1>; rel="prev", </3>; rel="next""#);
+/// ```
+impl ::headers::Header for Header {
+    fn name() -> &'static HeaderName {
+        &::http::header::LINK
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Header, ::headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let mut header = Header::new();
+        let mut found = false;
+
+        for value in values {
+            let other =
+                parse(&decode(value.as_bytes()), None).map_err(|_| ::headers::Error::invalid())?;
+
+            header.merge(other, MergePolicy::KeepAll);
+            found = true;
+        }
+
+        if found {
+            Ok(header)
+        } else {
+            Err(::headers::Error::invalid())
+        }
+    }
+
+    /// Encodes the header as a single field. A header that cannot be encoded
+    /// (see `TryFrom<&Header> for HeaderValue`) is left out.
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(HeaderValue::try_from(self).ok());
+    }
+}
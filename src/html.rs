@@ -0,0 +1,324 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Links from and to HTML `<link>` elements.
+//!
+//! RFC8288 links can be serialised both as a `Link` header field and as HTML
+//! elements. Reading both into a `Header` lets clients merge them. Reading
+//! requires the `html` feature.
+
+use crate::header::Header;
+#[cfg(feature = "html")]
+use crate::param::{Param, Value};
+#[cfg(feature = "html")]
+use crate::parser::LinkBuilder;
+#[cfg(feature = "html")]
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Options to choose which elements to read links from.
+///
+/// ```
+/// use linkheader::html::{parse_with_options, HtmlOptions};
+///
+/// let document = r#"<a rel="me" href="https://social.example/@arnau">Me</a>"#;
+/// let options = HtmlOptions::new().hyperlinks(true);
+/// let header = parse_with_options(document, None, &options);
+///
+/// assert_eq!(header.by_relation("me").len(), 1);
+/// ```
+#[cfg(feature = "html")]
+#[derive(Debug, Clone, Default)]
+pub struct HtmlOptions {
+    hyperlinks: bool,
+}
+
+#[cfg(feature = "html")]
+impl HtmlOptions {
+    pub fn new() -> HtmlOptions {
+        HtmlOptions::default()
+    }
+
+    /// Also reads `<a>` and `<area>` elements with a "rel", e.g. `rel="me"`,
+    /// `rel="webmention"` or `rel="nofollow"`. Hyperlinks with no "rel" are
+    /// left out.
+    pub fn hyperlinks(mut self, hyperlinks: bool) -> HtmlOptions {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    pub fn has_hyperlinks(&self) -> bool {
+        self.hyperlinks
+    }
+}
+
+/// Reads every `<link>` element with an "href" in the document, in order.
+///
+/// The context is the document URL or, when the document has a
+/// `<base href>`, the base URL resolved against it. Relation types are
+/// lowercased as HTML link types are case-insensitive. "title", "hreflang",
+/// "media" and "type" map to their target attributes and any other attribute
+/// is kept as a param.
+///
+/// ```
+/// use linkheader::{html, parse, MergePolicy};
+///
+/// let document = r#"<!doctype html>
+/// <html>
+///   <head>
+///     <link rel="Stylesheet" href="/main.css" media="screen">
+///     <link rel="preload" href="/font.woff2" as="font" crossorigin>
+///   </head>
+/// </html>"#;
+/// let url = url::Url::parse("https://example.org/").ok();
+/// let mut header = parse(r#"</app.js>; rel="preload"; as="script""#, url.clone()).unwrap();
+///
+/// header.merge(html::parse(document, url), MergePolicy::KeepAll);
+///
+/// assert_eq!(header.by_relation("preload").len(), 2);
+/// assert_eq!(header.by_relation("stylesheet")[0].media, Some("screen".into()));
+/// ```
+#[cfg(feature = "html")]
+pub fn parse(document: &str, url: Option<url::Url>) -> Header {
+    parse_with_options(document, url, &HtmlOptions::default())
+}
+
+/// Same as `parse` with the given options.
+#[cfg(feature = "html")]
+pub fn parse_with_options(document: &str, url: Option<url::Url>, options: &HtmlOptions) -> Header {
+    let document = Html::parse_document(document);
+    let base = Selector::parse("base[href]").expect("valid selector");
+    let links = if options.hyperlinks {
+        Selector::parse("link[href], a[href][rel], area[href][rel]")
+    } else {
+        Selector::parse("link[href]")
+    }
+    .expect("valid selector");
+    let context = match document
+        .select(&base)
+        .next()
+        .and_then(|element| element.value().attr("href"))
+    {
+        Some(href) => match &url {
+            Some(url) => url.join(href.trim()).ok().or(Some(url.clone())),
+            None => url::Url::parse(href.trim()).ok(),
+        },
+        None => url,
+    };
+    let mut result = vec![];
+
+    for (group, element) in document.select(&links).enumerate() {
+        let mut builder = LinkBuilder::new(context.clone());
+        builder.set_group(group);
+
+        for (name, value) in element.value().attrs() {
+            let text = Value::Simple(value.into());
+
+            match name {
+                "href" => builder.set_target(value.trim()),
+                "rel" => {
+                    let relations: Vec<_> = value.split_ascii_whitespace().collect();
+
+                    if !relations.is_empty() {
+                        builder.set_rel(Value::Simple(relations.join(" ").to_lowercase()));
+                    }
+                }
+                "title" => builder.set_title(text),
+                "hreflang" => builder.set_lang(text),
+                "media" => builder.set_media(text),
+                "type" => builder.set_type(text),
+                _ if value.is_empty() => builder.add_param(Param::new(name, None)),
+                _ => builder.add_param(Param::new(name, Some(text))),
+            }
+        }
+
+        result.extend(builder.build());
+    }
+
+    Header::from(result)
+}
+
+/// The links of a header written one `<link>` element per line. See
+/// `Header::to_html`.
+pub struct LinkElements<'a>(&'a Header);
+
+impl Header {
+    /// Serialises the header as HTML `<link>` elements, e.g. to mirror the
+    /// `Link` header in the markup.
+    ///
+    /// Relation types of the same link-value share an element. Target
+    /// attributes and params become attributes, keeping the first one when
+    /// repeated as HTML attributes are unique, and params with no value become
+    /// boolean attributes. Only attributes of the HTML `<link>` element are
+    /// written; anything else, e.g. an `onload` param, is dropped. Links with an "anchor" are left out as HTML cannot
+    /// express a context other than the document.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</fonts/a&b.woff2>; rel="preload"; as="font"; type="font/woff2"; crossorigin, </es>; rel="alternate"; hreflang="es"; title="<Español>""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.to_html().to_string(),
+    ///     r#"<link rel="preload" href="/fonts/a&amp;b.woff2" type="font/woff2" as="font" crossorigin>
+    /// <link rel="alternate" href="/es" title="&lt;Español&gt;" hreflang="es">
+    /// "#
+    /// );
+    /// ```
+    pub fn to_html(&self) -> LinkElements<'_> {
+        LinkElements(self)
+    }
+}
+
+impl fmt::Display for LinkElements<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for group in self.0.groups() {
+            let link = &group[0];
+
+            if link.context.anchor().is_some() {
+                continue;
+            }
+
+            let relations: Vec<&str> = group
+                .iter()
+                .filter_map(|link| link.relation.as_ref())
+                .map(|relation| relation.as_str())
+                .collect();
+
+            write!(formatter, "<link")?;
+
+            if !relations.is_empty() {
+                write!(formatter, " rel=\"{}\"", escape(&relations.join(" ")))?;
+            }
+
+            write!(formatter, " href=\"{}\"", escape(link.target.as_str()))?;
+
+            let mut seen: HashSet<String> = ["rel", "href"]
+                .iter()
+                .map(|name| name.to_string())
+                .collect();
+
+            for param in link.attributes() {
+                let name = param.name().trim_end_matches('*').to_lowercase();
+
+                if !ATTRIBUTES.contains(&name.as_str()) || !seen.insert(name.clone()) {
+                    continue;
+                }
+
+                match param.value() {
+                    Some(value) => write!(formatter, " {}=\"{}\"", name, escape(value.text()))?,
+                    None => write!(formatter, " {}", name)?,
+                }
+            }
+
+            writeln!(formatter, ">")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The attributes of the HTML `<link>` element, other than `rel` and `href`,
+/// plus the `nonce` global attribute.
+const ATTRIBUTES: [&str; 16] = [
+    "as",
+    "blocking",
+    "color",
+    "crossorigin",
+    "disabled",
+    "fetchpriority",
+    "hreflang",
+    "imagesizes",
+    "imagesrcset",
+    "integrity",
+    "media",
+    "nonce",
+    "referrerpolicy",
+    "sizes",
+    "title",
+    "type",
+];
+
+/// Escapes text for a double-quoted attribute value.
+fn escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "html")]
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn render_without_anchors() {
+        let input = r#"</a>; rel="next prefetch"; title*=UTF-8'ca'Cap%C3%ADtol; title="Capitol", </b>; rel="author"; anchor="/x""#;
+        let header = parser::parse(input, None).unwrap();
+
+        assert_eq!(
+            header.to_html().to_string(),
+            "<link rel=\"next prefetch\" href=\"/a\" title=\"Capítol\">\n"
+        );
+    }
+
+    #[test]
+    fn render_link_attributes_only() {
+        let input = r#"</app.js>; rel="preload"; as="script"; onload="alert(1)"; ONERROR; x-custom=1; nonce="r4nd""#;
+        let header = parser::parse(input, None).unwrap();
+
+        assert_eq!(
+            header.to_html().to_string(),
+            "<link rel=\"preload\" href=\"/app.js\" as=\"script\" nonce=\"r4nd\">\n"
+        );
+    }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn base_element() {
+        let document = r#"<base href="/docs/"><link rel="next  Prefetch" href="page2.html">"#;
+        let url = url::Url::parse("https://example.org/index.html").ok();
+        let header = parse(document, url);
+        let targets: Vec<_> = header
+            .iter()
+            .map(|link| link.resolve_target(None).unwrap().to_string())
+            .collect();
+
+        assert_eq!(header.len(), 2);
+        assert_eq!(header.links()[1].relation, Some("prefetch".into()));
+        assert_eq!(
+            targets,
+            vec![
+                "https://example.org/docs/page2.html",
+                "https://example.org/docs/page2.html"
+            ]
+        );
+    }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn hyperlinks_in_document_order() {
+        let document = r#"
+            <link rel="webmention" href="/mention">
+            <a href="/about">About</a>
+            <a rel="nofollow" href="/ad" target="_blank">Ad</a>
+            <map><area rel="help" href="/help"></map>
+        "#;
+        let options = HtmlOptions::new().hyperlinks(true);
+        let relations: Vec<_> = parse_with_options(document, None, &options)
+            .iter()
+            .map(|link| link.relation.as_ref().unwrap().as_str().to_string())
+            .collect();
+
+        assert_eq!(relations, vec!["webmention", "nofollow", "help"]);
+        assert_eq!(parse(document, None).len(), 1);
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Conversions from and to `http` header values.
+
+use crate::context::infer;
+use crate::error::{Error, Result};
+use crate::header::{Header, MergePolicy};
+use crate::parser::parse;
+use std::convert::TryFrom;
+
+/// Parses a `Link` header value with no context.
+///
+/// Values are expected to be UTF-8. Any other sequence of bytes is decoded as
+/// ISO-8859-1, the historical encoding for header field values.
+///
+/// ```
+/// use linkheader::Header;
+/// use std::convert::TryFrom;
+///
+/// let value = http::HeaderValue::from_static(r#"</TheBook/chapter4>; rel="next""#);
+/// let header = Header::try_from(&value).unwrap();
+///
+/// assert_eq!(header.next().unwrap().target.as_str(), "/TheBook/chapter4");
+/// ```
+impl TryFrom<&::http::HeaderValue> for Header {
+    type Error = Error;
+
+    fn try_from(value: &::http::HeaderValue) -> Result<Header> {
+        parse(&decode(value.as_bytes()), None)
+    }
+}
+
+/// Serialises the header as a header value.
+///
+/// Targets are converted to ASCII URIs (see `Header::to_ascii`). Other
+/// non-ASCII text, e.g. in a quoted "title", is kept as UTF-8 bytes.
+///
+/// ```
+/// use linkheader::parse;
+/// use std::convert::TryFrom;
+///
+/// let header = parse(r#"<https://bücher.example/>; rel="alternate"; title="Bücher""#, None).unwrap();
+/// let value = http::HeaderValue::try_from(&header).unwrap();
+///
+/// assert_eq!(
+///     value.as_bytes(),
+///     r#"<https://xn--bcher-kva.example/>; rel="alternate"; title="Bücher""#.as_bytes()
+/// );
+/// ```
+impl TryFrom<&Header> for ::http::HeaderValue {
+    type Error = Error;
+
+    fn try_from(header: &Header) -> Result<::http::HeaderValue> {
+        let value = header.to_ascii()?.to_string();
+
+        Ok(::http::HeaderValue::from_bytes(value.as_bytes())?)
+    }
+}
+
+impl Header {
+    /// Parses and merges every `Link` entry in the map, in order.
+    ///
+    /// A response may carry several `Link` header fields which must be
+    /// combined as if they were a single comma-separated one (RFC9110
+    /// Section 5.3).
+    ///
+    /// ```
+    /// use linkheader::Header;
+    /// use http::header::{HeaderMap, LINK};
+    ///
+    /// let mut map = HeaderMap::new();
+    /// map.append(LINK, r#"</1>; rel="prev""#.parse().unwrap());
+    /// map.append(LINK, r#"</3>; rel="next""#.parse().unwrap());
+    ///
+    /// let context = url::Url::parse("https://example.org/2").ok();
+    /// let header = Header::from_header_map(&map, context).unwrap();
+    ///
+    /// assert_eq!(header.to_string(), r#"</1>; rel="prev", </3>; rel="next""#);
+    /// ```
+    pub fn from_header_map(map: &::http::HeaderMap, context: Option<url::Url>) -> Result<Header> {
+        let mut header = Header::new();
+
+        for value in map.get_all(::http::header::LINK) {
+            let other = parse(&decode(value.as_bytes()), context.clone())?;
+
+            header.merge(other, MergePolicy::KeepAll);
+        }
+
+        Ok(header)
+    }
+
+    /// Parses and merges every `Link` field of a response, e.g. from a
+    /// `hyper` client, with the context inferred from the request URI, the
+    /// status and the `Content-Location` and `Location` fields (see
+    /// `context::infer`).
+    ///
+    /// Relative targets are resolved against the request URI, the base URI
+    /// of the response: `Content-Location` no longer sets it (RFC7231
+    /// Appendix B). Targets that cannot be resolved are left as given.
+    ///
+    /// Responses do not carry the URI they answer, so it has to be given. A
+    /// relative URI, e.g. the origin-form of a server request, is no context.
+    ///
+    /// ```
+    /// use linkheader::Header;
+    ///
+    /// let uri: http::Uri = "https://example.org/items?page=2".parse().unwrap();
+    /// let response = http::Response::builder()
+    ///     .header("link", r#"<?page=1>; rel="prev""#)
+    ///     .header("link", r#"<?page=3>; rel="next""#)
+    ///     .body(())
+    ///     .unwrap();
+    /// let header = Header::from_response(&response, &uri).unwrap();
+    /// let next = header.next().unwrap().resolve_target(None).unwrap();
+    ///
+    /// assert_eq!(header.len(), 2);
+    /// assert_eq!(next.as_str(), "https://example.org/items?page=3");
+    /// ```
+    pub fn from_response<B>(
+        response: &::http::Response<B>,
+        request_uri: &::http::Uri,
+    ) -> Result<Header> {
+        let headers = response.headers();
+        let field = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &::http::HeaderValue| value.to_str().ok())
+        };
+        let base = uri_context(request_uri);
+        let context = base.as_ref().map(|url| {
+            infer(
+                url,
+                response.status().as_u16(),
+                field(::http::header::CONTENT_LOCATION),
+                field(::http::header::LOCATION),
+            )
+        });
+        let mut header = Header::from_header_map(headers, context)?;
+
+        if let Some(base) = &base {
+            header.resolve_targets(base);
+        }
+
+        Ok(header)
+    }
+}
+
+/// The URI as a context URL when it is absolute.
+pub(crate) fn uri_context(uri: &::http::Uri) -> Option<url::Url> {
+    match uri.scheme() {
+        Some(_) => url::Url::parse(&uri.to_string()).ok(),
+        None => None,
+    }
+}
+
+/// Decodes the bytes as UTF-8 falling back to ISO-8859-1.
+pub(crate) fn decode(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(value) => value.to_string(),
+        Err(_) => bytes.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latin1_fallback() {
+        let value =
+            ::http::HeaderValue::from_bytes(b"</a>; rel=\"next\"; title=\"Cap\xEDtol\"").unwrap();
+        let header = Header::try_from(&value).unwrap();
+
+        assert_eq!(header.links()[0].title, Some("Capítol".into()));
+    }
+
+    #[test]
+    fn response_context() {
+        let uri: ::http::Uri = "https://example.org/books".parse().unwrap();
+        let response = ::http::Response::builder()
+            .status(201)
+            .header("location", "/books/2")
+            .header("link", r#"<?v=1>; rel="version-history""#)
+            .body(())
+            .unwrap();
+        let header = Header::from_response(&response, &uri).unwrap();
+        let link = &header.links()[0];
+
+        assert_eq!(link.target.as_str(), "https://example.org/books?v=1");
+        assert_eq!(
+            link.context.url().unwrap().as_str(),
+            "https://example.org/books/2"
+        );
+    }
+}
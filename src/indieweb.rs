@@ -0,0 +1,189 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! IndieWeb endpoint discovery, i.e. Webmention, Micropub and IndieAuth, and
+//! `rel="me"` identity links.
+//!
+//! Endpoints are looked up in the `Link` header first and, with the `html`
+//! feature, in the `<link>`, `<a>` and `<area>` elements of the document as
+//! described by the [Webmention](https://www.w3.org/TR/webmention/#sender-discovers-receiver-webmention-endpoint)
+//! recommendation.
+
+use crate::header::Header;
+#[cfg(feature = "html")]
+use crate::html::{parse_with_options, HtmlOptions};
+
+/// The endpoints advertised by a page, resolved against its URL.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Endpoints {
+    pub webmention: Option<url::Url>,
+    pub micropub: Option<url::Url>,
+    pub authorization_endpoint: Option<url::Url>,
+    pub token_endpoint: Option<url::Url>,
+}
+
+impl Endpoints {
+    /// Reads the endpoints from the `Link` header of the page at the given
+    /// URL.
+    ///
+    /// ```
+    /// use linkheader::indieweb::Endpoints;
+    /// use linkheader::parse;
+    ///
+    /// let url = url::Url::parse("https://aaronpk.example/post/1").unwrap();
+    /// let header = parse(r#"</webmention>; rel="webmention", <https://auth.example/token>; rel="token_endpoint""#, Some(url.clone())).unwrap();
+    /// let endpoints = Endpoints::from_header(&header, &url);
+    ///
+    /// assert_eq!(endpoints.webmention.unwrap().as_str(), "https://aaronpk.example/webmention");
+    /// assert_eq!(endpoints.token_endpoint.unwrap().as_str(), "https://auth.example/token");
+    /// assert_eq!(endpoints.micropub, None);
+    /// ```
+    pub fn from_header(header: &Header, url: &url::Url) -> Endpoints {
+        Endpoints {
+            webmention: header.endpoint("webmention", url),
+            micropub: header.endpoint("micropub", url),
+            authorization_endpoint: header.endpoint("authorization_endpoint", url),
+            token_endpoint: header.endpoint("token_endpoint", url),
+        }
+    }
+
+    /// Reads the endpoints from the `Link` header and falls back to the HTML
+    /// document for any endpoint missing from it.
+    ///
+    /// ```
+    /// use linkheader::indieweb::Endpoints;
+    /// use linkheader::Header;
+    ///
+    /// let url = url::Url::parse("https://aaronpk.example/post/1").unwrap();
+    /// let document = r#"<link rel="micropub" href="/micropub"><a rel="webmention" href="">Mention me</a>"#;
+    /// let endpoints = Endpoints::discover(&Header::new(), document, &url);
+    ///
+    /// assert_eq!(endpoints.webmention.unwrap(), url);
+    /// assert_eq!(endpoints.micropub.unwrap().as_str(), "https://aaronpk.example/micropub");
+    /// ```
+    #[cfg(feature = "html")]
+    pub fn discover(header: &Header, document: &str, url: &url::Url) -> Endpoints {
+        let endpoints = Endpoints::from_header(header, url);
+        let options = HtmlOptions::new().hyperlinks(true);
+        let document = parse_with_options(document, Some(url.clone()), &options);
+        let fallback = Endpoints::from_header(&document, url);
+
+        Endpoints {
+            webmention: endpoints.webmention.or(fallback.webmention),
+            micropub: endpoints.micropub.or(fallback.micropub),
+            authorization_endpoint: endpoints
+                .authorization_endpoint
+                .or(fallback.authorization_endpoint),
+            token_endpoint: endpoints.token_endpoint.or(fallback.token_endpoint),
+        }
+    }
+}
+
+impl Header {
+    /// The first link with the given relation type and no anchor that
+    /// resolves, against the link context or else the given URL.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let url = url::Url::parse("https://example.org/notes/1").unwrap();
+    /// let header = parse(r#"<?a=b>; rel="webmention""#, None).unwrap();
+    ///
+    /// assert_eq!(header.endpoint("webmention", &url).unwrap().as_str(), "https://example.org/notes/1?a=b");
+    /// ```
+    pub fn endpoint(&self, relation: &str, url: &url::Url) -> Option<url::Url> {
+        self.by_relation(relation)
+            .into_iter()
+            .filter(|link| link.context.anchor().is_none())
+            .find_map(|link| {
+                link.resolve_target(Some(link.context.url().unwrap_or(url)))
+                    .ok()
+            })
+    }
+
+    /// Every `rel="me"` target with no anchor resolved against the link
+    /// context, in order and with no duplicates. Targets that cannot be
+    /// resolved are left out.
+    ///
+    /// Identity verification, e.g. IndieAuth or Mastodon profile
+    /// verification, checks all of them rather than the first one.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let context = url::Url::parse("https://arnau.example/").ok();
+    /// let input = r#"<https://github.com/arnau>; rel="me", <https://social.example/@arnau>; rel="me authn", </about>; rel="me", <https://github.com/arnau>; rel="me""#;
+    /// let header = parse(input, context).unwrap();
+    /// let me: Vec<_> = header.me_links().iter().map(|url| url.to_string()).collect();
+    ///
+    /// assert_eq!(me, vec!["https://github.com/arnau", "https://social.example/@arnau", "https://arnau.example/about"]);
+    /// ```
+    pub fn me_links(&self) -> Vec<url::Url> {
+        let mut result: Vec<url::Url> = vec![];
+
+        for link in self.by_relation("me") {
+            if link.context.anchor().is_some() {
+                continue;
+            }
+
+            if let Ok(url) = link.resolve_target(None) {
+                if !result.contains(&url) {
+                    result.push(url);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "html")]
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn skip_anchored_links() {
+        let url = url::Url::parse("https://example.org/").unwrap();
+        let input = r#"</other>; rel="micropub"; anchor="/x", <http://[>; rel="micropub", </mp>; rel="micropub""#;
+        let header = parse(input, None).unwrap();
+
+        assert_eq!(
+            header.endpoint("micropub", &url).unwrap().as_str(),
+            "https://example.org/mp"
+        );
+    }
+
+    #[test]
+    fn me_links_need_a_context() {
+        let input = r#"</about>; rel="me", <https://github.com/arnau>; rel="ME""#;
+        let header = parse(input, None).unwrap();
+
+        assert_eq!(
+            header.me_links(),
+            vec![url::Url::parse("https://github.com/arnau").unwrap()]
+        );
+    }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn header_takes_precedence() {
+        let url = url::Url::parse("https://example.org/").unwrap();
+        let header = parse(r#"</from-header>; rel="webmention""#, Some(url.clone())).unwrap();
+        let document = r#"<base href="/b/"><link rel="webmention" href="/from-html"><link rel="authorization_endpoint" href="auth">"#;
+        let endpoints = Endpoints::discover(&header, document, &url);
+
+        assert_eq!(
+            endpoints.webmention.unwrap().as_str(),
+            "https://example.org/from-header"
+        );
+        assert_eq!(
+            endpoints.authorization_endpoint.unwrap().as_str(),
+            "https://example.org/b/auth"
+        );
+    }
+}
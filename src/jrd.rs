@@ -0,0 +1,274 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Conversions from and to the `links` of WebFinger JRD documents
+//! ([RFC7033](https://tools.ietf.org/html/rfc7033)).
+
+use crate::error::{ParserError, Result};
+use crate::header::Header;
+use crate::link::Link;
+use crate::param::{Encoding, Param, Value};
+use crate::parser::LinkBuilder;
+use serde_json::{Map, Value as Json};
+
+/// The "titles" key for a title with no language (RFC7033 Section 4.4.4.3).
+const UNDETERMINED: &str = "und";
+
+/// Reads the `links` array of a JRD document. The context is the "subject"
+/// when it is a URI.
+///
+/// ```
+/// use linkheader::jrd;
+///
+/// let input = r#"{
+///   "subject": "acct:carol@example.com",
+///   "links": [
+///     {
+///       "rel": "http://webfinger.example/rel/profile-page",
+///       "href": "https://www.example.com/~carol/"
+///     },
+///     {
+///       "rel": "http://webfinger.example/rel/businesscard",
+///       "type": "text/vcard",
+///       "href": "https://www.example.com/~carol/carol.vcf",
+///       "titles": {"en-us": "Carol's business card", "und": "Carol"},
+///       "properties": {"http://example.com/role": "editor"}
+///     }
+///   ]
+/// }"#;
+/// let header = jrd::parse(input).unwrap();
+/// let card = &header.links()[1];
+///
+/// assert_eq!(card.context.url().unwrap().as_str(), "acct:carol@example.com");
+/// assert_eq!(card.title_for("en-US"), Some("Carol's business card"));
+/// assert_eq!(card.param("http://example.com/role").unwrap().value(), &Some("editor".into()));
+/// ```
+pub fn parse(input: &str) -> Result<Header> {
+    let document: Json = serde_json::from_str(input)?;
+    let context = document
+        .get("subject")
+        .and_then(Json::as_str)
+        .and_then(|subject| url::Url::parse(subject).ok());
+
+    match document.get("links") {
+        Some(links) => from_links(links, context),
+        None => Ok(Header::new()),
+    }
+}
+
+/// Reads a JRD `links` array. See `parse`.
+///
+/// "rel", "type" and "href" map to the relation type, the "type" attribute
+/// and the target. "titles" map to "title" attributes, with "und" as the
+/// title with no language, and "properties" map to params named by their
+/// URI, with no value when null.
+pub fn from_links(links: &Json, context: Option<url::Url>) -> Result<Header> {
+    let links = links
+        .as_array()
+        .ok_or_else(|| invalid("expected \"links\" to be an array"))?;
+    let mut result = vec![];
+
+    for (group, object) in links.iter().enumerate() {
+        let object = object
+            .as_object()
+            .ok_or_else(|| invalid("expected a link object"))?;
+        let mut builder = LinkBuilder::new(context.clone());
+        builder.set_group(group);
+        collect_link(&mut builder, object)?;
+        result.extend(builder.build());
+    }
+
+    Ok(Header::from(result))
+}
+
+fn collect_link(builder: &mut LinkBuilder, object: &Map<String, Json>) -> Result<()> {
+    let text = |name: &str| match object.get(name) {
+        Some(value) => value
+            .as_str()
+            .map(Some)
+            .ok_or_else(|| invalid(&format!("expected {:?} to be a string", name))),
+        None => Ok(None),
+    };
+    let relation = text("rel")?.ok_or_else(|| invalid("expected a link with a \"rel\" string"))?;
+    let href = text("href")?.ok_or_else(|| invalid("expected a link with an \"href\" string"))?;
+
+    builder.set_target(href);
+    builder.set_rel(Value::Simple(relation.into()));
+
+    if let Some(content_type) = text("type")? {
+        builder.set_type(Value::Simple(content_type.into()));
+    }
+
+    for (language, title) in members(object, "titles")? {
+        let title = title
+            .as_str()
+            .ok_or_else(|| invalid("expected titles to be strings"))?;
+
+        builder.set_title(if language == UNDETERMINED {
+            Value::Simple(title.into())
+        } else {
+            Value::Compound {
+                encoding: Encoding::Utf8,
+                language: Some(language.clone()),
+                value: title.into(),
+            }
+        });
+    }
+
+    for (name, value) in members(object, "properties")? {
+        let value = match value {
+            Json::String(value) => Some(Value::Simple(value.clone())),
+            Json::Null => None,
+            _ => return Err(invalid("expected properties to be strings or null")),
+        };
+
+        builder.add_param(Param::new(name.clone(), value));
+    }
+
+    Ok(())
+}
+
+/// The members of an optional object property.
+fn members<'a>(object: &'a Map<String, Json>, name: &str) -> Result<Vec<(&'a String, &'a Json)>> {
+    match object.get(name) {
+        Some(Json::Object(members)) => Ok(members.iter().collect()),
+        Some(_) => Err(invalid(&format!("expected {:?} to be an object", name))),
+        None => Ok(vec![]),
+    }
+}
+
+fn invalid(message: &str) -> failure::Error {
+    ParserError::InvalidJrd(message.into()).into()
+}
+
+impl Header {
+    /// Converts the header into a JRD `links` array.
+    ///
+    /// Links with no relation type or with an "anchor" are left out as JRD
+    /// links are always from the subject. See `Link::to_jrd_link`.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<https://example.com/~carol/>; rel="http://webfinger.example/rel/profile-page"; type="text/html", </other>; rel="item"; anchor="/list""#;
+    /// let header = parse(input, None).unwrap();
+    /// let links = header.to_jrd_links();
+    ///
+    /// assert_eq!(links[0]["rel"], "http://webfinger.example/rel/profile-page");
+    /// assert_eq!(links[0]["type"], "text/html");
+    /// assert_eq!(links.as_array().unwrap().len(), 1);
+    /// ```
+    pub fn to_jrd_links(&self) -> Json {
+        self.iter()
+            .filter(|link| link.context.anchor().is_none())
+            .filter_map(Link::to_jrd_link)
+            .collect()
+    }
+}
+
+impl Link {
+    /// Converts the link into a JRD link object. `None` when the link has no
+    /// relation type.
+    ///
+    /// Titles are keyed by their language, or "und" when they have none,
+    /// keeping the first one per language. Params other than "title" become
+    /// properties; "hreflang" and "media" have no JRD equivalent and are left
+    /// out.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    /// use serde_json::json;
+    ///
+    /// let input = r#"</card.vcf>; rel="http://webfinger.example/rel/businesscard"; title="Card"; title*=UTF-8'es'Tarjeta; private"#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.links()[0].to_jrd_link().unwrap(),
+    ///     json!({
+    ///         "rel": "http://webfinger.example/rel/businesscard",
+    ///         "href": "/card.vcf",
+    ///         "titles": { "es": "Tarjeta", "und": "Card" },
+    ///         "properties": { "private": null },
+    ///     })
+    /// );
+    /// ```
+    pub fn to_jrd_link(&self) -> Option<Json> {
+        let mut object = Map::new();
+        object.insert("rel".into(), self.relation.as_ref()?.as_str().into());
+
+        if let Some(content_type) = &self.content_type {
+            object.insert("type".into(), content_type.text().into());
+        }
+
+        object.insert("href".into(), self.target.as_str().into());
+
+        let mut titles = Map::new();
+
+        for (language, title) in self.titles() {
+            titles
+                .entry(language.unwrap_or(UNDETERMINED))
+                .or_insert_with(|| title.into());
+        }
+
+        if !titles.is_empty() {
+            object.insert("titles".into(), titles.into());
+        }
+
+        let mut properties = Map::new();
+
+        for param in self
+            .params
+            .iter()
+            .filter(|param| !param.name().eq_ignore_ascii_case("title"))
+        {
+            properties
+                .entry(param.name().trim_end_matches('*'))
+                .or_insert_with(|| param.value().as_ref().map(Value::text).into());
+        }
+
+        if !properties.is_empty() {
+            object.insert("properties".into(), properties.into());
+        }
+
+        Some(Json::Object(object))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn roundtrip() {
+        let links = json!([
+            {
+                "rel": "http://openid.net/specs/connect/1.0/issuer",
+                "href": "https://openid.example.com"
+            },
+            {
+                "rel": "copyright",
+                "href": "http://www.example.com/copyright",
+                "type": "text/html",
+                "titles": { "und": "Copyright", "fr": "Droit d'auteur" },
+                "properties": { "http://example.com/p/x": null }
+            }
+        ]);
+        let header = from_links(&links, None).unwrap();
+
+        assert_eq!(header.to_jrd_links(), links);
+    }
+
+    #[test]
+    fn invalid_links() {
+        assert!(from_links(&json!({}), None).is_err());
+        assert!(from_links(&json!([{ "href": "/a" }]), None).is_err());
+        assert!(from_links(&json!([{ "rel": "a", "href": "/a", "titles": [] }]), None).is_err());
+        assert!(parse(r#"{"subject": "acct:a@example.com"}"#)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Language range matching (RFC4647) for the "hreflang" target attribute and
+//! generation of alternate-language link sets.
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::param::Value;
+use crate::parser::LinkBuilder;
+use std::collections::BTreeMap;
+
+/// Whether the basic language range matches the tag (RFC4647 Section 3.3.1).
+///
+/// ```
+/// use linkheader::language::basic_filter;
+///
+/// assert!(basic_filter("de", "de-CH"));
+/// assert!(basic_filter("*", "fr"));
+/// assert!(!basic_filter("de-CH", "de"));
+/// assert!(!basic_filter("de", "den"));
+/// ```
+pub fn basic_filter(range: &str, tag: &str) -> bool {
+    if range == "*" {
+        return true;
+    }
+
+    tag.len() >= range.len()
+        && tag.is_char_boundary(range.len())
+        && tag[..range.len()].eq_ignore_ascii_case(range)
+        && (tag.len() == range.len() || tag[range.len()..].starts_with('-'))
+}
+
+/// Whether the extended language range matches the tag (RFC4647 Section
+/// 3.3.2).
+///
+/// ```
+/// use linkheader::language::extended_filter;
+///
+/// assert!(extended_filter("de-*-DE", "de-Latn-DE"));
+/// assert!(extended_filter("de-DE", "de-Latn-DE-1996"));
+/// assert!(!extended_filter("de-DE", "de-x-DE"));
+/// ```
+pub fn extended_filter(range: &str, tag: &str) -> bool {
+    let mut range = range.split('-');
+    let mut tag = tag.split('-').peekable();
+
+    match (range.next(), tag.next()) {
+        (Some(r), Some(t)) if r == "*" || r.eq_ignore_ascii_case(t) => (),
+        _ => return false,
+    }
+
+    for subtag in range {
+        if subtag == "*" {
+            continue;
+        }
+
+        loop {
+            match tag.next() {
+                None => return false,
+                Some(t) if t.eq_ignore_ascii_case(subtag) => break,
+                Some(t) if t.len() == 1 => return false,
+                Some(_) => (),
+            }
+        }
+    }
+
+    true
+}
+
+/// The ranges to try in order when looking up a tag (RFC4647 Section 3.4),
+/// from the most to the least specific.
+///
+/// ```
+/// use linkheader::language::fallbacks;
+///
+/// assert_eq!(fallbacks("zh-Hant-CN-x-private1"), vec!["zh-Hant-CN-x-private1", "zh-Hant-CN", "zh-Hant", "zh"]);
+/// ```
+pub fn fallbacks(tag: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut rest = tag;
+
+    while !rest.is_empty() {
+        result.push(rest);
+
+        rest = match rest.rfind('-') {
+            Some(idx) => &rest[..idx],
+            None => "",
+        };
+
+        while let Some(idx) = rest.rfind('-') {
+            if rest.len() - idx != 2 {
+                break;
+            }
+
+            rest = &rest[..idx];
+        }
+    }
+
+    result
+}
+
+impl Header {
+    /// Returns the `rel="alternate"` links suitable for the given language
+    /// tag, best first.
+    ///
+    /// Each fallback of the tag (see `fallbacks`) is tried in turn, collecting
+    /// first the links with an exact "hreflang" and then those with a more
+    /// specific one.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</de-at>; rel="alternate"; hreflang="de-AT", </de>; rel="alternate"; hreflang="de", </en>; rel="alternate"; hreflang="en", </de-ch>; rel="alternate"; hreflang="de-CH""#;
+    /// let header = parse(input, None).unwrap();
+    /// let links: Vec<_> = header
+    ///     .alternates_for_language("de-CH")
+    ///     .iter()
+    ///     .map(|link| link.target.as_str())
+    ///     .collect();
+    ///
+    /// assert_eq!(links, vec!["/de-ch", "/de", "/de-at"]);
+    /// ```
+    pub fn alternates_for_language(&self, tag: &str) -> Vec<&Link> {
+        let alternates: Vec<&Link> = self
+            .links()
+            .iter()
+            .filter(|link| link.has_relation("alternate"))
+            .collect();
+        let mut result: Vec<&Link> = vec![];
+
+        for range in fallbacks(tag) {
+            let exact = |link: &Link| {
+                link.lang
+                    .iter()
+                    .any(|value| value.text().eq_ignore_ascii_case(range))
+            };
+            let filtered = |link: &Link| {
+                link.lang
+                    .iter()
+                    .any(|value| basic_filter(range, value.text()))
+            };
+            let candidates = alternates
+                .iter()
+                .filter(|link| exact(link))
+                .chain(alternates.iter().filter(|link| filtered(link)));
+
+            for link in candidates {
+                if !result.iter().any(|other| std::ptr::eq(*other, *link)) {
+                    result.push(link);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Generates the `rel="alternate"` links to every language version of a
+/// resource, e.g. for search engines.
+///
+/// Links are ordered by language tag, compared case-insensitively, with the
+/// optional `x-default` version last. When a language is given more than once
+/// the first URL is kept.
+///
+/// ```
+/// use linkheader::language::alternates;
+/// use std::collections::HashMap;
+///
+/// let mut languages = HashMap::new();
+/// languages.insert("en", "https://example.org/en/");
+/// languages.insert("de-CH", "https://example.org/ch/");
+/// languages.insert("de", "https://example.org/de/");
+///
+/// let header = alternates(languages, Some("https://example.org/"));
+///
+/// assert_eq!(
+///     header.to_string(),
+///     r#"<https://example.org/de/>; rel="alternate"; hreflang="de", <https://example.org/ch/>; rel="alternate"; hreflang="de-CH", <https://example.org/en/>; rel="alternate"; hreflang="en", <https://example.org/>; rel="alternate"; hreflang="x-default""#
+/// );
+/// ```
+pub fn alternates<K, V>(
+    languages: impl IntoIterator<Item = (K, V)>,
+    x_default: Option<&str>,
+) -> Header
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut versions: BTreeMap<String, (String, String)> = BTreeMap::new();
+
+    for (tag, url) in languages {
+        let tag = tag.as_ref().trim();
+
+        if tag.is_empty() || tag.eq_ignore_ascii_case("x-default") {
+            continue;
+        }
+
+        versions
+            .entry(tag.to_lowercase())
+            .or_insert_with(|| (tag.into(), url.as_ref().into()));
+    }
+
+    versions
+        .into_values()
+        .chain(x_default.map(|url| ("x-default".into(), url.into())))
+        .enumerate()
+        .flat_map(|(group, (tag, url))| {
+            let mut builder = LinkBuilder::new(None);
+            builder.set_group(group);
+            builder.set_target(&url);
+            builder.set_rel(Value::Simple("alternate".into()));
+            builder.set_lang(Value::Simple(tag));
+            builder.build()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extended_wildcards() {
+        assert!(extended_filter("*-CH", "de-CH"));
+        assert!(extended_filter("*-CH", "fr-Latn-CH"));
+        assert!(!extended_filter("*-CH", "de-DE"));
+        assert!(!extended_filter("de-Latn", "de"));
+    }
+
+    #[test]
+    fn fallbacks_drop_singletons() {
+        assert_eq!(
+            fallbacks("en-a-bbb-x-a-ccc"),
+            vec!["en-a-bbb-x-a-ccc", "en-a-bbb", "en"]
+        );
+        assert_eq!(fallbacks(""), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn alternates_dedup() {
+        let languages = vec![
+            ("fr", "/fr"),
+            ("FR", "/fr-upper"),
+            ("x-default", "/ignored"),
+            ("en", "/en"),
+        ];
+        let header = alternates(languages, None);
+        let targets: Vec<_> = header.iter().map(|link| link.target.as_str()).collect();
+
+        assert_eq!(targets, vec!["/en", "/fr"]);
+        assert_eq!(
+            header.alternates_for_language("fr-CA")[0].target.as_str(),
+            "/fr"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Linked Data Platform ([LDP 1.0](https://www.w3.org/TR/ldp/)) interaction
+//! models, advertised by servers as `rel="type"` links.
+
+use crate::header::Header;
+use crate::link::Link;
+
+/// The LDP vocabulary namespace.
+pub const NAMESPACE: &str = "http://www.w3.org/ns/ldp#";
+pub const RESOURCE: &str = "http://www.w3.org/ns/ldp#Resource";
+pub const RDF_SOURCE: &str = "http://www.w3.org/ns/ldp#RDFSource";
+pub const NON_RDF_SOURCE: &str = "http://www.w3.org/ns/ldp#NonRDFSource";
+pub const CONTAINER: &str = "http://www.w3.org/ns/ldp#Container";
+pub const BASIC_CONTAINER: &str = "http://www.w3.org/ns/ldp#BasicContainer";
+pub const DIRECT_CONTAINER: &str = "http://www.w3.org/ns/ldp#DirectContainer";
+pub const INDIRECT_CONTAINER: &str = "http://www.w3.org/ns/ldp#IndirectContainer";
+/// The relation type of the link to the constraints a server puts on a
+/// resource (LDP 1.0 Section 4.2.1.6).
+pub const CONSTRAINED_BY: &str = "http://www.w3.org/ns/ldp#constrainedBy";
+
+impl Header {
+    /// The targets of the `rel="type"` links with no anchor, in order, e.g.
+    /// `ldp::BASIC_CONTAINER`.
+    ///
+    /// ```
+    /// use linkheader::{ldp, parse};
+    ///
+    /// let input = r#"<http://www.w3.org/ns/ldp#BasicContainer>; rel="type", <http://www.w3.org/ns/ldp#Resource>; rel="type", </constraints>; rel="http://www.w3.org/ns/ldp#constrainedBy""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(header.ldp_types(), vec![ldp::BASIC_CONTAINER, ldp::RESOURCE]);
+    /// assert!(header.is_ldp_container());
+    /// assert_eq!(header.constrained_by().unwrap().target.as_str(), "/constraints");
+    /// ```
+    pub fn ldp_types(&self) -> Vec<&str> {
+        self.by_relation("type")
+            .into_iter()
+            .filter(|link| link.context.anchor().is_none())
+            .map(|link| link.target.as_str())
+            .collect()
+    }
+
+    /// Whether the resource is any kind of LDP container.
+    pub fn is_ldp_container(&self) -> bool {
+        self.ldp_types().iter().any(|kind| {
+            [
+                CONTAINER,
+                BASIC_CONTAINER,
+                DIRECT_CONTAINER,
+                INDIRECT_CONTAINER,
+            ]
+            .contains(kind)
+        })
+    }
+
+    /// The `ldp:constrainedBy` link, if any.
+    pub fn constrained_by(&self) -> Option<&Link> {
+        self.first_by_relation(CONSTRAINED_BY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn anchored_types() {
+        let input = r#"<http://www.w3.org/ns/ldp#Container>; rel="type"; anchor="/parent", <http://www.w3.org/ns/ldp#RDFSource>; rel="type""#;
+        let header = parse(input, None).unwrap();
+
+        assert_eq!(header.ldp_types(), vec![RDF_SOURCE]);
+        assert!(!header.is_ldp_container());
+    }
+}
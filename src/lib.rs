@@ -4,6 +4,10 @@
 // This file may not be copied, modified, or distributed except
 // according to those terms.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+#[cfg(feature = "std")]
 #[macro_use]
 extern crate failure;
 
@@ -11,15 +15,106 @@ extern crate pest;
 #[macro_use]
 extern crate pest_derive;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "axum")]
+pub mod axum;
+#[cfg(feature = "std")]
+pub mod conformance;
+pub mod context;
+#[cfg(feature = "std")]
+pub mod deprecation;
+#[cfg(feature = "std")]
+pub mod diff;
 pub mod error;
+#[cfg(feature = "std")]
+pub mod feed;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "follow")]
+pub mod follow;
+#[cfg(feature = "json")]
+pub mod hal;
+#[cfg(feature = "har")]
+pub mod har;
+#[cfg(feature = "std")]
+pub mod head;
 pub mod header;
+#[cfg(feature = "headers")]
+mod headers;
+#[cfg(feature = "std")]
+pub mod html;
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "std")]
+pub mod indieweb;
+#[cfg(feature = "json")]
+pub mod jrd;
+#[cfg(feature = "std")]
+pub mod language;
+#[cfg(feature = "std")]
+pub mod ldp;
 pub mod link;
+pub mod link_format;
+#[cfg(feature = "std")]
+pub mod link_template;
+#[cfg(feature = "std")]
+pub mod linkset;
+#[cfg(feature = "std")]
+pub mod lint;
+#[cfg(feature = "std")]
+pub mod media;
+#[cfg(feature = "std")]
+pub mod memento;
+#[cfg(feature = "std")]
+pub mod navigate;
+#[cfg(feature = "std")]
+pub mod negotiation;
+#[cfg(feature = "std")]
+pub mod oembed;
+#[cfg(feature = "std")]
+pub mod pagination;
 pub mod param;
 pub mod parser;
+#[cfg(feature = "std")]
+pub mod preload;
+#[cfg(feature = "pyo3")]
+mod python;
+#[cfg(feature = "std")]
+pub mod query;
+#[cfg(feature = "std")]
+pub mod rdf;
+#[cfg(feature = "std")]
+pub mod redact;
+#[cfg(feature = "reqwest")]
+pub mod reqwest;
+#[cfg(feature = "std")]
+pub mod service;
+#[cfg(feature = "std")]
+pub mod structured;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tonic")]
+mod tonic;
+#[cfg(feature = "tower")]
+pub mod tower;
 pub mod uri;
+#[cfg(feature = "warp")]
+pub mod warp;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+pub mod websub;
 
+pub use context::Context;
 pub use header::Header;
+#[cfg(feature = "std")]
+pub use header::MergePolicy;
 pub use link::{Link, Relation};
 pub use param::{Encoding, Param, Value};
-pub use parser::parse;
+#[cfg(feature = "std")]
+pub use parser::{parse, parse_with_options};
+pub use parser::{parse_without_context, AnchorPolicy, ParserOptions, SchemePolicy};
+#[cfg(feature = "std")]
+pub use query::Query;
 pub use uri::UriRef;
@@ -4,17 +4,225 @@
 // This file may not be copied, modified, or distributed except
 // according to those terms.
 
-use crate::param::{Param, Value};
+use crate::context::Context;
+#[cfg(feature = "std")]
+use crate::error::Result;
+use crate::param::{fmt_param, Param, Value};
 use crate::uri::UriRef;
-use url;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::{self, Display};
+use core::hash::{Hash, Hasher};
 
 /// A link relation type.
 ///
 /// RFC8288 requires a link to have a direct relation type. Reverse relations
 /// are kept as link params but not handled as relation types.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Relation types are compared in a case-insensitive fashion (RFC8288 Section
+/// 2.1).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Relation(String);
 
+impl Relation {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the relation type is expected to appear at most once per
+    /// context, e.g. `canonical` or `next`.
+    pub fn is_singleton(&self) -> bool {
+        SINGLETON_RELATIONS
+            .iter()
+            .any(|rel| self.0.eq_ignore_ascii_case(rel))
+    }
+
+    /// Whether the relation type is an extension relation type, i.e. a URI
+    /// (RFC8288 Section 2.1.2).
+    pub fn is_extension(&self) -> bool {
+        self.0.contains(':')
+    }
+
+    /// Whether the relation type is in the IANA Link Relations registry.
+    ///
+    /// ```
+    /// use linkheader::Relation;
+    ///
+    /// assert!(Relation::from("Preload").is_registered());
+    /// assert!(!Relation::from("sidebar").is_registered());
+    /// ```
+    pub fn is_registered(&self) -> bool {
+        REGISTERED_RELATIONS
+            .iter()
+            .any(|rel| self.0.eq_ignore_ascii_case(rel))
+    }
+}
+
+/// Registered relation types that only make sense once per link context.
+const SINGLETON_RELATIONS: &[&str] = &[
+    "canonical",
+    "first",
+    "last",
+    "next",
+    "prev",
+    "previous",
+    "self",
+];
+
+/// The IANA Link Relations registry.
+///
+/// See <https://www.iana.org/assignments/link-relations/link-relations.xhtml>.
+const REGISTERED_RELATIONS: &[&str] = &[
+    "about",
+    "acl",
+    "alternate",
+    "amphtml",
+    "api-catalog",
+    "appendix",
+    "apple-touch-icon",
+    "apple-touch-startup-image",
+    "archives",
+    "author",
+    "blocked-by",
+    "bookmark",
+    "c2pa-manifest",
+    "canonical",
+    "chapter",
+    "cite-as",
+    "collection",
+    "compression-dictionary",
+    "contents",
+    "convertedfrom",
+    "copyright",
+    "create-form",
+    "current",
+    "deprecation",
+    "describedby",
+    "describes",
+    "disclosure",
+    "dns-prefetch",
+    "duplicate",
+    "edit",
+    "edit-form",
+    "edit-media",
+    "enclosure",
+    "external",
+    "first",
+    "geofeed",
+    "glossary",
+    "help",
+    "hosts",
+    "hub",
+    "ice-server",
+    "icon",
+    "index",
+    "intervalafter",
+    "intervalbefore",
+    "intervalcontains",
+    "intervaldisjoint",
+    "intervalduring",
+    "intervalequals",
+    "intervalfinishedby",
+    "intervalfinishes",
+    "intervalin",
+    "intervalmeets",
+    "intervalmetby",
+    "intervaloverlappedby",
+    "intervaloverlaps",
+    "intervalstartedby",
+    "intervalstarts",
+    "item",
+    "last",
+    "latest-version",
+    "license",
+    "linkset",
+    "lrdd",
+    "manifest",
+    "mask-icon",
+    "me",
+    "media-feed",
+    "memento",
+    "micropub",
+    "modulepreload",
+    "monitor",
+    "monitor-group",
+    "next",
+    "next-archive",
+    "nofollow",
+    "noopener",
+    "noreferrer",
+    "opener",
+    "openid2.local_id",
+    "openid2.provider",
+    "original",
+    "p3pv1",
+    "payment",
+    "pingback",
+    "preconnect",
+    "predecessor-version",
+    "prefetch",
+    "preload",
+    "prerender",
+    "prev",
+    "prev-archive",
+    "preview",
+    "previous",
+    "privacy-policy",
+    "profile",
+    "publication",
+    "related",
+    "replies",
+    "restconf",
+    "ruleinput",
+    "search",
+    "section",
+    "self",
+    "service",
+    "service-desc",
+    "service-doc",
+    "service-meta",
+    "sip-trunking-capability",
+    "sponsored",
+    "start",
+    "status",
+    "stylesheet",
+    "subsection",
+    "successor-version",
+    "sunset",
+    "tag",
+    "terms-of-service",
+    "timegate",
+    "timemap",
+    "type",
+    "ugc",
+    "up",
+    "version-history",
+    "via",
+    "webmention",
+    "working-copy",
+    "working-copy-of",
+];
+
+impl PartialEq for Relation {
+    fn eq(&self, other: &Relation) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for Relation {}
+
+impl Hash for Relation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_ascii_lowercase().hash(state);
+    }
+}
+
 impl From<&str> for Relation {
     fn from(s: &str) -> Relation {
         Relation(s.into())
@@ -28,14 +236,498 @@ impl From<String> for Relation {
 }
 
 /// A link to a target resource.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Link {
     pub target: UriRef,
-    pub context: Option<url::Url>,
+    /// The link context. It is always absent without the `std` feature.
+    pub context: Context,
     pub relation: Option<Relation>,
     pub title: Option<Value>,
     pub lang: Vec<Value>,
     pub media: Option<Value>,
     pub content_type: Option<Value>,
     pub params: Vec<Param>,
+    /// The position of the link-value the link was collected from.
+    ///
+    /// Links sharing a group come from the same link-value with a multi-token
+    /// "rel".
+    pub group: Option<usize>,
+}
+
+impl Link {
+    /// Returns the first param with the given name, compared
+    /// case-insensitively.
+    ///
+    /// Note that "rel", "anchor", "title", "hreflang", "media" and "type" are
+    /// only found here when they are repeated.
+    pub fn param(&self, name: &str) -> Option<&Param> {
+        self.params
+            .iter()
+            .find(|param| param.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns every value of the given param, compared case-insensitively,
+    /// including the "title", "hreflang", "media" and "type" attributes.
+    ///
+    /// Valueless params are skipped.
+    pub fn param_values(&self, name: &str) -> Vec<&Value> {
+        let attributes: Vec<&Value> = match &name.to_lowercase()[..] {
+            "title" => self.title.iter().collect(),
+            "hreflang" => self.lang.iter().collect(),
+            "media" => self.media.iter().collect(),
+            "type" => self.content_type.iter().collect(),
+            _ => vec![],
+        };
+
+        attributes
+            .into_iter()
+            .chain(
+                self.params
+                    .iter()
+                    .filter(|param| param.name().eq_ignore_ascii_case(name))
+                    .filter_map(|param| param.value().as_ref()),
+            )
+            .collect()
+    }
+
+    /// The URIs listed in "profile" params (RFC6906).
+    ///
+    /// A profile param holds a space-separated list of URIs, all of them are
+    /// collected in order.
+    ///
+    /// ```
+    /// use linkheader::{parse, UriRef};
+    ///
+    /// let input = r#"</book.json>; rel="alternate"; profile="http://example.com/profiles/a http://example.com/profiles/b""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.links()[0].profiles(),
+    ///     vec![
+    ///         UriRef::from("http://example.com/profiles/a"),
+    ///         UriRef::from("http://example.com/profiles/b"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn profiles(&self) -> Vec<UriRef> {
+        self.params
+            .iter()
+            .filter(|param| param.name().eq_ignore_ascii_case("profile"))
+            .filter_map(|param| param.value().as_ref())
+            .flat_map(|value| value.text().split_whitespace())
+            .map(UriRef::from)
+            .collect()
+    }
+
+    /// Whether the link relation type is the given one, compared
+    /// case-insensitively.
+    pub fn has_relation(&self, relation: &str) -> bool {
+        self.relation
+            .as_ref()
+            .is_some_and(|rel| rel.as_str().eq_ignore_ascii_case(relation))
+    }
+
+    pub fn set_target(&mut self, target: impl Into<UriRef>) {
+        self.target = target.into();
+    }
+
+    pub fn set_relation(&mut self, relation: impl Into<Relation>) {
+        self.relation = Some(relation.into());
+    }
+
+    pub fn set_title(&mut self, title: impl Into<Value>) {
+        self.title = Some(title.into());
+    }
+
+    /// Every title with its language, if any, in order of appearance.
+    ///
+    /// A link may carry a "title" and several "title*" values. The preferred
+    /// one is kept in `title` and the rest in `params`; this collects all of
+    /// them.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</TheBook/chapter4>; rel="next"; title="next chapter"; title*=UTF-8'de'n%c3%a4chstes%20Kapitel; title*=UTF-8'fr'chapitre%20suivant"#;
+    /// let header = parse(input, None).unwrap();
+    /// let titles = header.links()[0].titles();
+    ///
+    /// assert_eq!(titles.len(), 3);
+    /// assert!(titles.contains(&(Some("de"), "nächstes Kapitel")));
+    /// assert!(titles.contains(&(None, "next chapter")));
+    /// ```
+    pub fn titles(&self) -> Vec<(Option<&str>, &str)> {
+        self.title
+            .iter()
+            .chain(
+                self.params
+                    .iter()
+                    .filter(|param| param.name().eq_ignore_ascii_case("title"))
+                    .filter_map(|param| param.value().as_ref()),
+            )
+            .map(|value| {
+                let language = match value {
+                    Value::Compound {
+                        language: Some(language),
+                        ..
+                    } if !language.is_empty() => Some(&language[..]),
+                    _ => None,
+                };
+
+                (language, value.text())
+            })
+            .collect()
+    }
+
+    /// The title best suited for the given language tag.
+    ///
+    /// The lookup fallbacks of the tag are tried in turn (RFC4647 Section
+    /// 3.4) and, failing those, a title with no language.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</TheBook/chapter4>; rel="next"; title="next chapter"; title*=UTF-8'de'n%c3%a4chstes%20Kapitel"#;
+    /// let header = parse(input, None).unwrap();
+    /// let link = &header.links()[0];
+    ///
+    /// assert_eq!(link.title_for("de-CH"), Some("nächstes Kapitel"));
+    /// assert_eq!(link.title_for("fr"), Some("next chapter"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn title_for(&self, tag: &str) -> Option<&str> {
+        let titles = self.titles();
+
+        crate::language::fallbacks(tag)
+            .into_iter()
+            .find_map(|range| {
+                titles.iter().find(|(language, _)| {
+                    language.is_some_and(|language| language.eq_ignore_ascii_case(range))
+                })
+            })
+            .or_else(|| titles.iter().find(|(language, _)| language.is_none()))
+            .map(|(_, text)| *text)
+    }
+
+    /// Appends a param to the link.
+    ///
+    /// Note that the param is kept as is even if it is a "title", "hreflang",
+    /// "media" or "type".
+    pub fn push_param(&mut self, param: Param) {
+        self.params.push(param);
+    }
+
+    /// Removes every param with the given name, compared case-insensitively,
+    /// and returns them.
+    ///
+    /// The "title", "hreflang", "media" and "type" attributes are removed as
+    /// well.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    /// use linkheader::param::Param;
+    ///
+    /// let input = r#"</TheBook/chapter4>; rel="next"; title="Next"; utm_source="feed""#;
+    /// let mut header = parse(input, None).unwrap();
+    /// let link = &mut header.links_mut()[0];
+    ///
+    /// let removed = link.remove_param("utm_source");
+    /// link.set_title("Chapter 4");
+    /// link.push_param(Param::new("nopush", None));
+    ///
+    /// assert_eq!(removed, vec![Param::new("utm_source", Some("feed".into()))]);
+    /// assert_eq!(link.to_string(), r#"</TheBook/chapter4>; rel="next"; title="Chapter 4"; nopush"#);
+    /// ```
+    pub fn remove_param(&mut self, name: &str) -> Vec<Param> {
+        let name = name.to_lowercase();
+        let mut removed: Vec<Param> = match &name[..] {
+            "title" => self.title.take().into_iter().collect(),
+            "hreflang" => self.lang.drain(..).collect(),
+            "media" => self.media.take().into_iter().collect(),
+            "type" => self.content_type.take().into_iter().collect(),
+            _ => vec![],
+        }
+        .into_iter()
+        .map(|value| Param::new(name.clone(), Some(value)))
+        .collect();
+
+        let (matching, rest) = self
+            .params
+            .drain(..)
+            .partition(|param| param.name().eq_ignore_ascii_case(&name));
+
+        self.params = rest;
+        removed.extend::<Vec<Param>>(matching);
+
+        removed
+    }
+
+    /// Replaces all params with the given name by a single one with the given
+    /// value, keeping the position of the first one.
+    #[cfg(feature = "std")]
+    pub(crate) fn replace_param(&mut self, name: &str, value: Option<Value>) {
+        let position = self
+            .params
+            .iter()
+            .position(|param| param.name().eq_ignore_ascii_case(name))
+            .unwrap_or(self.params.len());
+
+        self.params
+            .retain(|param| !param.name().eq_ignore_ascii_case(name));
+        self.params
+            .insert(position.min(self.params.len()), Param::new(name, value));
+    }
+
+    /// Resolves the target against the given base or, when missing, against
+    /// the default link context.
+    ///
+    /// An "anchor" changes the link context, not the base URI targets resolve
+    /// against (RFC8288 Section 3.2 and Appendix B).
+    ///
+    /// Fails if the target is relative and there is nothing to resolve it
+    /// against.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let context = url::Url::parse("http://example.com/TheBook/chapter3").ok();
+    /// let header = parse(r#"</TheBook/chapter4>; rel="next""#, context).unwrap();
+    /// let target = header.links()[0].resolve_target(None).unwrap();
+    ///
+    /// assert_eq!(target.as_str(), "http://example.com/TheBook/chapter4");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn resolve_target(&self, base: Option<&url::Url>) -> Result<url::Url> {
+        self.target.to_url(base.or(self.context.base()))
+    }
+
+    /// Parses the "type" attribute as a media type.
+    ///
+    /// Returns `None` when there is no "type" or it is not a valid media type.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</book.json>; rel="alternate"; type="application/json; charset=utf-8""#;
+    /// let header = parse(input, None).unwrap();
+    /// let media_type = header.links()[0].media_type().unwrap();
+    ///
+    /// assert_eq!(media_type.essence_str(), "application/json");
+    /// ```
+    #[cfg(feature = "mime")]
+    pub fn media_type(&self) -> Option<mime::Mime> {
+        self.content_type.as_ref()?.text().parse().ok()
+    }
+
+    /// Parses every "hreflang" value as a validated language tag.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<https://ca.example.org>; rel="alternate"; hreflang="ca"; hreflang="ca-ES-valencia"; hreflang="en_GB""#;
+    /// let header = parse(input, None).unwrap();
+    /// let tags = header.links()[0].language_tags();
+    ///
+    /// assert!(tags[0].is_ok());
+    /// assert!(tags[1].is_ok());
+    /// assert!(tags[2].is_err());
+    /// ```
+    #[cfg(feature = "language-tags")]
+    pub fn language_tags(&self) -> Vec<Result<language_tags::LanguageTag>> {
+        self.lang
+            .iter()
+            .map(|value| crate::param::parse_language_tag(value.text()))
+            .collect()
+    }
+
+    /// Whether both the target and the context, when present, share origin
+    /// with the given URL.
+    ///
+    /// The target is resolved against the given URL. Links with an
+    /// unresolvable target are never same-origin.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let base = url::Url::parse("https://example.org/books").unwrap();
+    /// let input = r#"</books?page=2>; rel="next", <https://cdn.example.org/app.js>; rel="preload""#;
+    /// let header = parse(input, Some(base.clone())).unwrap();
+    ///
+    /// assert!(header.links()[0].is_same_origin(&base));
+    /// assert!(!header.links()[1].is_same_origin(&base));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn is_same_origin(&self, url: &url::Url) -> bool {
+        let origin = url.origin();
+        let target = match self.resolve_target(Some(url)) {
+            Ok(target) => target,
+            Err(_) => return false,
+        };
+
+        target.origin() == origin
+            && self
+                .context
+                .url()
+                .is_none_or(|context| context.origin() == origin)
+    }
+
+    /// Whether the target scheme is one of the given ones, compared
+    /// case-insensitively.
+    ///
+    /// Relative targets take the scheme from the default context, which they
+    /// resolve against, and are allowed when there is no context.
+    pub(crate) fn has_scheme_in(&self, schemes: &[&str]) -> bool {
+        let scheme = self.target.scheme();
+        #[cfg(feature = "std")]
+        let scheme = scheme.or_else(|| self.context.base().map(|url| url.scheme()));
+
+        match scheme {
+            Some(scheme) => schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)),
+            None => true,
+        }
+    }
+
+    /// The identity of the link for comparison purposes: equivalent target,
+    /// relation type and context, regardless of any other attribute.
+    #[cfg(feature = "std")]
+    pub(crate) fn equivalence_key(&self) -> (UriRef, Option<Relation>, Option<url::Url>) {
+        (
+            self.target.normalize(),
+            self.relation.clone(),
+            self.context.url().cloned(),
+        )
+    }
+
+    /// The target attributes and params as a single list of params, in
+    /// serialisation order.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let header = parse(r#"</2>; nopush; rel="next"; title="Two""#, None).unwrap();
+    /// let names: Vec<_> = header.links()[0].attributes().iter().map(|param| param.name().to_string()).collect();
+    ///
+    /// assert_eq!(names, vec!["title", "nopush"]);
+    /// ```
+    pub fn attributes(&self) -> Vec<Param> {
+        self.title
+            .iter()
+            .map(|value| Param::new("title", Some(value.clone())))
+            .chain(
+                self.lang
+                    .iter()
+                    .map(|value| Param::new("hreflang", Some(value.clone()))),
+            )
+            .chain(
+                self.media
+                    .iter()
+                    .map(|value| Param::new("media", Some(value.clone()))),
+            )
+            .chain(
+                self.content_type
+                    .iter()
+                    .map(|value| Param::new("type", Some(value.clone()))),
+            )
+            .chain(self.params.iter().cloned())
+            .collect()
+    }
+
+    /// Whether both links only differ by their relation type, i.e. they can be
+    /// serialised as a single link-value.
+    pub(crate) fn is_sibling(&self, other: &Link) -> bool {
+        self.group.is_some()
+            && self.group == other.group
+            && self.target == other.target
+            && self.context == other.context
+            && self.title == other.title
+            && self.lang == other.lang
+            && self.media == other.media
+            && self.content_type == other.content_type
+            && self.params == other.params
+    }
+
+    /// Writes the link-value for this link using the given relation types
+    /// instead of its own.
+    pub(crate) fn fmt_with_relations(
+        &self,
+        formatter: &mut fmt::Formatter,
+        relations: &[&Relation],
+    ) -> fmt::Result {
+        write!(formatter, "<{}>", self.target)?;
+
+        if !relations.is_empty() {
+            let rel: Vec<&str> = relations.iter().map(|rel| rel.as_str()).collect();
+            write!(formatter, "; ")?;
+            fmt_param(formatter, "rel", Some(&rel.join(" ").into()))?;
+        }
+
+        if let Some(anchor) = self.context.anchor() {
+            write!(formatter, "; ")?;
+            fmt_param(formatter, "anchor", Some(&anchor.into()))?;
+        }
+
+        let attributes = self
+            .title
+            .iter()
+            .map(|value| ("title", value))
+            .chain(self.lang.iter().map(|value| ("hreflang", value)))
+            .chain(self.media.iter().map(|value| ("media", value)))
+            .chain(self.content_type.iter().map(|value| ("type", value)));
+
+        for (name, value) in attributes {
+            write!(formatter, "; ")?;
+            fmt_param(formatter, name, Some(value))?;
+        }
+
+        for param in &self.params {
+            write!(formatter, "; {}", param)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Serialises the link as a single link-value.
+///
+/// The context is only serialised when it comes from an "anchor".
+impl Display for Link {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let relations: Vec<&Relation> = self.relation.iter().collect();
+
+        self.fmt_with_relations(formatter, &relations)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::parser::{parse, parse_with_options, ParserOptions, SchemePolicy};
+
+    #[test]
+    fn anchors_do_not_change_the_base() {
+        let context = url::Url::parse("https://example.org/page").ok();
+        let input = r#"</next?p=2>; rel="next"; anchor="https://evil.example/x""#;
+        let header = parse(input, context).unwrap();
+        let link = &header.links()[0];
+
+        assert_eq!(
+            link.context.url().unwrap().as_str(),
+            "https://evil.example/x"
+        );
+        assert_eq!(
+            link.resolve_target(None).unwrap().as_str(),
+            "https://example.org/next?p=2"
+        );
+    }
+
+    #[test]
+    fn relative_targets_take_the_base_scheme() {
+        let context = url::Url::parse("http://example.org/").ok();
+        let input = r#"</a>; rel="next"; anchor="https://example.org/""#;
+        let options = ParserOptions::new().allowed_schemes(&["https"], SchemePolicy::Drop);
+        let header = parse_with_options(input, context, &options).unwrap();
+
+        assert!(header.is_empty());
+    }
 }
@@ -0,0 +1,258 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! CoRE Link Format (RFC6690), i.e. `application/link-format` payloads as
+//! returned by CoAP `/.well-known/core` resources.
+
+use crate::error::Result;
+use crate::header::Header;
+use crate::link::Link;
+use crate::param::{fmt_param, Value};
+#[cfg(feature = "std")]
+use crate::parser::parse_linkset_with_options;
+use crate::parser::{parse_rule, LinkBuilder, Rule};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Parses an `application/link-format` payload.
+///
+/// The syntax is the same as the `Link` header field with no whitespace
+/// required. An empty payload is an empty header. Line breaks between
+/// link-values are tolerated.
+///
+/// The default context is the origin of the given URL, i.e. the URL of the
+/// resource the payload was retrieved from (RFC6690 Section 2.1).
+///
+/// ```
+/// use linkheader::link_format;
+///
+/// let input = r#"</sensors/temp>;rt="temperature-c";if="sensor",</sensors/light>;rt="light-lux";if="sensor""#;
+/// let url = url::Url::parse("coap://node.example/.well-known/core").ok();
+/// let header = link_format::parse(input, url).unwrap();
+/// let target = header.links()[1].resolve_target(None).unwrap();
+///
+/// assert_eq!(header.len(), 2);
+/// assert_eq!(target.as_str(), "coap://node.example/sensors/light");
+/// assert_eq!(header.links()[0].context.url().unwrap().as_str(), "coap://node.example/");
+/// ```
+#[cfg(feature = "std")]
+pub fn parse(input: &str, url: Option<url::Url>) -> Result<Header> {
+    let context = url.map(|url| url.join("/").unwrap_or(url));
+
+    parse_linkset_with_options(input, context, &Default::default())
+}
+
+/// Parses an `application/link-format` payload with no known context, as
+/// `parse(input, None)` does.
+///
+/// This is the entry point without the `std` feature, e.g. on constrained
+/// CoAP nodes, where "anchor" params are kept as ordinary params.
+///
+/// ```
+/// use linkheader::link_format;
+///
+/// let header = link_format::parse_without_context(r#"</sensors/temp>;rt="temperature-c";anchor="/node""#).unwrap();
+///
+/// assert_eq!(header.links()[0].resource_types(), vec!["temperature-c"]);
+/// ```
+pub fn parse_without_context(input: &str) -> Result<Header> {
+    parse_rule(
+        Rule::linkset,
+        input,
+        LinkBuilder::default(),
+        &Default::default(),
+    )
+}
+
+/// CoRE target attributes (RFC6690 Section 3 and RFC7641 Section 6).
+///
+/// ```
+/// use linkheader::link_format;
+///
+/// let input = r#"</sensors/temp>;rt="temperature-c oic.r.temperature";if="sensor";ct="0 50";sz=32;obs"#;
+/// let header = link_format::parse(input, None).unwrap();
+/// let link = &header.links()[0];
+///
+/// assert_eq!(link.resource_types(), vec!["temperature-c", "oic.r.temperature"]);
+/// assert_eq!(link.interfaces(), vec!["sensor"]);
+/// assert_eq!(link.content_formats(), vec![0, 50]);
+/// assert_eq!(link.size(), Some(32));
+/// assert!(link.is_observable());
+/// ```
+impl Link {
+    /// The resource types ("rt"). Every "rt" param can hold a
+    /// whitespace-separated list.
+    pub fn resource_types(&self) -> Vec<&str> {
+        self.tokens("rt")
+    }
+
+    /// The interface descriptions ("if"). Every "if" param can hold a
+    /// whitespace-separated list.
+    pub fn interfaces(&self) -> Vec<&str> {
+        self.tokens("if")
+    }
+
+    /// The estimated maximum size of the target in bytes ("sz"), if given and
+    /// numeric.
+    pub fn size(&self) -> Option<u64> {
+        self.param_values("sz")
+            .first()
+            .and_then(|value| value.text().trim().parse().ok())
+    }
+
+    /// The CoAP content-format codes of the target ("ct"). Every "ct" param
+    /// can hold a whitespace-separated list (RFC7252 Section 7.2.1).
+    /// Non-numeric codes are skipped.
+    pub fn content_formats(&self) -> Vec<u16> {
+        self.tokens("ct")
+            .into_iter()
+            .filter_map(|token| token.parse().ok())
+            .collect()
+    }
+
+    /// Whether the target can be observed ("obs").
+    pub fn is_observable(&self) -> bool {
+        self.param("obs").is_some()
+    }
+
+    fn tokens(&self, name: &str) -> Vec<&str> {
+        self.param_values(name)
+            .into_iter()
+            .flat_map(|value| value.text().split_whitespace())
+            .collect()
+    }
+}
+
+impl Header {
+    /// The links with the given resource type ("rt"), compared exactly as
+    /// resource types are opaque strings.
+    ///
+    /// ```
+    /// use linkheader::link_format;
+    ///
+    /// let input = r#"</s/1>;rt="temperature-c",</s/2>;rt="light-lux",</s/3>;rt="x temperature-c""#;
+    /// let header = link_format::parse(input, None).unwrap();
+    ///
+    /// assert_eq!(header.by_resource_type("temperature-c").len(), 2);
+    /// ```
+    pub fn by_resource_type(&self, resource_type: &str) -> Vec<&Link> {
+        self.iter()
+            .filter(|link| link.resource_types().contains(&resource_type))
+            .collect()
+    }
+
+    /// The links with the given interface description ("if"), compared
+    /// exactly.
+    pub fn by_interface(&self, interface: &str) -> Vec<&Link> {
+        self.iter()
+            .filter(|link| link.interfaces().contains(&interface))
+            .collect()
+    }
+}
+
+/// The links of a header written as an `application/link-format` payload.
+/// See `Header::to_link_format`.
+pub struct LinkFormat<'a>(&'a Header);
+
+impl Header {
+    /// Serialises the header as an `application/link-format` payload.
+    ///
+    /// Link-values and params are written with no whitespace and numeric
+    /// values, e.g. "ct" or "sz", are written unquoted.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let header = parse(r#"</sensors/temp>; rt="temperature-c"; ct=0; obs, </sensors>; rel="hosts""#, None).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.to_link_format().to_string(),
+    ///     r#"</sensors/temp>;rt="temperature-c";ct=0;obs,</sensors>;rel="hosts""#
+    /// );
+    /// ```
+    pub fn to_link_format(&self) -> LinkFormat<'_> {
+        LinkFormat(self)
+    }
+}
+
+impl fmt::Display for LinkFormat<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, group) in self.0.groups().into_iter().enumerate() {
+            if idx > 0 {
+                write!(formatter, ",")?;
+            }
+
+            let link = &group[0];
+            let relations: Vec<&str> = group
+                .iter()
+                .filter_map(|link| link.relation.as_ref())
+                .map(|relation| relation.as_str())
+                .collect();
+
+            write!(formatter, "<{}>", link.target)?;
+
+            if !relations.is_empty() {
+                write!(formatter, ";")?;
+                fmt_param(formatter, "rel", Some(&relations.join(" ").into()))?;
+            }
+
+            if let Some(anchor) = link.context.anchor() {
+                write!(formatter, ";")?;
+                fmt_param(formatter, "anchor", Some(&anchor.into()))?;
+            }
+
+            for param in link.attributes() {
+                write!(formatter, ";")?;
+
+                match param.value() {
+                    Some(Value::Simple(value))
+                        if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) =>
+                    {
+                        write!(formatter, "{}={}", param.name(), value)?
+                    }
+                    value => fmt_param(formatter, param.name(), value.as_ref())?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_payload() {
+        assert!(parse_without_context("").unwrap().is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn repeated_attributes() {
+        let header = parse(r#"</a>;if="sensor";if="core.s core.a";ct=abc;ct=40"#, None).unwrap();
+        let link = &header.links()[0];
+
+        assert_eq!(link.interfaces(), vec!["sensor", "core.s", "core.a"]);
+        assert_eq!(link.content_formats(), vec![40]);
+        assert_eq!(header.by_interface("core.a").len(), 1);
+        assert!(!link.is_observable());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn roundtrip() {
+        let input = "</a>;rel=\"item\";anchor=\"/c\";title=\"A, B\",\n</b>;sz=512";
+        let header = parse(input, None).unwrap();
+
+        assert_eq!(
+            header.to_link_format().to_string(),
+            r#"</a>;rel="item";anchor="/c";title="A, B",</b>;sz=512"#
+        );
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! The `Link-Template` header field (RFC9652): links whose targets are URI
+//! Templates (RFC6570).
+//!
+//! Unlike `Link`, the field is a Structured Field List (RFC9651) where every
+//! member is a String with the target attributes as parameters.
+
+use crate::error::{ParserError, Result};
+use crate::param::{Param, Value};
+use crate::structured::{self, BareItem, Member};
+use std::fmt;
+
+/// A templated link.
+///
+/// ```
+/// use linkheader::link_template::LinkTemplate;
+/// use linkheader::param::Param;
+///
+/// let mut template = LinkTemplate::new("/widgets/{widget_id}");
+/// template.params.push(Param::new("rel", Some("https://example.org/rel/widget".into())));
+/// template.params.push(Param::new("var-base", Some("https://example.org/vars/".into())));
+///
+/// assert_eq!(
+///     template.to_string(),
+///     r#""/widgets/{widget_id}"; rel="https://example.org/rel/widget"; var-base="https://example.org/vars/""#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkTemplate {
+    /// The URI Template of the target.
+    pub template: String,
+    /// The target attributes in order, including "rel", "anchor" and
+    /// "var-base".
+    pub params: Vec<Param>,
+}
+
+impl LinkTemplate {
+    pub fn new(template: impl Into<String>) -> LinkTemplate {
+        LinkTemplate {
+            template: template.into(),
+            params: vec![],
+        }
+    }
+
+    /// Returns the first param with the given name, compared
+    /// case-insensitively.
+    pub fn param(&self, name: &str) -> Option<&Param> {
+        self.params
+            .iter()
+            .find(|param| param.name().eq_ignore_ascii_case(name))
+    }
+
+    /// The relation types given by the "rel" params.
+    pub fn relations(&self) -> Vec<&str> {
+        self.params
+            .iter()
+            .filter(|param| param.name().eq_ignore_ascii_case("rel"))
+            .filter_map(|param| param.value().as_ref())
+            .flat_map(|value| value.text().split_whitespace())
+            .collect()
+    }
+
+    /// The "anchor" param, itself a URI Template.
+    pub fn anchor(&self) -> Option<&str> {
+        Some(self.param("anchor")?.value().as_ref()?.text())
+    }
+
+    /// The "var-base" param, the URI the variable names are resolved against
+    /// to identify their semantics (RFC9652 Section 2.1).
+    pub fn var_base(&self) -> Option<&str> {
+        Some(self.param("var-base")?.value().as_ref()?.text())
+    }
+
+    /// The variable names of the target template, in order and with no
+    /// operators nor modifiers.
+    ///
+    /// ```
+    /// use linkheader::link_template;
+    ///
+    /// let templates = link_template::parse(r#""/books{/id}{?page,per_page:3}{&tags*}"; rel="item""#).unwrap();
+    ///
+    /// assert_eq!(templates[0].variables(), vec!["id", "page", "per_page", "tags"]);
+    /// ```
+    pub fn variables(&self) -> Vec<&str> {
+        self.template
+            .split('{')
+            .skip(1)
+            .filter_map(|expression| expression.split_once('}'))
+            .flat_map(|(expression, _)| {
+                expression
+                    .trim_start_matches(|c| "+#./;?&=,!@|".contains(c))
+                    .split(',')
+            })
+            .map(|variable| variable.split([':', '*']).next().unwrap_or(variable))
+            .filter(|variable| !variable.is_empty())
+            .collect()
+    }
+
+    /// Resolves a variable name against "var-base", if any.
+    ///
+    /// ```
+    /// use linkheader::link_template;
+    ///
+    /// let templates = link_template::parse(r#""/widgets/{widget_id}"; rel="item"; var-base="https://example.org/vars/""#).unwrap();
+    /// let uri = templates[0].variable_uri("widget_id").unwrap().unwrap();
+    ///
+    /// assert_eq!(uri.as_str(), "https://example.org/vars/widget_id");
+    /// ```
+    pub fn variable_uri(&self, name: &str) -> Option<Result<url::Url>> {
+        let base = self.var_base()?;
+
+        Some(
+            url::Url::parse(base)
+                .and_then(|base| base.join(name))
+                .map_err(Into::into),
+        )
+    }
+}
+
+/// Writes the link template as a Structured Field List member.
+///
+/// Values are written as Strings, or Display Strings when they are not ASCII,
+/// and params with no value as `true`. Param names are lowercased.
+impl fmt::Display for LinkTemplate {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        structured::fmt_string(formatter, &self.template)?;
+
+        for param in &self.params {
+            write!(formatter, "; {}", param.name().to_lowercase())?;
+
+            if let Some(value) = param.value() {
+                write!(formatter, "=")?;
+                structured::fmt_string(formatter, value.text())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a `Link-Template` header field value.
+///
+/// Members must be Strings. Parameter values are kept as text, `true` as a
+/// param with no value and `false` is left out.
+///
+/// ```
+/// use linkheader::link_template;
+///
+/// let input = r#""/{username}"; rel="item", "/search{?q}"; rel="search"; title=%"b%c3%basqueda""#;
+/// let templates = link_template::parse(input).unwrap();
+///
+/// assert_eq!(templates.len(), 2);
+/// assert_eq!(templates[0].template, "/{username}");
+/// assert_eq!(templates[1].relations(), vec!["search"]);
+/// assert_eq!(templates[1].param("title").unwrap().value(), &Some("búsqueda".into()));
+/// ```
+pub fn parse(input: &str) -> Result<Vec<LinkTemplate>> {
+    structured::parse_list(input)?
+        .into_iter()
+        .map(|member| {
+            let item = match member {
+                Member::Item(item) => item,
+                Member::InnerList(..) => {
+                    return Err(invalid("expected a string, not an inner list"))
+                }
+            };
+            let template = match item.bare {
+                BareItem::String(template) => template,
+                _ => return Err(invalid("expected a string")),
+            };
+            let params = item
+                .params
+                .into_iter()
+                .filter(|(_, value)| *value != BareItem::Boolean(false))
+                .map(|(name, value)| Param::new(name, value.text().map(Value::from)))
+                .collect();
+
+            Ok(LinkTemplate { template, params })
+        })
+        .collect()
+}
+
+/// Serialises link templates as a `Link-Template` header field value.
+///
+/// ```
+/// use linkheader::link_template;
+///
+/// let input = r#""/{username}";   rel="item","/search{?q}";rel="search""#;
+/// let templates = link_template::parse(input).unwrap();
+///
+/// assert_eq!(link_template::format(&templates), r#""/{username}"; rel="item", "/search{?q}"; rel="search""#);
+/// ```
+pub fn format(templates: &[LinkTemplate]) -> String {
+    templates
+        .iter()
+        .map(|template| template.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn invalid(message: &str) -> failure::Error {
+    ParserError::InvalidStructuredField(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let input =
+            r#""/a{?q}"; rel="next prefetch"; anchor="/x{id}"; nopush; title="\"A\""; hidden=?0"#;
+        let templates = parse(input).unwrap();
+
+        assert_eq!(templates[0].relations(), vec!["next", "prefetch"]);
+        assert_eq!(templates[0].anchor(), Some("/x{id}"));
+        assert!(templates[0].param("hidden").is_none());
+        assert_eq!(
+            format(&templates),
+            r#""/a{?q}"; rel="next prefetch"; anchor="/x{id}"; nopush; title="\"A\"""#
+        );
+        assert_eq!(parse(&format(&templates)).unwrap(), templates);
+    }
+
+    #[test]
+    fn invalid_members() {
+        assert!(parse(r#"</a>; rel="item""#).is_err());
+        assert!(parse(r#"("/a"); rel="item""#).is_err());
+        assert!(parse("a; rel=item").is_err());
+        assert!(parse(r#"%"/a"; rel="item""#).is_err());
+        assert!(parse("").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,438 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Linksets (RFC9264): sets of links spanning multiple contexts.
+//!
+//! The `application/linkset+json` format requires the `json` feature.
+
+use crate::context::Context;
+#[cfg(feature = "json")]
+use crate::error::ParserError;
+use crate::error::Result;
+use crate::header::Header;
+#[cfg(feature = "json")]
+use crate::link::Link;
+#[cfg(feature = "json")]
+use crate::param::{Encoding, Param, Value};
+#[cfg(feature = "json")]
+use crate::parser::LinkBuilder;
+#[cfg(feature = "json")]
+use serde_json::{json, Map, Value as Json};
+use std::fmt;
+
+/// Parses an `application/linkset` document (RFC9264 Section 4.1).
+///
+/// The syntax is the same as the `Link` header field but link-values and
+/// params can be split across lines. The given context is the linkset
+/// resource URL.
+///
+/// ```
+/// use linkheader::linkset;
+///
+/// let input = r#"<https://authors.example.net/johndoe>
+///    ; rel="author"
+///    ; type="application/rdf+xml"
+///    ; anchor="https://example.org/resource1",
+///  <https://example.org/resource1?version=3>
+///    ; rel="latest-version"
+///    ; type="text/html"
+///    ; anchor="https://example.org/resource1"
+/// "#;
+/// let header = linkset::parse(input, None).unwrap();
+///
+/// assert_eq!(header.links().len(), 2);
+/// assert_eq!(header.links()[1].context.anchor(), Some("https://example.org/resource1"));
+/// ```
+pub fn parse(input: &str, context: Option<url::Url>) -> Result<Header> {
+    crate::parser::parse_linkset_with_options(input, context, &Default::default())
+}
+
+/// The link-values of a header written one per line as an
+/// `application/linkset` document. See `Header::to_linkset`.
+pub struct Linkset<'a>(&'a Header);
+
+impl Header {
+    /// Serialises the header as an `application/linkset` document, one
+    /// link-value per line.
+    ///
+    /// Links inheriting the default context get it as an explicit "anchor"
+    /// so the document is self-contained.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let context = url::Url::parse("https://example.org/").ok();
+    /// let header = parse(r#"</1>; rel="prev", </3>; rel="next"; anchor="/2""#, context).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.to_linkset().to_string(),
+    ///     "</1>; rel=\"prev\"; anchor=\"https://example.org/\",\n</3>; rel=\"next\"; anchor=\"/2\"\n"
+    /// );
+    /// ```
+    pub fn to_linkset(&self) -> Linkset<'_> {
+        Linkset(self)
+    }
+}
+
+impl fmt::Display for Linkset<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut header = self.0.clone();
+
+        for link in header.links_mut() {
+            if let Context::Inherited(url) = &link.context {
+                link.context = Context::anchored(Some(url.clone()), url.as_str());
+            }
+        }
+
+        if !header.is_empty() {
+            header.fmt_with_separator(formatter, ",\n")?;
+            writeln!(formatter)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses an `application/linkset+json` document (RFC9264 Section 4.2).
+///
+/// The given context is the linkset resource URL. It resolves relative
+/// anchors and is the context of link context objects with no anchor.
+///
+/// ```
+/// use linkheader::linkset;
+///
+/// let input = r#"{
+///   "linkset": [
+///     {
+///       "anchor": "https://example.org/article",
+///       "author": [{ "href": "https://example.org/authors/1", "title*": [{ "value": "Autorin", "language": "de" }] }],
+///       "alternate": [{ "href": "/article.de", "hreflang": ["de"], "type": "text/html" }]
+///     }
+///   ]
+/// }"#;
+/// let header = linkset::parse_json(input, None).unwrap();
+/// let author = header.by_relation("author")[0];
+///
+/// assert_eq!(header.links().len(), 2);
+/// assert_eq!(author.context.url().unwrap().as_str(), "https://example.org/article");
+/// assert_eq!(author.title_for("de"), Some("Autorin"));
+/// ```
+#[cfg(feature = "json")]
+pub fn parse_json(input: &str, context: Option<url::Url>) -> Result<Header> {
+    let document: Json = serde_json::from_str(input)?;
+    let contexts = document
+        .get("linkset")
+        .and_then(Json::as_array)
+        .ok_or_else(|| invalid("expected a \"linkset\" array"))?;
+    let mut links = vec![];
+    let mut group = 0;
+
+    for object in contexts {
+        let object = object
+            .as_object()
+            .ok_or_else(|| invalid("expected a link context object"))?;
+        let anchor = match object.get("anchor") {
+            Some(anchor) => Some(
+                anchor
+                    .as_str()
+                    .ok_or_else(|| invalid("expected \"anchor\" to be a string"))?,
+            ),
+            None => None,
+        };
+
+        for (relation, targets) in object.iter().filter(|(name, _)| *name != "anchor") {
+            let targets = targets
+                .as_array()
+                .ok_or_else(|| invalid("expected an array of target objects"))?;
+
+            for target in targets {
+                let mut builder = LinkBuilder::new(context.clone());
+                builder.set_group(group);
+                builder.set_rel(Value::Simple(relation.clone()));
+
+                if let Some(anchor) = anchor {
+                    builder.set_anchor(anchor.into());
+                }
+
+                collect_target(&mut builder, target)?;
+                links.extend(builder.build());
+                group += 1;
+            }
+        }
+    }
+
+    Ok(Header::from(links))
+}
+
+#[cfg(feature = "json")]
+fn collect_target(builder: &mut LinkBuilder, target: &Json) -> Result<()> {
+    let target = target
+        .as_object()
+        .ok_or_else(|| invalid("expected a target object"))?;
+    let href = target
+        .get("href")
+        .and_then(Json::as_str)
+        .ok_or_else(|| invalid("expected a target object with an \"href\" string"))?;
+
+    builder.set_target(href);
+
+    for (name, value) in target.iter().filter(|(name, _)| *name != "href") {
+        let values = attribute_values(name, value)?;
+
+        for value in values {
+            match &name[..] {
+                "title" | "title*" => builder.set_title(value),
+                "hreflang" => builder.set_lang(value),
+                "media" => builder.set_media(value),
+                "type" => builder.set_type(value),
+                _ => builder.add_param(Param::new(name.trim_end_matches('*'), Some(value))),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a target attribute: a string, an array of strings or, for star
+/// attributes, an array of internationalised objects.
+#[cfg(feature = "json")]
+fn attribute_values(name: &str, value: &Json) -> Result<Vec<Value>> {
+    let items = match value {
+        Json::String(text) => return Ok(vec![Value::Simple(text.clone())]),
+        Json::Array(items) => items,
+        _ => return Err(invalid(&format!("unexpected value for {:?}", name))),
+    };
+
+    items
+        .iter()
+        .map(|item| match item {
+            Json::String(text) if !name.ends_with('*') => Ok(Value::Simple(text.clone())),
+            Json::Object(object) if name.ends_with('*') => {
+                let value = object
+                    .get("value")
+                    .and_then(Json::as_str)
+                    .ok_or_else(|| invalid(&format!("expected a \"value\" in {:?}", name)))?;
+
+                Ok(Value::Compound {
+                    encoding: Encoding::Utf8,
+                    language: object
+                        .get("language")
+                        .and_then(Json::as_str)
+                        .map(|language| language.to_string()),
+                    value: value.into(),
+                })
+            }
+            _ => Err(invalid(&format!("unexpected value for {:?}", name))),
+        })
+        .collect()
+}
+
+#[cfg(feature = "json")]
+fn invalid(message: &str) -> failure::Error {
+    ParserError::InvalidLinkset(message.into()).into()
+}
+
+#[cfg(feature = "json")]
+impl Header {
+    /// Converts the header into an `application/linkset+json` document
+    /// (RFC9264 Section 4.2).
+    ///
+    /// Links are grouped by context in order of appearance. The context is
+    /// written as "anchor" when known. Links with no relation type or with the
+    /// relation type "anchor" are left out as they cannot be represented, and
+    /// so are "href" params.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    /// use serde_json::json;
+    ///
+    /// let context = url::Url::parse("https://example.org/").ok();
+    /// let header = parse(r#"</next>; rel="next"; title*=UTF-8'de'n%c3%a4chste; hreflang="de""#, context).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.to_linkset_json(),
+    ///     json!({
+    ///         "linkset": [{
+    ///             "anchor": "https://example.org/",
+    ///             "next": [{
+    ///                 "href": "/next",
+    ///                 "title*": [{ "value": "nächste", "language": "de" }],
+    ///                 "hreflang": ["de"],
+    ///             }],
+    ///         }],
+    ///     })
+    /// );
+    /// ```
+    pub fn to_linkset_json(&self) -> Json {
+        let mut contexts: Vec<(&Context, Map<String, Json>)> = vec![];
+
+        for link in self.links() {
+            let relation = match &link.relation {
+                Some(relation) if relation.as_str() != "anchor" => relation.as_str(),
+                _ => continue,
+            };
+            let idx = match contexts
+                .iter()
+                .position(|(context, _)| **context == link.context)
+            {
+                Some(idx) => idx,
+                None => {
+                    let mut object = Map::new();
+
+                    if let Some(anchor) = context_anchor(&link.context) {
+                        object.insert("anchor".into(), anchor.into());
+                    }
+
+                    contexts.push((&link.context, object));
+                    contexts.len() - 1
+                }
+            };
+
+            push_item(&mut contexts[idx].1, relation.into(), target_object(link));
+        }
+
+        let contexts: Vec<Json> = contexts
+            .into_iter()
+            .map(|(_, object)| Json::Object(object))
+            .collect();
+
+        json!({ "linkset": contexts })
+    }
+}
+
+/// The anchor to write for the context: the resolved URL when known,
+/// otherwise the anchor as given.
+#[cfg(feature = "json")]
+fn context_anchor(context: &Context) -> Option<String> {
+    match context.url() {
+        Some(url) => Some(url.to_string()),
+        None => context.anchor().map(|anchor| anchor.to_string()),
+    }
+}
+
+#[cfg(feature = "json")]
+fn target_object(link: &Link) -> Json {
+    let mut object = Map::new();
+
+    object.insert("href".into(), link.target.as_str().into());
+
+    for param in link.attributes() {
+        let name = param.name().to_lowercase();
+
+        match (&name[..], param.into_value()) {
+            ("href", _) => (),
+            ("title", Some(Value::Simple(text)))
+            | ("media", Some(Value::Simple(text)))
+            | ("type", Some(Value::Simple(text)))
+                if !object.contains_key(&name) =>
+            {
+                object.insert(name, text.into());
+            }
+            (
+                _,
+                Some(Value::Compound {
+                    language, value, ..
+                }),
+            ) => {
+                let mut item = Map::new();
+                item.insert("value".into(), value.into());
+
+                if let Some(language) = language.filter(|language| !language.is_empty()) {
+                    item.insert("language".into(), language.into());
+                }
+
+                push_item(&mut object, format!("{}*", name), Json::Object(item));
+            }
+            (_, value) => {
+                let text = value.map_or(String::new(), |value| value.text().to_string());
+
+                push_item(&mut object, name, text.into());
+            }
+        }
+    }
+
+    Json::Object(object)
+}
+
+/// Appends an item to an array member. A single-valued member already
+/// present is turned into an array.
+#[cfg(feature = "json")]
+fn push_item(object: &mut Map<String, Json>, name: String, item: Json) {
+    let member = object.entry(name).or_insert_with(|| json!([]));
+
+    if !member.is_array() {
+        *member = json!([member.take()]);
+    }
+
+    member.as_array_mut().expect("checked above").push(item);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn text_roundtrip() {
+        let context = url::Url::parse("https://example.org/").ok();
+        let input = r#"</a>; rel="next prev"; title="A, B", </b>; rel="item"; anchor="/list""#;
+        let header = parse(input, context.clone()).unwrap();
+        let actual = super::parse(&header.to_linkset().to_string(), context).unwrap();
+
+        assert_eq!(
+            actual.to_string(),
+            header.to_linkset().to_string().trim().replace(",\n", ", ")
+        );
+        assert_eq!(super::parse("\n", None).unwrap(), Header::new());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_roundtrip() {
+        let context = url::Url::parse("https://example.org/").ok();
+        let input = r#"</a>; rel="next"; title="A"; title*=UTF-8'de'%c3%84; hreflang="en"; hreflang="de", </b>; rel="item"; anchor="/list"; nopush; type="text/html""#;
+        let header = parse(input, context.clone()).unwrap();
+        let json = header.to_linkset_json().to_string();
+        let actual = parse_json(&json, context).unwrap();
+
+        assert_eq!(actual.links()[0].titles(), header.links()[0].titles());
+        assert_eq!(actual.links()[0].lang, header.links()[0].lang);
+        assert_eq!(
+            actual.links()[1].context.url(),
+            header.links()[1].context.url()
+        );
+        assert_eq!(
+            actual.links()[1].param("nopush"),
+            Some(&Param::new("nopush", Some("".into())))
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_reserved_members() {
+        let context = url::Url::parse("https://example.org/").ok();
+        let input = r#"</x>; rel="anchor", </y>; rel="next"; href="/z""#;
+        let header = parse(input, context).unwrap();
+
+        assert_eq!(
+            header.to_linkset_json(),
+            json!({
+                "linkset": [{
+                    "anchor": "https://example.org/",
+                    "next": [{ "href": "/y" }],
+                }],
+            })
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn invalid_documents() {
+        assert!(parse_json(r#"{"links": []}"#, None).is_err());
+        assert!(parse_json(r#"{"linkset": [{"next": {"href": "/"}}]}"#, None).is_err());
+        assert!(parse_json(r#"{"linkset": [{"next": [{"title": "x"}]}]}"#, None).is_err());
+    }
+}
@@ -0,0 +1,397 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Validation of links against RFC8288 and common pitfalls.
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::uri::UriRef;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+
+/// The checks performed by a `Linter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Check {
+    /// More than one distinct target for a relation type expected once per
+    /// context, e.g. `canonical`.
+    DuplicateSingleton,
+    /// Use of the deprecated "rev" param (RFC8288 Section 3.3).
+    ReverseRelation,
+    /// A relation type that is neither registered nor a URI (RFC8288 Section
+    /// 2.1).
+    UnregisteredRelation,
+    /// A "type" attribute that is not a media type (RFC6838 Section 4.2).
+    InvalidType,
+    /// A rule added with `Linter::rule`.
+    Custom(&'static str),
+}
+
+impl Display for Check {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Check::DuplicateSingleton => "duplicate-singleton",
+            Check::ReverseRelation => "reverse-relation",
+            Check::UnregisteredRelation => "unregistered-relation",
+            Check::InvalidType => "invalid-type",
+            Check::Custom(name) => name,
+        };
+
+        write!(formatter, "{}", name)
+    }
+}
+
+/// How serious an issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Suspicious but allowed.
+    Warning,
+    /// A violation of the specification.
+    Error,
+}
+
+/// A problem found in a link.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub check: Check,
+    pub severity: Severity,
+    /// The position of the offending link in the header.
+    pub link: usize,
+    pub message: String,
+}
+
+impl Display for Issue {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        write!(
+            formatter,
+            "{}[{}]: link {}: {}",
+            severity, self.check, self.link, self.message
+        )
+    }
+}
+
+/// The outcome of validating a header.
+///
+/// ```
+/// use linkheader::parse;
+/// use linkheader::lint::Check;
+///
+/// let input = r#"</a>; rel="canonical", </b>; rel="canonical", </c>; rel="sidebar"; type="json", </d>; rel="next"; rev="prev""#;
+/// let header = parse(input, None).unwrap();
+/// let report = header.validate();
+/// let checks: Vec<_> = report.issues.iter().map(|issue| issue.check).collect();
+///
+/// assert!(!report.is_valid());
+/// assert_eq!(
+///     checks,
+///     vec![
+///         Check::DuplicateSingleton,
+///         Check::UnregisteredRelation,
+///         Check::InvalidType,
+///         Check::ReverseRelation,
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub issues: Vec<Issue>,
+}
+
+impl Report {
+    /// Whether there are no errors. Warnings are allowed.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(formatter, "{}", issue)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A per-link rule returning a message for every offending link.
+type Rule = Box<dyn Fn(&Link) -> Option<String> + Send + Sync>;
+
+/// A configurable set of checks.
+///
+/// Every built-in check is enabled by default. Custom rules can be added to
+/// enforce house rules.
+///
+/// ```
+/// use linkheader::parse;
+/// use linkheader::lint::{Check, Linter, Severity};
+///
+/// let linter = Linter::new()
+///     .disable(Check::UnregisteredRelation)
+///     .rule("preload-integrity", Severity::Error, |link| {
+///         if link.has_relation("preload") && link.integrity().is_none() {
+///             Some("preload links must carry \"integrity\"".into())
+///         } else {
+///             None
+///         }
+///     });
+///
+/// let input = r#"</app.js>; rel="preload"; as="script", </x>; rel="sidebar""#;
+/// let report = linter.run(&parse(input, None).unwrap());
+///
+/// assert_eq!(report.issues.len(), 1);
+/// assert_eq!(report.issues[0].check, Check::Custom("preload-integrity"));
+/// ```
+#[derive(Default)]
+pub struct Linter {
+    disabled: HashSet<Check>,
+    rules: Vec<(&'static str, Severity, Rule)>,
+}
+
+impl Linter {
+    pub fn new() -> Linter {
+        Linter::default()
+    }
+
+    pub fn disable(mut self, check: Check) -> Linter {
+        self.disabled.insert(check);
+        self
+    }
+
+    pub fn enable(mut self, check: Check) -> Linter {
+        self.disabled.remove(&check);
+        self
+    }
+
+    pub fn is_enabled(&self, check: Check) -> bool {
+        !self.disabled.contains(&check)
+    }
+
+    /// Adds a custom rule reported as `Check::Custom(name)`. The rule returns
+    /// a message when the link is at fault.
+    pub fn rule<F>(mut self, name: &'static str, severity: Severity, rule: F) -> Linter
+    where
+        F: Fn(&Link) -> Option<String> + Send + Sync + 'static,
+    {
+        self.rules.push((name, severity, Box::new(rule)));
+        self
+    }
+
+    /// Reports the issues found in the header, sorted by link position.
+    ///
+    /// Checks on params shared by every link of a link-value (e.g. "rev")
+    /// report the first link of the link-value only.
+    pub fn run(&self, header: &Header) -> Report {
+        let mut issues = vec![];
+        let mut singletons: HashMap<_, UriRef> = HashMap::new();
+        let mut idx = 0;
+
+        for group in header.groups() {
+            for (position, link) in group.iter().enumerate() {
+                if let Some(relation) = &link.relation {
+                    if relation.is_singleton() && self.is_enabled(Check::DuplicateSingleton) {
+                        let key = (relation.clone(), link.context.url());
+                        let target = link.target.normalize();
+
+                        match singletons.get(&key) {
+                            Some(first) if *first != target => issues.push(Issue {
+                                check: Check::DuplicateSingleton,
+                                severity: Severity::Warning,
+                                link: idx,
+                                message: format!(
+                                    "relation {:?} already points to {:?}",
+                                    relation.as_str(),
+                                    first.as_str()
+                                ),
+                            }),
+                            Some(_) => (),
+                            None => {
+                                singletons.insert(key, target);
+                            }
+                        }
+                    }
+
+                    if !relation.is_extension()
+                        && !relation.is_registered()
+                        && self.is_enabled(Check::UnregisteredRelation)
+                    {
+                        issues.push(Issue {
+                            check: Check::UnregisteredRelation,
+                            severity: Severity::Warning,
+                            link: idx,
+                            message: format!("relation {:?} is not registered", relation.as_str()),
+                        });
+                    }
+                }
+
+                if position == 0 {
+                    self.check_link_value(idx, link, &mut issues);
+                }
+
+                for (name, severity, rule) in &self.rules {
+                    if !self.is_enabled(Check::Custom(name)) {
+                        continue;
+                    }
+
+                    if let Some(message) = rule(link) {
+                        issues.push(Issue {
+                            check: Check::Custom(name),
+                            severity: *severity,
+                            link: idx,
+                            message,
+                        });
+                    }
+                }
+
+                idx += 1;
+            }
+        }
+
+        issues.sort_by_key(|issue| issue.link);
+
+        Report { issues }
+    }
+
+    /// Runs the enabled checks on the params of a link-value, given its first
+    /// link.
+    fn check_link_value(&self, idx: usize, link: &Link, issues: &mut Vec<Issue>) {
+        if self.is_enabled(Check::ReverseRelation) && link.param("rev").is_some() {
+            issues.push(Issue {
+                check: Check::ReverseRelation,
+                severity: Severity::Warning,
+                link: idx,
+                message: "\"rev\" is deprecated, use a relation type with the opposite meaning"
+                    .into(),
+            });
+        }
+
+        if !self.is_enabled(Check::InvalidType) {
+            return;
+        }
+
+        for value in link.param_values("type") {
+            if !is_media_type(value.text()) {
+                issues.push(Issue {
+                    check: Check::InvalidType,
+                    severity: Severity::Error,
+                    link: idx,
+                    message: format!("{:?} is not a media type", value.text()),
+                });
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Linter {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let rules: Vec<_> = self.rules.iter().map(|(name, _, _)| name).collect();
+
+        formatter
+            .debug_struct("Linter")
+            .field("disabled", &self.disabled)
+            .field("rules", &rules)
+            .finish()
+    }
+}
+
+impl Header {
+    /// Reports RFC violations and suspicious patterns in the links using the
+    /// default `Linter`.
+    pub fn validate(&self) -> Report {
+        Linter::new().run(self)
+    }
+}
+
+/// Whether the input is a `type/subtype` media type with optional parameters.
+fn is_media_type(input: &str) -> bool {
+    let essence = input.split(';').next().unwrap_or("").trim();
+    let is_name = |name: &str| {
+        !name.is_empty()
+            && name.len() <= 127
+            && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$&-^_.+".contains(c))
+    };
+
+    match essence.split_once('/') {
+        Some((kind, subtype)) => is_name(kind) && is_name(subtype),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn media_types() {
+        assert!(is_media_type("application/ld+json; profile=\"x\""));
+        assert!(is_media_type("image/svg+xml"));
+        assert!(!is_media_type("json"));
+        assert!(!is_media_type("text/"));
+        assert!(!is_media_type("text/html/x"));
+    }
+
+    #[test]
+    fn clean_header() {
+        let input = r#"</a>; rel="canonical", </a>; rel="canonical", <https://example.org/>; rel="https://example.org/rel/custom"; type="text/html""#;
+        let report = parse(input, None).unwrap().validate();
+
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn disabled_checks_are_skipped() {
+        let input = r#"</a>; rel="canonical", </b>; rel="canonical"; rev="x""#;
+        let header = parse(input, None).unwrap();
+        let linter = Linter::new()
+            .disable(Check::DuplicateSingleton)
+            .disable(Check::ReverseRelation)
+            .enable(Check::ReverseRelation);
+        let report = linter.run(&header);
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].check, Check::ReverseRelation);
+    }
+
+    #[test]
+    fn link_value_checks_report_once() {
+        let input = r#"</a>; rel="next prev"; rev="x"; type="bogus", </b>; rel="up""#;
+        let report = parse(input, None).unwrap().validate();
+        let checks: Vec<_> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.check, issue.link))
+            .collect();
+
+        assert_eq!(
+            checks,
+            vec![(Check::ReverseRelation, 0), (Check::InvalidType, 0)]
+        );
+    }
+}
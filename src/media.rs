@@ -0,0 +1,530 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Media queries for the "media" target attribute.
+
+use crate::header::Header;
+use crate::link::Link;
+use std::fmt::{self, Display};
+
+/// A comma-separated list of media queries (Media Queries Level 3), e.g.
+/// `screen and (max-width: 600px), print`.
+///
+/// Parsing is forgiving: a malformed query is kept as `not all` as required by
+/// the Media Queries specification, so it never matches.
+///
+/// ```
+/// use linkheader::media::{MediaQueryList, Qualifier};
+///
+/// let list = MediaQueryList::parse("screen and (max-width: 600px), not print");
+///
+/// assert_eq!(list.queries.len(), 2);
+/// assert_eq!(list.queries[0].media_type.as_deref(), Some("screen"));
+/// assert_eq!(list.queries[0].features[0].name, "max-width");
+/// assert_eq!(list.queries[0].features[0].value.as_deref(), Some("600px"));
+/// assert_eq!(list.queries[1].qualifier, Some(Qualifier::Not));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaQueryList {
+    pub queries: Vec<MediaQuery>,
+}
+
+/// A single media query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaQuery {
+    pub qualifier: Option<Qualifier>,
+    /// The lowercased media type. `None` when the query only has features,
+    /// which is equivalent to `all`.
+    pub media_type: Option<String>,
+    pub features: Vec<MediaFeature>,
+}
+
+/// The optional prefix of a media query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Qualifier {
+    Not,
+    Only,
+}
+
+/// A media feature expression, e.g. `(max-width: 600px)` or `(color)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaFeature {
+    /// The lowercased feature name.
+    pub name: String,
+    /// The raw value, if any.
+    pub value: Option<String>,
+}
+
+/// A description of the rendering device media queries are evaluated against.
+///
+/// Lengths are in CSS pixels and the resolution in dots per pixel (`dppx`).
+///
+/// ```
+/// use linkheader::media::{Device, MediaQueryList};
+///
+/// let phone = Device::screen(375.0, 812.0);
+/// let list = MediaQueryList::parse("screen and (max-width: 600px)");
+///
+/// assert!(list.matches(&phone));
+/// assert!(!list.matches(&Device::screen(1280.0, 800.0)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Device {
+    /// The lowercased media type, e.g. `screen` or `print`.
+    pub media_type: String,
+    pub width: f64,
+    pub height: f64,
+    pub resolution: f64,
+    /// Bits per colour component, 0 for monochrome devices.
+    pub color: u32,
+    /// Bits per pixel in a monochrome device, 0 otherwise.
+    pub monochrome: u32,
+}
+
+impl Device {
+    /// A colour screen with the given viewport size and a 1dppx resolution.
+    pub fn screen(width: f64, height: f64) -> Device {
+        Device {
+            media_type: "screen".into(),
+            width,
+            height,
+            resolution: 1.0,
+            color: 8,
+            monochrome: 0,
+        }
+    }
+
+    /// A monochrome A4 page at 300dpi.
+    pub fn print() -> Device {
+        Device {
+            media_type: "print".into(),
+            width: 794.0,
+            height: 1123.0,
+            resolution: 3.125,
+            color: 0,
+            monochrome: 1,
+        }
+    }
+
+    pub fn resolution(mut self, dppx: f64) -> Device {
+        self.resolution = dppx;
+        self
+    }
+}
+
+impl MediaQueryList {
+    /// Parses a media query list. Malformed queries become `not all` and a
+    /// blank input is an empty list, i.e. `all`.
+    pub fn parse(input: &str) -> MediaQueryList {
+        if input.trim().is_empty() {
+            return MediaQueryList { queries: vec![] };
+        }
+
+        let queries = split_top_level(input, ',')
+            .into_iter()
+            .map(|query| MediaQuery::parse(query).unwrap_or_else(MediaQuery::not_all))
+            .collect();
+
+        MediaQueryList { queries }
+    }
+
+    /// Whether any of the queries matches the device. An empty list matches
+    /// every device.
+    pub fn matches(&self, device: &Device) -> bool {
+        self.queries.is_empty() || self.queries.iter().any(|query| query.matches(device))
+    }
+}
+
+impl MediaQuery {
+    /// Parses a single media query, `None` if malformed.
+    fn parse(input: &str) -> Option<MediaQuery> {
+        let mut tokens = tokenize(input)?.into_iter().peekable();
+        let mut query = MediaQuery {
+            qualifier: None,
+            media_type: None,
+            features: vec![],
+        };
+
+        if let Some(Token::Word(word)) = tokens.peek() {
+            query.qualifier = match &word[..] {
+                "not" => Some(Qualifier::Not),
+                "only" => Some(Qualifier::Only),
+                _ => None,
+            };
+
+            if query.qualifier.is_some() {
+                tokens.next();
+            }
+
+            match tokens.next() {
+                Some(Token::Word(word)) if word != "and" => query.media_type = Some(word),
+                _ => return None,
+            }
+        } else {
+            match tokens.next() {
+                Some(Token::Group(group)) => query.features.push(MediaFeature::parse(&group)?),
+                _ => return None,
+            }
+        }
+
+        while let Some(token) = tokens.next() {
+            match (token, tokens.next()) {
+                (Token::Word(word), Some(Token::Group(group))) if word == "and" => {
+                    query.features.push(MediaFeature::parse(&group)?)
+                }
+                _ => return None,
+            }
+        }
+
+        Some(query)
+    }
+
+    /// Whether the query matches the device. Unknown features or malformed
+    /// values never match.
+    pub fn matches(&self, device: &Device) -> bool {
+        let media_type = self
+            .media_type
+            .as_ref()
+            .is_none_or(|media_type| media_type == "all" || *media_type == device.media_type);
+        let result = media_type && self.features.iter().all(|feature| feature.matches(device));
+
+        match self.qualifier {
+            Some(Qualifier::Not) => !result,
+            _ => result,
+        }
+    }
+
+    fn not_all() -> MediaQuery {
+        MediaQuery {
+            qualifier: Some(Qualifier::Not),
+            media_type: Some("all".into()),
+            features: vec![],
+        }
+    }
+}
+
+impl MediaFeature {
+    fn parse(input: &str) -> Option<MediaFeature> {
+        let (name, value) = match input.find(':') {
+            Some(idx) => (&input[..idx], Some(input[idx + 1..].trim().to_string())),
+            None => (input, None),
+        };
+        let name = name.trim().to_lowercase();
+
+        if name.is_empty() || value.as_deref() == Some("") {
+            return None;
+        }
+
+        Some(MediaFeature { name, value })
+    }
+
+    /// Whether the feature expression holds for the device.
+    pub fn matches(&self, device: &Device) -> bool {
+        let (prefix, name) = match self.name.split_once('-') {
+            Some((prefix @ "min", name)) | Some((prefix @ "max", name)) => (Some(prefix), name),
+            _ => (None, &self.name[..]),
+        };
+        let value = self.value.as_deref();
+
+        let (actual, expected) = match name {
+            "width" => (device.width, value.map(parse_length)),
+            "height" => (device.height, value.map(parse_length)),
+            "aspect-ratio" => (device.width / device.height, value.map(parse_ratio)),
+            "resolution" => (device.resolution, value.map(parse_resolution)),
+            "color" => (device.color as f64, value.map(|v| v.parse().ok())),
+            "monochrome" => (device.monochrome as f64, value.map(|v| v.parse().ok())),
+            "orientation" if prefix.is_none() => {
+                let portrait = device.height >= device.width;
+
+                return match value {
+                    Some("portrait") => portrait,
+                    Some("landscape") => !portrait,
+                    _ => false,
+                };
+            }
+            _ => return false,
+        };
+
+        match (prefix, expected) {
+            (None, None) => actual != 0.0,
+            (_, Some(Some(expected))) => match prefix {
+                Some("min") => actual >= expected,
+                Some(_) => actual <= expected,
+                None => (actual - expected).abs() < f64::EPSILON,
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Parses a length into CSS pixels. Relative units assume a 16px font size.
+fn parse_length(input: &str) -> Option<f64> {
+    let input = input.trim().to_ascii_lowercase();
+    let idx = input
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(input.len());
+    let number: f64 = input[..idx].trim().parse().ok()?;
+    let factor = match &input[idx..] {
+        "px" => 1.0,
+        "" if number == 0.0 => 1.0,
+        "em" | "rem" => 16.0,
+        "in" => 96.0,
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        "pt" => 96.0 / 72.0,
+        "pc" => 16.0,
+        _ => return None,
+    };
+
+    Some(number * factor)
+}
+
+/// Parses a resolution into dots per pixel.
+fn parse_resolution(input: &str) -> Option<f64> {
+    let input = input.trim().to_ascii_lowercase();
+    let idx = input
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(input.len());
+    let number: f64 = input[..idx].trim().parse().ok()?;
+    let factor = match &input[idx..] {
+        "dppx" | "x" => 1.0,
+        "dpi" => 1.0 / 96.0,
+        "dpcm" => 2.54 / 96.0,
+        _ => return None,
+    };
+
+    Some(number * factor)
+}
+
+/// Parses a ratio such as `16/9` or a single number.
+fn parse_ratio(input: &str) -> Option<f64> {
+    match input.split_once('/') {
+        Some((a, b)) => {
+            let a: f64 = a.trim().parse().ok()?;
+            let b: f64 = b.trim().parse().ok()?;
+
+            Some(a / b)
+        }
+        None => input.trim().parse().ok(),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Group(String),
+}
+
+/// Splits a media query into lowercased words and the contents of
+/// parenthesised groups.
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut rest = input.trim();
+
+    while !rest.is_empty() {
+        if rest.starts_with('(') {
+            let end = rest.find(')')?;
+            tokens.push(Token::Group(rest[1..end].to_string()));
+            rest = &rest[end + 1..];
+        } else {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '(')
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+
+            if word.contains(')') {
+                return None;
+            }
+
+            tokens.push(Token::Word(word.to_lowercase()));
+            rest = &rest[end..];
+        }
+
+        rest = rest.trim_start();
+    }
+
+    Some(tokens)
+}
+
+/// Splits by the given separator ignoring the ones in parenthesis.
+fn split_top_level(input: &str, separator: char) -> Vec<&str> {
+    let mut result = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (idx, c) in input.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c == separator && depth == 0 => {
+                result.push(&input[start..idx]);
+                start = idx + 1;
+            }
+            _ => (),
+        }
+    }
+
+    result.push(&input[start..]);
+
+    result
+}
+
+impl Display for MediaQueryList {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, query) in self.queries.iter().enumerate() {
+            if idx > 0 {
+                write!(formatter, ", ")?;
+            }
+
+            write!(formatter, "{}", query)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for MediaQuery {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = vec![];
+
+        match self.qualifier {
+            Some(Qualifier::Not) => parts.push("not".to_string()),
+            Some(Qualifier::Only) => parts.push("only".to_string()),
+            None => (),
+        }
+
+        if let Some(media_type) = &self.media_type {
+            parts.push(media_type.clone());
+        }
+
+        for feature in &self.features {
+            if !parts.is_empty() {
+                parts.push("and".into());
+            }
+
+            match &feature.value {
+                Some(value) => parts.push(format!("({}: {})", feature.name, value)),
+                None => parts.push(format!("({})", feature.name)),
+            }
+        }
+
+        write!(formatter, "{}", parts.join(" "))
+    }
+}
+
+impl Link {
+    /// Parses the "media" attribute as a media query list.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</print.css>; rel="stylesheet"; media="PRINT""#;
+    /// let header = parse(input, None).unwrap();
+    /// let media = header.links()[0].media_query().unwrap();
+    ///
+    /// assert_eq!(media.to_string(), "print");
+    /// ```
+    pub fn media_query(&self) -> Option<MediaQueryList> {
+        self.media
+            .as_ref()
+            .map(|value| MediaQueryList::parse(value.text()))
+    }
+
+    /// Whether the link applies to the device. Links with no "media"
+    /// attribute apply to every device.
+    pub fn applies_to(&self, device: &Device) -> bool {
+        self.media_query().is_none_or(|media| media.matches(device))
+    }
+}
+
+impl Header {
+    /// Returns the links applicable to the given device in order.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    /// use linkheader::media::Device;
+    ///
+    /// let input = r#"</base.css>; rel="stylesheet", </narrow.css>; rel="stylesheet"; media="screen and (max-width: 600px)", </print.css>; rel="stylesheet"; media="print""#;
+    /// let header = parse(input, None).unwrap();
+    /// let links = header.applicable_to(&Device::print());
+    ///
+    /// assert_eq!(links.len(), 2);
+    /// assert_eq!(links[1].target.as_str(), "/print.css");
+    /// ```
+    pub fn applicable_to(&self, device: &Device) -> Vec<&Link> {
+        self.links()
+            .iter()
+            .filter(|link| link.applies_to(device))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn features_only() {
+        let list = MediaQueryList::parse("(min-width: 40em) and (orientation:landscape)");
+
+        assert_eq!(
+            list.queries,
+            vec![MediaQuery {
+                qualifier: None,
+                media_type: None,
+                features: vec![
+                    MediaFeature {
+                        name: "min-width".into(),
+                        value: Some("40em".into())
+                    },
+                    MediaFeature {
+                        name: "orientation".into(),
+                        value: Some("landscape".into())
+                    },
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn malformed_query_is_not_all() {
+        let list = MediaQueryList::parse("screen and, print and (color)");
+
+        assert_eq!(list.queries[0], MediaQuery::not_all());
+        assert_eq!(list.to_string(), "not all, print and (color)");
+    }
+
+    #[test]
+    fn blank_is_all() {
+        let list = MediaQueryList::parse(" ");
+
+        assert!(list.queries.is_empty());
+        assert!(list.matches(&Device::print()));
+    }
+
+    #[test]
+    fn only_requires_media_type() {
+        let list = MediaQueryList::parse("only (color)");
+
+        assert_eq!(list.queries[0], MediaQuery::not_all());
+    }
+
+    #[test]
+    fn evaluate_features() {
+        let device = Device::screen(1024.0, 768.0).resolution(2.0);
+        let matches = |input| MediaQueryList::parse(input).matches(&device);
+
+        assert!(matches("(min-width: 64em)"));
+        assert!(matches("screen and (orientation: landscape) and (color)"));
+        assert!(matches("(min-resolution: 192dpi)"));
+        assert!(matches("(aspect-ratio: 4/3)"));
+        assert!(matches("not print"));
+        assert!(!matches("(monochrome)"));
+        assert!(!matches("(max-height: 10cm)"));
+        assert!(!matches("(hover: hover)"));
+        assert!(!matches("screen and"));
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Memento relation types and params (RFC7089), as returned by web archives.
+//!
+//! Parsing the "datetime", "from" and "until" params requires the `chrono`
+//! feature.
+
+use crate::header::Header;
+use crate::link::Link;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+impl Header {
+    /// The first `rel="original"` link, i.e. the Original Resource.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<http://a.example/>; rel="original", <http://arxiv.example/timegate/http://a.example/>; rel="timegate", <http://arxiv.example/timemap/http://a.example/>; rel="timemap"; type="application/link-format""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(header.original().unwrap().target.as_str(), "http://a.example/");
+    /// assert!(header.timegate().is_some());
+    /// assert!(header.timemap().is_some());
+    /// ```
+    pub fn original(&self) -> Option<&Link> {
+        self.first_by_relation("original")
+    }
+
+    /// The first `rel="timegate"` link.
+    pub fn timegate(&self) -> Option<&Link> {
+        self.first_by_relation("timegate")
+    }
+
+    /// The first `rel="timemap"` link.
+    pub fn timemap(&self) -> Option<&Link> {
+        self.first_by_relation("timemap")
+    }
+
+    /// Every `rel="memento"` link, in order, including the ones also typed as
+    /// "first", "last", "prev" or "next".
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<http://arxiv.example/20010911/http://a.example/>; rel="first memento"; datetime="Tue, 11 Sep 2001 20:47:33 GMT", <http://arxiv.example/20011104/http://a.example/>; rel="memento"; datetime="Sun, 04 Nov 2001 12:00:00 GMT""#;
+    /// let header = parse(input, None).unwrap();
+    /// let mementos = header.mementos();
+    ///
+    /// assert_eq!(mementos.len(), 2);
+    /// assert_eq!(mementos[0].datetime_str(), Some("Tue, 11 Sep 2001 20:47:33 GMT"));
+    /// ```
+    pub fn mementos(&self) -> Vec<&Link> {
+        self.by_relation("memento")
+    }
+
+    /// The memento closest to the given datetime, before or after it. Mementos
+    /// with no valid "datetime" are skipped.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</2001>; rel="memento"; datetime="Tue, 11 Sep 2001 20:47:33 GMT", </2005>; rel="memento"; datetime="Thu, 13 Jan 2005 09:00:00 GMT""#;
+    /// let header = parse(input, None).unwrap();
+    /// let datetime = chrono::DateTime::parse_from_rfc3339("2004-06-01T00:00:00Z").unwrap();
+    ///
+    /// assert_eq!(header.closest_memento(datetime.into()).unwrap().target.as_str(), "/2005");
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn closest_memento(&self, datetime: DateTime<Utc>) -> Option<&Link> {
+        self.mementos()
+            .into_iter()
+            .filter_map(|link| Some((link, link.datetime()?)))
+            .min_by_key(|(_, other)| (*other - datetime).num_seconds().abs())
+            .map(|(link, _)| link)
+    }
+}
+
+impl Link {
+    /// The "datetime" param as given, i.e. the Memento-Datetime of a memento.
+    pub fn datetime_str(&self) -> Option<&str> {
+        Some(self.param_values("datetime").first()?.text())
+    }
+
+    /// The "datetime" param as an HTTP-date (RFC7089 Section 2.2.1).
+    ///
+    /// Returns `None` when there is no "datetime" or it is not a valid date.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</20010911>; rel="memento"; datetime="Tue, 11 Sep 2001 20:47:33 GMT""#;
+    /// let header = parse(input, None).unwrap();
+    /// let datetime = header.links()[0].datetime().unwrap();
+    ///
+    /// assert_eq!(datetime.to_rfc3339(), "2001-09-11T20:47:33+00:00");
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn datetime(&self) -> Option<DateTime<Utc>> {
+        self.http_date("datetime")
+    }
+
+    /// The "from" param of a TimeMap link, i.e. the datetime of the first
+    /// memento in the TimeMap (RFC7089 Section 5.1).
+    #[cfg(feature = "chrono")]
+    pub fn from_datetime(&self) -> Option<DateTime<Utc>> {
+        self.http_date("from")
+    }
+
+    /// The "until" param of a TimeMap link, i.e. the datetime of the last
+    /// memento in the TimeMap (RFC7089 Section 5.1).
+    #[cfg(feature = "chrono")]
+    pub fn until_datetime(&self) -> Option<DateTime<Utc>> {
+        self.http_date("until")
+    }
+
+    #[cfg(feature = "chrono")]
+    fn http_date(&self, name: &str) -> Option<DateTime<Utc>> {
+        let value = self.param_values(name).first()?.text().trim();
+
+        DateTime::parse_from_rfc2822(value)
+            .ok()
+            .map(|datetime| datetime.with_timezone(&Utc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse;
+
+    #[test]
+    fn relation_types_are_case_insensitive() {
+        let input = r#"</t>; rel="TimeGate", </m>; rel="last Memento""#;
+        let header = parse(input, None).unwrap();
+
+        assert_eq!(header.timegate().unwrap().target.as_str(), "/t");
+        assert_eq!(header.mementos().len(), 1);
+        assert!(header.original().is_none());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn timemap_range() {
+        let input = r#"</timemap>; rel="timemap"; from="Tue, 11 Sep 2001 20:47:33 GMT"; until="not a date""#;
+        let header = parse(input, None).unwrap();
+        let timemap = header.timemap().unwrap();
+
+        assert_eq!(
+            timemap.from_datetime().unwrap().to_rfc3339(),
+            "2001-09-11T20:47:33+00:00"
+        );
+        assert_eq!(timemap.until_datetime(), None);
+        assert_eq!(timemap.datetime(), None);
+    }
+}
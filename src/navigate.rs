@@ -0,0 +1,226 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Client-agnostic navigation of paginated resources.
+
+use crate::error::Result;
+use crate::header::Header;
+use std::collections::HashSet;
+
+/// Walks a paginated collection with a blocking fetch function.
+///
+/// The fetch function gets the absolute URL of a page and returns its `Link`
+/// header together with whatever body the caller needs. Relative targets are
+/// resolved against the link context or, if absent, the page URL.
+///
+/// ```
+/// use linkheader::navigate::Navigator;
+/// use linkheader::parse;
+///
+/// let mut navigator = Navigator::new(|url: url::Url| {
+///     let page: u32 = url.query().unwrap()[5..].parse()?;
+///     let mut links = vec![];
+///
+///     if page > 1 {
+///         links.push(format!("<?page={}>; rel=\"prev\"", page - 1));
+///     }
+///     if page < 3 {
+///         links.push(format!("<?page={}>; rel=\"next\"", page + 1));
+///     }
+///
+///     Ok((parse(&links.join(", "), None)?, page))
+/// });
+///
+/// assert_eq!(navigator.get(url::Url::parse("https://example.org/items?page=1")?)?, 1);
+/// assert_eq!(navigator.next_page()?, Some(2));
+/// assert_eq!(navigator.next_page()?, Some(3));
+/// assert_eq!(navigator.next_page()?, None);
+/// assert_eq!(navigator.prev_page()?, Some(2));
+/// assert_eq!(navigator.url().unwrap().as_str(), "https://example.org/items?page=2");
+/// # Ok::<(), failure::Error>(())
+/// ```
+pub struct Navigator<F> {
+    fetch: F,
+    current: Option<(url::Url, Header)>,
+}
+
+impl<F, B> Navigator<F>
+where
+    F: FnMut(url::Url) -> Result<(Header, B)>,
+{
+    pub fn new(fetch: F) -> Navigator<F> {
+        Navigator {
+            fetch,
+            current: None,
+        }
+    }
+
+    /// The URL of the current page, if any.
+    pub fn url(&self) -> Option<&url::Url> {
+        self.current.as_ref().map(|(url, _)| url)
+    }
+
+    /// The `Link` header of the current page, if any.
+    pub fn header(&self) -> Option<&Header> {
+        self.current.as_ref().map(|(_, header)| header)
+    }
+
+    /// Fetches the given page and makes it the current one.
+    pub fn get(&mut self, url: url::Url) -> Result<B> {
+        let (header, body) = (self.fetch)(url.clone())?;
+        self.current = Some((url, header));
+
+        Ok(body)
+    }
+
+    /// The absolute URL of the first link with the given relation type in
+    /// the current page.
+    pub fn url_for(&self, relation: &str) -> Result<Option<url::Url>> {
+        match &self.current {
+            Some((url, header)) => header
+                .by_relation(relation)
+                .first()
+                .map(|link| {
+                    let base = link.context.base().unwrap_or(url);
+                    link.target.to_url(Some(base))
+                })
+                .transpose(),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches the page linked from the current one with the given relation
+    /// type. `None` when there is no such link, leaving the current page
+    /// unchanged.
+    pub fn follow(&mut self, relation: &str) -> Result<Option<B>> {
+        match self.url_for(relation)? {
+            Some(url) => self.get(url).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches the `rel="next"` page.
+    pub fn next_page(&mut self) -> Result<Option<B>> {
+        self.follow("next")
+    }
+
+    /// Fetches the `rel="prev"` page, or `rel="previous"` when there is no
+    /// "prev" link.
+    pub fn prev_page(&mut self) -> Result<Option<B>> {
+        match self.follow("prev")? {
+            Some(body) => Ok(Some(body)),
+            None => self.follow("previous"),
+        }
+    }
+
+    /// Iterates over the bodies of the pages after the current one following
+    /// `rel="next"` links.
+    ///
+    /// The iterator yields an error and stops when a "next" link points to a
+    /// page already visited.
+    pub fn pages(&mut self) -> Pages<'_, F> {
+        let visited = self.url().cloned().into_iter().collect();
+
+        Pages {
+            navigator: self,
+            visited,
+            done: false,
+        }
+    }
+}
+
+/// An iterator over the pages following `rel="next"` links. See
+/// `Navigator::pages`.
+pub struct Pages<'a, F> {
+    navigator: &'a mut Navigator<F>,
+    visited: HashSet<url::Url>,
+    done: bool,
+}
+
+impl<F, B> Iterator for Pages<'_, F>
+where
+    F: FnMut(url::Url) -> Result<(Header, B)>,
+{
+    type Item = Result<B>;
+
+    fn next(&mut self) -> Option<Result<B>> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.step();
+
+        if !matches!(result, Ok(Some(_))) {
+            self.done = true;
+        }
+
+        result.transpose()
+    }
+}
+
+impl<F, B> Pages<'_, F>
+where
+    F: FnMut(url::Url) -> Result<(Header, B)>,
+{
+    fn step(&mut self) -> Result<Option<B>> {
+        match self.navigator.url_for("next")? {
+            Some(url) => {
+                ensure!(
+                    self.visited.insert(url.clone()),
+                    "Pagination loop at {}",
+                    url
+                );
+
+                self.navigator.get(url).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn detect_loops() {
+        let mut navigator = Navigator::new(|url: url::Url| {
+            let next = if url.path() == "/a" { "/b" } else { "/a" };
+            let header = parse(&format!("<{}>; rel=\"next\"", next), None)?;
+
+            Ok((header, url.path().to_string()))
+        });
+
+        navigator
+            .get(url::Url::parse("https://example.org/a").unwrap())
+            .unwrap();
+
+        let pages: Vec<_> = navigator.pages().collect();
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].as_ref().unwrap(), "/b");
+        assert!(pages[1].is_err());
+    }
+
+    #[test]
+    fn context_over_page_url() {
+        let mut navigator = Navigator::new(|url: url::Url| {
+            let context = url::Url::parse("https://cdn.example.org/items").ok();
+            let header = parse(r#"<?page=2>; rel="next""#, context)?;
+
+            Ok((header, url))
+        });
+
+        navigator
+            .get(url::Url::parse("https://example.org/items").unwrap())
+            .unwrap();
+
+        let next = navigator.next_page().unwrap().unwrap();
+
+        assert_eq!(next.as_str(), "https://cdn.example.org/items?page=2");
+    }
+}
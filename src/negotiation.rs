@@ -0,0 +1,198 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Agent-driven content negotiation over `rel="alternate"` links.
+
+use crate::header::Header;
+use crate::language::basic_filter;
+use crate::link::Link;
+
+/// The client preferences as expressed by the `Accept` and `Accept-Language`
+/// request headers (RFC7231 Section 5.3).
+///
+/// ```
+/// use linkheader::parse;
+/// use linkheader::negotiation::Preferences;
+///
+/// let input = r#"</doc.html>; rel="alternate"; type="text/html"; hreflang="en", </doc.de.html>; rel="alternate"; type="text/html"; hreflang="de", </doc.json>; rel="alternate"; type="application/json"; hreflang="de""#;
+/// let header = parse(input, None).unwrap();
+/// let preferences = Preferences::new()
+///     .accept("text/html, application/json;q=0.5")
+///     .accept_language("de-CH, de;q=0.9, en;q=0.8");
+/// let link = header.negotiate(&preferences).unwrap();
+///
+/// assert_eq!(link.target.as_str(), "/doc.de.html");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Preferences {
+    media_ranges: Option<Vec<(String, f32)>>,
+    language_ranges: Option<Vec<(String, f32)>>,
+}
+
+impl Preferences {
+    pub fn new() -> Preferences {
+        Preferences::default()
+    }
+
+    /// Sets the media ranges from an `Accept` header value.
+    pub fn accept(mut self, value: &str) -> Preferences {
+        self.media_ranges = Some(parse_weighted(value));
+        self
+    }
+
+    /// Sets the language ranges from an `Accept-Language` header value.
+    pub fn accept_language(mut self, value: &str) -> Preferences {
+        self.language_ranges = Some(parse_weighted(value));
+        self
+    }
+
+    /// Computes the quality of the given link, between 0 and 1.
+    ///
+    /// A missing header or a link without the relevant attribute is neutral.
+    /// Otherwise the most specific matching range determines the quality.
+    pub fn quality(&self, link: &Link) -> f32 {
+        let media = match (&self.media_ranges, &link.content_type) {
+            (Some(ranges), Some(value)) => media_quality(ranges, value.text()),
+            _ => 1.0,
+        };
+
+        let language = match &self.language_ranges {
+            Some(ranges) if !link.lang.is_empty() => link
+                .lang
+                .iter()
+                .map(|value| language_quality(ranges, value.text()))
+                .fold(0.0, f32::max),
+            _ => 1.0,
+        };
+
+        media * language
+    }
+}
+
+impl Header {
+    /// Returns the `rel="alternate"` links acceptable to the given preferences
+    /// sorted by quality, best first. Links with equal quality keep their
+    /// order.
+    pub fn rank_alternates(&self, preferences: &Preferences) -> Vec<&Link> {
+        let mut ranked: Vec<(f32, &Link)> = self
+            .links()
+            .iter()
+            .filter(|link| link.has_relation("alternate"))
+            .map(|link| (preferences.quality(link), link))
+            .filter(|(quality, _)| *quality > 0.0)
+            .collect();
+
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        ranked.into_iter().map(|(_, link)| link).collect()
+    }
+
+    /// Picks the best `rel="alternate"` link for the given preferences.
+    pub fn negotiate(&self, preferences: &Preferences) -> Option<&Link> {
+        self.rank_alternates(preferences).into_iter().next()
+    }
+}
+
+/// Parses a comma-separated list of ranges with an optional `q` weight.
+/// Ranges with a malformed weight are ignored.
+fn parse_weighted(input: &str) -> Vec<(String, f32)> {
+    input
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let range = parts.next()?.trim();
+
+            if range.is_empty() {
+                return None;
+            }
+
+            let mut quality = 1.0;
+
+            for part in parts {
+                if let Some((name, value)) = part.split_once('=') {
+                    if name.trim().eq_ignore_ascii_case("q") {
+                        quality = value.trim().parse::<f32>().ok()?;
+                    }
+                }
+            }
+
+            Some((range.to_ascii_lowercase(), quality.clamp(0.0, 1.0)))
+        })
+        .collect()
+}
+
+/// The quality of the most specific media range matching the media type.
+fn media_quality(ranges: &[(String, f32)], media_type: &str) -> f32 {
+    let essence = media_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    let kind = essence.split('/').next().unwrap_or("");
+
+    ranges
+        .iter()
+        .filter_map(|(range, quality)| {
+            let range = range.split(';').next().unwrap_or("").trim();
+            let specificity = if range == essence {
+                2
+            } else if range.strip_suffix("/*") == Some(kind) {
+                1
+            } else if range == "*/*" {
+                0
+            } else {
+                return None;
+            };
+
+            Some((specificity, *quality))
+        })
+        .max_by_key(|(specificity, _)| *specificity)
+        .map_or(0.0, |(_, quality)| quality)
+}
+
+/// The quality of the longest language range matching the tag (RFC4647
+/// Section 3.3.1).
+fn language_quality(ranges: &[(String, f32)], tag: &str) -> f32 {
+    ranges
+        .iter()
+        .filter(|(range, _)| basic_filter(range, tag))
+        .max_by_key(|(range, _)| if range == "*" { 0 } else { range.len() })
+        .map_or(0.0, |(_, quality)| *quality)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn most_specific_range_wins() {
+        let ranges = parse_weighted("*/*;q=0.1, text/*;q=0.5, text/html");
+
+        assert_eq!(media_quality(&ranges, "text/html; charset=utf-8"), 1.0);
+        assert_eq!(media_quality(&ranges, "text/plain"), 0.5);
+        assert_eq!(media_quality(&ranges, "image/png"), 0.1);
+    }
+
+    #[test]
+    fn language_prefix() {
+        let ranges = parse_weighted("de;q=0.5, de-ch, *;q=0.1");
+
+        assert_eq!(language_quality(&ranges, "de-CH"), 1.0);
+        assert_eq!(language_quality(&ranges, "de-AT"), 0.5);
+        assert_eq!(language_quality(&ranges, "den"), 0.1);
+    }
+
+    #[test]
+    fn unacceptable_alternates() {
+        let input = r#"</a.json>; rel="alternate"; type="application/json", </a.xml>; rel="alternate"; type="application/xml""#;
+        let header = parse(input, None).unwrap();
+        let preferences = Preferences::new().accept("application/xml;q=0, text/*");
+
+        assert_eq!(header.negotiate(&preferences), None);
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! oEmbed endpoint discovery ([oEmbed](https://oembed.com/#section4)).
+//!
+//! Providers advertise their endpoints as `rel="alternate"` links, either in
+//! the `Link` header or as HTML `<link>` elements (see `html::parse`).
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::query::Query;
+
+/// The media type of JSON oEmbed endpoints.
+pub const JSON: &str = "application/json+oembed";
+/// The media type of XML oEmbed endpoints.
+pub const XML: &str = "text/xml+oembed";
+
+/// The response format of an oEmbed endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Xml,
+}
+
+impl Link {
+    /// The oEmbed format of a `rel="alternate"` link, if its "type" is an
+    /// oEmbed media type.
+    pub fn oembed_format(&self) -> Option<Format> {
+        if Query::new().rel("alternate").media_type(JSON).matches(self) {
+            Some(Format::Json)
+        } else if Query::new().rel("alternate").media_type(XML).matches(self) {
+            Some(Format::Xml)
+        } else {
+            None
+        }
+    }
+}
+
+impl Header {
+    /// The oEmbed endpoints, in order.
+    ///
+    /// ```
+    /// use linkheader::oembed::Format;
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<https://www.flickr.com/services/oembed?url=https%3A%2F%2Fflic.kr%2Fp%2FbM&format=xml>; rel="alternate"; type="text/xml+oembed"; title="Bacon Lollys", <https://www.flickr.com/services/oembed?url=https%3A%2F%2Fflic.kr%2Fp%2FbM&format=json>; rel="alternate"; type="application/json+oembed"; title="Bacon Lollys", </photo.rss>; rel="alternate"; type="application/rss+xml""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(header.oembed().len(), 2);
+    /// assert_eq!(header.oembed_endpoint().unwrap().oembed_format(), Some(Format::Json));
+    /// ```
+    pub fn oembed(&self) -> Vec<&Link> {
+        self.iter()
+            .filter(|link| link.oembed_format().is_some())
+            .collect()
+    }
+
+    /// The preferred oEmbed endpoint: the first JSON one or else the first
+    /// XML one.
+    pub fn oembed_endpoint(&self) -> Option<&Link> {
+        let endpoints = self.oembed();
+
+        endpoints
+            .iter()
+            .find(|link| link.oembed_format() == Some(Format::Json))
+            .or_else(|| endpoints.first())
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn xml_only() {
+        let input = r#"</oembed.xml>; rel="Alternate"; type="Text/XML+oEmbed; charset=utf-8", </oembed.json>; rel="related"; type="application/json+oembed""#;
+        let header = parse(input, None).unwrap();
+
+        assert_eq!(header.oembed().len(), 1);
+        assert_eq!(
+            header.oembed_endpoint().unwrap().oembed_format(),
+            Some(Format::Xml)
+        );
+    }
+}
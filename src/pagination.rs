@@ -0,0 +1,388 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Pagination metadata from the query params of the pagination targets.
+//!
+//! Page-based pagination follows the `page` and `per_page` convention of APIs
+//! such as GitHub or GitLab, and cursor-based pagination an opaque token in
+//! the `rel="next"` target. `PageLinks` generates the links for servers.
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::param::Value;
+use crate::parser::LinkBuilder;
+
+/// The query params checked for a cursor by default, in order.
+pub const CURSOR_PARAMS: &[&str] = &["cursor", "page_token", "pageToken", "next_token", "after"];
+
+/// The page numbers found in the `first`, `prev`, `next` and `last` targets.
+///
+/// ```
+/// use linkheader::parse;
+///
+/// let input = r#"<https://api.github.com/repositories/1300192/issues?page=2&per_page=30>; rel="prev", <https://api.github.com/repositories/1300192/issues?page=4&per_page=30>; rel="next", <https://api.github.com/repositories/1300192/issues?page=515&per_page=30>; rel="last", <https://api.github.com/repositories/1300192/issues?page=1&per_page=30>; rel="first""#;
+/// let pagination = parse(input, None).unwrap().pagination();
+///
+/// assert_eq!(pagination.current_page(), Some(3));
+/// assert_eq!(pagination.total_pages(), Some(515));
+/// assert_eq!(pagination.per_page, Some(30));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pagination {
+    pub first_page: Option<u64>,
+    pub prev_page: Option<u64>,
+    pub next_page: Option<u64>,
+    pub last_page: Option<u64>,
+    /// The page size, from the first target carrying one.
+    pub per_page: Option<u64>,
+}
+
+impl Pagination {
+    /// Extracts the metadata using the given query param names for the page
+    /// number and the page size.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    /// use linkheader::pagination::Pagination;
+    ///
+    /// let header = parse(r#"</items?p=2&limit=50>; rel="next""#, None).unwrap();
+    /// let pagination = Pagination::with_params(&header, "p", "limit");
+    ///
+    /// assert_eq!(pagination.next_page, Some(2));
+    /// assert_eq!(pagination.per_page, Some(50));
+    /// ```
+    pub fn with_params(header: &Header, page: &str, per_page: &str) -> Pagination {
+        let links = [
+            header.first_by_relation("first"),
+            header.prev(),
+            header.next(),
+            header.first_by_relation("last"),
+        ];
+        let number = |link: Option<&Link>, name: &str| -> Option<u64> {
+            link?
+                .target
+                .query_pairs()
+                .find(|(key, _)| key == name)?
+                .1
+                .parse()
+                .ok()
+        };
+
+        Pagination {
+            first_page: number(links[0], page),
+            prev_page: number(links[1], page),
+            next_page: number(links[2], page),
+            last_page: number(links[3], page),
+            per_page: links.iter().find_map(|link| number(*link, per_page)),
+        }
+    }
+
+    /// The current page, one after the previous page or one before the next
+    /// page. On the last page it is the last page itself when the previous
+    /// page is unknown. `None` when the previous page is the largest number or
+    /// the next page is below 2, as pages start at 1.
+    pub fn current_page(&self) -> Option<u64> {
+        match (self.prev_page, self.next_page) {
+            (Some(page), _) => page.checked_add(1),
+            (None, Some(page)) if page >= 2 => Some(page - 1),
+            (None, Some(_)) => None,
+            (None, None) => self.last_page,
+        }
+    }
+
+    /// The number of pages, i.e. the last page or, when there is no next
+    /// page, the current one.
+    pub fn total_pages(&self) -> Option<u64> {
+        self.last_page.or_else(|| {
+            if self.next_page.is_none() {
+                self.current_page()
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The maximum number of items across all pages, assuming every page is
+    /// full. `None` when it does not fit in a `u64`.
+    pub fn max_items(&self) -> Option<u64> {
+        self.total_pages()?.checked_mul(self.per_page?)
+    }
+
+    /// Whether there are pages after the current one.
+    pub fn has_next(&self) -> bool {
+        self.next_page.is_some()
+    }
+}
+
+impl From<&Header> for Pagination {
+    fn from(header: &Header) -> Pagination {
+        Pagination::with_params(header, "page", "per_page")
+    }
+}
+
+impl Header {
+    /// The pagination metadata using the `page` and `per_page` query params.
+    /// See `Pagination::with_params` for other names.
+    pub fn pagination(&self) -> Pagination {
+        Pagination::from(self)
+    }
+
+    /// The cursor of the `rel="next"` target, from the first of
+    /// `CURSOR_PARAMS` present in its query. `None` when there is no next page
+    /// or it has no cursor.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let header = parse(r#"<https://api.example/v1/items?limit=10&cursor=dXNlcjpXMDdRQ1JQQTQ%3D>; rel="next""#, None).unwrap();
+    ///
+    /// assert_eq!(header.next_cursor().as_deref(), Some("dXNlcjpXMDdRQ1JQQTQ="));
+    /// ```
+    pub fn next_cursor(&self) -> Option<String> {
+        self.next_cursor_with(CURSOR_PARAMS)
+    }
+
+    /// The cursor of the `rel="next"` target from the first of the given
+    /// query params present, percent-decoded.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let header = parse(r#"</events?since=2019-01-01&continuation=abc>; rel="next""#, None).unwrap();
+    ///
+    /// assert_eq!(header.next_cursor_with(&["continuation"]).as_deref(), Some("abc"));
+    /// assert_eq!(header.next_cursor(), None);
+    /// ```
+    pub fn next_cursor_with(&self, names: &[&str]) -> Option<String> {
+        let pairs: Vec<_> = self.next()?.target.query_pairs().collect();
+
+        names.iter().find_map(|name| {
+            pairs
+                .iter()
+                .find(|(key, value)| key == name && !value.is_empty())
+                .map(|(_, value)| value.to_string())
+        })
+    }
+}
+
+/// Generates the `first`, `prev`, `next` and `last` links of a page-based
+/// collection, the counterpart of `Pagination`.
+///
+/// Targets are the base URL with the page number and size set in its query,
+/// keeping any other query param. `last` requires the total count and `next`
+/// either the total count or `has_next`.
+///
+/// ```
+/// use linkheader::pagination::PageLinks;
+///
+/// let base = url::Url::parse("https://api.example/issues?state=open").unwrap();
+/// let header = PageLinks::new(base, 2, 30).total(95).build();
+///
+/// assert_eq!(
+///     header.to_string(),
+///     r#"<https://api.example/issues?state=open&page=1&per_page=30>; rel="first", <https://api.example/issues?state=open&page=1&per_page=30>; rel="prev", <https://api.example/issues?state=open&page=3&per_page=30>; rel="next", <https://api.example/issues?state=open&page=4&per_page=30>; rel="last""#
+/// );
+/// assert_eq!(header.pagination().current_page(), Some(2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PageLinks {
+    base: url::Url,
+    page: u64,
+    per_page: u64,
+    total: Option<u64>,
+    has_next: bool,
+    page_param: String,
+    per_page_param: String,
+}
+
+impl PageLinks {
+    /// The links for the given page, counting from 1, and page size.
+    pub fn new(base: url::Url, page: u64, per_page: u64) -> PageLinks {
+        PageLinks {
+            base,
+            page: page.max(1),
+            per_page: per_page.max(1),
+            total: None,
+            has_next: false,
+            page_param: "page".into(),
+            per_page_param: "per_page".into(),
+        }
+    }
+
+    /// Sets the total number of items.
+    pub fn total(mut self, total: u64) -> PageLinks {
+        self.total = Some(total);
+        self
+    }
+
+    /// Sets whether there is a next page when the total is unknown.
+    pub fn has_next(mut self, has_next: bool) -> PageLinks {
+        self.has_next = has_next;
+        self
+    }
+
+    /// Sets the query param names for the page number and size.
+    pub fn params(mut self, page: &str, per_page: &str) -> PageLinks {
+        self.page_param = page.into();
+        self.per_page_param = per_page.into();
+        self
+    }
+
+    /// The last page number, if the total is known. An empty collection has
+    /// one empty page.
+    pub fn last_page(&self) -> Option<u64> {
+        self.total.map(|total| total.div_ceil(self.per_page).max(1))
+    }
+
+    pub fn build(&self) -> Header {
+        let last = self.last_page();
+        let has_next = match last {
+            Some(last) => self.page < last,
+            None => self.has_next,
+        };
+        let mut pages = vec![("first", 1)];
+
+        if self.page > 1 {
+            let prev = match last {
+                Some(last) => (self.page - 1).min(last),
+                None => self.page - 1,
+            };
+
+            pages.push(("prev", prev));
+        }
+
+        if let Some(next) = self.page.checked_add(1).filter(|_| has_next) {
+            pages.push(("next", next));
+        }
+
+        if let Some(last) = last {
+            pages.push(("last", last));
+        }
+
+        pages
+            .into_iter()
+            .enumerate()
+            .flat_map(|(group, (relation, page))| {
+                let mut builder = LinkBuilder::new(None);
+                builder.set_group(group);
+                builder.set_target(self.url_for(page).as_str());
+                builder.set_rel(Value::Simple(relation.into()));
+                builder.build()
+            })
+            .collect()
+    }
+
+    fn url_for(&self, page: u64) -> url::Url {
+        let mut url = self.base.clone();
+        let pairs: Vec<(String, String)> = self
+            .base
+            .query_pairs()
+            .filter(|(key, _)| *key != self.page_param && *key != self.per_page_param)
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+
+        url.query_pairs_mut()
+            .clear()
+            .extend_pairs(pairs)
+            .append_pair(&self.page_param, &page.to_string())
+            .append_pair(&self.per_page_param, &self.per_page.to_string());
+
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn last_page() {
+        let input = r#"</items?page=1>; rel="first", </items?page=4>; rel="previous""#;
+        let pagination = parse(input, None).unwrap().pagination();
+
+        assert_eq!(pagination.prev_page, Some(4));
+        assert_eq!(pagination.current_page(), Some(5));
+        assert_eq!(pagination.total_pages(), Some(5));
+        assert_eq!(pagination.max_items(), None);
+        assert!(!pagination.has_next());
+    }
+
+    #[test]
+    fn unknown_total() {
+        let input = r#"</items?page=2&per_page=abc>; rel="next""#;
+        let pagination = parse(input, None).unwrap().pagination();
+
+        assert_eq!(pagination.current_page(), Some(1));
+        assert_eq!(pagination.total_pages(), None);
+        assert_eq!(pagination.per_page, None);
+    }
+
+    #[test]
+    fn huge_numbers() {
+        let input = format!(
+            r#"</items?page={max}&per_page=2>; rel="prev", </items?page={max}>; rel="last""#,
+            max = u64::MAX
+        );
+        let pagination = parse(&input, None).unwrap().pagination();
+
+        assert_eq!(pagination.current_page(), None);
+        assert_eq!(pagination.total_pages(), Some(u64::MAX));
+        assert_eq!(pagination.max_items(), None);
+
+        let base = url::Url::parse("https://api.example/items").unwrap();
+        let header = PageLinks::new(base, u64::MAX, 10).has_next(true).build();
+
+        assert_eq!(header.next(), None);
+    }
+
+    #[test]
+    fn next_page_below_two() {
+        for next in ["0", "1"] {
+            let input = format!(r#"</items?page={}>; rel="next""#, next);
+            let pagination = parse(&input, None).unwrap().pagination();
+
+            assert_eq!(pagination.current_page(), None);
+        }
+    }
+
+    #[test]
+    fn cursor_precedence() {
+        let input = r#"</items?after=b&cursor=>, </items?after=b&page_token=a>; rel="next""#;
+        let header = parse(input, None).unwrap();
+
+        assert_eq!(header.next_cursor().as_deref(), Some("a"));
+        assert_eq!(header.next_cursor_with(&["cursor"]), None);
+    }
+
+    #[test]
+    fn generated_links_roundtrip() {
+        let base = url::Url::parse("https://api.example/items?page=9&q=a+b").unwrap();
+        let header = PageLinks::new(base.clone(), 3, 10)
+            .has_next(true)
+            .params("page", "limit")
+            .build();
+        let pagination = Pagination::with_params(&header, "page", "limit");
+
+        assert_eq!(pagination.prev_page, Some(2));
+        assert_eq!(pagination.next_page, Some(4));
+        assert_eq!(pagination.last_page, None);
+        assert_eq!(pagination.per_page, Some(10));
+        assert_eq!(
+            header.links()[0].target.as_str(),
+            "https://api.example/items?q=a+b&page=1&limit=10"
+        );
+
+        let header = PageLinks::new(base, 7, 10).total(0).build();
+        let relations: Vec<_> = header
+            .iter()
+            .map(|link| link.relation.as_ref().unwrap().as_str().to_string())
+            .collect();
+
+        assert_eq!(relations, vec!["first", "prev", "last"]);
+        assert_eq!(header.pagination().prev_page, Some(1));
+    }
+}
@@ -4,8 +4,28 @@
 // This file may not be copied, modified, or distributed except
 // according to those terms.
 
-use percent_encoding::{utf8_percent_encode, DEFAULT_ENCODE_SET};
-use std::fmt::{self, Display};
+#[cfg(feature = "language-tags")]
+use crate::error::Result;
+use alloc::borrow::Cow;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+use core::fmt::{self, Display};
+#[cfg(feature = "encoding_rs")]
+use percent_encoding::{percent_decode, percent_encode};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+/// The octets percent-encoded in compound values: controls, non-ASCII,
+/// space and `"#<>?`{}`.
+const ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
 
 /// A link param pair.
 ///
@@ -44,8 +64,11 @@ use std::fmt::{self, Display};
 ///
 /// assert_eq!(param.name(), "rel");
 /// assert_eq!(param.value(), &Some(Value::Simple("next".into())));
+/// assert_eq!(param.to_string(), r#"rel="next""#);
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Param {
     name: String,
     value: Option<Value>,
@@ -75,9 +98,38 @@ impl Param {
     /// A star param e.g. "title*" is a param marked to identify that its value
     /// is a compound value.
     pub fn is_star(&self) -> bool {
-        match self.value {
-            Some(Value::Compound { .. }) => true,
-            _ => false,
+        matches!(self.value, Some(Value::Compound { .. }))
+    }
+}
+
+impl Display for Param {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt_param(formatter, &self.name, self.value.as_ref())
+    }
+}
+
+/// Writes a param in its link-value form, quoting simple values and marking
+/// compound ones as star params.
+pub(crate) fn fmt_param(
+    formatter: &mut fmt::Formatter,
+    name: &str,
+    value: Option<&Value>,
+) -> fmt::Result {
+    match value {
+        None => write!(formatter, "{}", name),
+        Some(value @ Value::Compound { .. }) => write!(formatter, "{}*={}", name, value),
+        Some(Value::Simple(value)) => {
+            write!(formatter, "{}=\"", name)?;
+
+            for c in value.chars() {
+                if c == '"' || c == '\\' {
+                    write!(formatter, "\\")?;
+                }
+
+                write!(formatter, "{}", c)?;
+            }
+
+            write!(formatter, "\"")
         }
     }
 }
@@ -96,6 +148,8 @@ impl Param {
 /// > Extension character encodings (mime-charset) are reserved for future
 /// > use.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Encoding {
     Utf8,
     Extension(String),
@@ -112,6 +166,41 @@ impl From<&str> for Encoding {
     }
 }
 
+#[cfg(feature = "encoding_rs")]
+impl Encoding {
+    /// The `encoding_rs` codec of an extension charset, if known and able to
+    /// encode back to the same charset.
+    fn codec(&self) -> Option<&'static encoding_rs::Encoding> {
+        match self {
+            Encoding::Utf8 => None,
+            Encoding::Extension(label) => encoding_rs::Encoding::for_label(label.as_bytes())
+                .filter(|codec| codec.output_encoding() == *codec),
+        }
+    }
+
+    /// Decodes a percent-encoded value in an extension charset.
+    ///
+    /// Returns `None` when the charset is unknown or the value malformed.
+    pub(crate) fn decode(&self, raw: &str) -> Option<String> {
+        let bytes: Vec<u8> = percent_decode(raw.as_bytes()).collect();
+
+        self.codec()?
+            .decode_without_bom_handling_and_without_replacement(&bytes)
+            .map(Cow::into_owned)
+    }
+
+    /// Percent-encodes a value in an extension charset.
+    ///
+    /// Returns `None` when the charset is unknown or cannot represent the
+    /// value.
+    fn encode(&self, text: &str) -> Option<String> {
+        match self.codec()?.encode(text) {
+            (_, _, true) => None,
+            (bytes, _, false) => Some(percent_encode(&bytes, ENCODE_SET).to_string()),
+        }
+    }
+}
+
 impl Display for Encoding {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -127,7 +216,9 @@ impl Display for Encoding {
 /// Note that RFC8187 names a compound value as "extended value".
 ///
 /// When the encoding of a compound value is not UTF-8, the value will be kept
-/// untouched, that is percent-encoded.
+/// untouched, that is percent-encoded. With the `encoding_rs` feature, values
+/// in a charset known to `encoding_rs` are decoded instead. `Value::raw`
+/// returns the percent-encoded form either way.
 ///
 /// ```
 /// use linkheader::param::Value;
@@ -161,6 +252,8 @@ impl Display for Encoding {
 /// assert_eq!(value.to_string(), "GIB''%C0%FF%EE".to_string());
 /// ```
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Value {
     Simple(String),
     Compound {
@@ -186,26 +279,91 @@ impl Value {
     /// Returns the text value from either simple or compound values.
     pub fn text(&self) -> &str {
         match self {
-            Value::Simple(value) => &value,
-            Value::Compound { value, .. } => &value,
+            Value::Simple(value) => value,
+            Value::Compound { value, .. } => value,
         }
     }
 
-    pub fn is_compound(&self) -> bool {
+    /// Returns the text as written in the header: percent-encoded in its
+    /// encoding for compound values.
+    ///
+    /// ```
+    /// use linkheader::param::{Encoding, Value};
+    ///
+    /// let value = Value::Compound {
+    ///     encoding: Encoding::Utf8,
+    ///     language: None,
+    ///     value: "£ rates".into(),
+    /// };
+    ///
+    /// assert_eq!(value.raw(), "%C2%A3%20rates");
+    /// ```
+    pub fn raw(&self) -> Cow<'_, str> {
         match self {
-            Value::Compound { .. } => true,
-            _ => false,
+            Value::Simple(value) => value.into(),
+            Value::Compound {
+                encoding, value, ..
+            } => Value::encode(encoding, value),
         }
     }
 
+    fn encode<'a>(encoding: &Encoding, value: &'a str) -> Cow<'a, str> {
+        match encoding {
+            Encoding::Utf8 => utf8_percent_encode(value, ENCODE_SET).into(),
+            #[cfg(feature = "encoding_rs")]
+            Encoding::Extension(_) => encoding.encode(value).map_or(value.into(), Cow::Owned),
+            #[cfg(not(feature = "encoding_rs"))]
+            Encoding::Extension(_) => value.into(),
+        }
+    }
+
+    pub fn is_compound(&self) -> bool {
+        matches!(self, Value::Compound { .. })
+    }
+
     pub fn is_simple(&self) -> bool {
+        matches!(self, Value::Simple(_))
+    }
+
+    /// Parses the language of a compound value as a validated language tag.
+    ///
+    /// Returns `None` for simple values and compound values with no language.
+    ///
+    /// ```
+    /// use linkheader::param::{Encoding, Value};
+    ///
+    /// let value = Value::Compound {
+    ///     encoding: Encoding::Utf8,
+    ///     language: Some("de-CH".into()),
+    ///     value: "nächstes Kapitel".into(),
+    /// };
+    /// let tag = value.language_tag().unwrap().unwrap();
+    ///
+    /// assert_eq!(tag.primary_language(), "de");
+    /// ```
+    #[cfg(feature = "language-tags")]
+    pub fn language_tag(&self) -> Option<Result<language_tags::LanguageTag>> {
         match self {
-            Value::Simple(_) => true,
-            _ => false,
+            Value::Compound {
+                language: Some(language),
+                ..
+            } if !language.is_empty() => Some(parse_language_tag(language)),
+            _ => None,
         }
     }
 }
 
+/// Parses a well-formed and valid language tag.
+#[cfg(feature = "language-tags")]
+pub(crate) fn parse_language_tag(tag: &str) -> Result<language_tags::LanguageTag> {
+    use crate::error::ParserError;
+
+    language_tags::LanguageTag::parse(tag)
+        .ok()
+        .filter(|language_tag| language_tag.is_valid())
+        .ok_or_else(|| ParserError::InvalidLanguageTag(tag.into()).into())
+}
+
 impl Display for Value {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -215,17 +373,12 @@ impl Display for Value {
                 language,
                 value,
             } => {
-                let val = match encoding {
-                    Encoding::Utf8 => utf8_percent_encode(value, DEFAULT_ENCODE_SET).to_string(),
-                    _ => value.to_string(),
-                };
-
                 write!(
                     formatter,
                     "{}'{}'{}",
                     encoding,
                     language.clone().unwrap_or("".into()),
-                    val
+                    Value::encode(encoding, value)
                 )
             }
         }
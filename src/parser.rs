@@ -5,11 +5,17 @@
 // according to those terms.
 
 use crate::error::{ParserError, Result};
+use crate::Context;
 use crate::{Encoding, Header, Link, Param, Value};
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{self, Display};
 use percent_encoding::percent_decode;
 pub use pest::{iterators::Pair, Parser};
-use std::fmt::{self, Display};
-use url;
 
 #[derive(Parser)]
 #[grammar = "rfc8288.pest"]
@@ -21,28 +27,201 @@ impl Display for Rule {
     }
 }
 
+/// Options to tune how forgiving the parser is.
+///
+/// ```
+/// use linkheader::parser::{parse_with_options, ParserOptions};
+///
+/// let options = ParserOptions::new().strict(true);
+/// let header = parse_with_options(r#"<https://example.org>; rel="next""#, None, &options);
+///
+/// assert!(header.is_ok());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParserOptions {
+    strict: bool,
+    anchor_policy: AnchorPolicy,
+    allowed_schemes: Option<(Vec<String>, SchemePolicy)>,
+}
+
+/// What to do with links whose target scheme is not allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemePolicy {
+    /// Leave the link out of the header.
+    Drop,
+    /// Fail with `ParserError::DisallowedScheme`.
+    Reject,
+}
+
+/// How to treat "anchor" params.
+///
+/// RFC8288 Section 3.2 notes that applications may need to ignore anchors
+/// to prevent a resource from making claims about other resources.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnchorPolicy {
+    /// The first anchor sets the link context.
+    #[default]
+    Honor,
+    /// Anchors never change the link context and are kept as ordinary
+    /// params.
+    Ignore,
+    /// The first anchor is recorded in the context but never resolved, so the
+    /// link is neither attributed to the default context nor to the anchor.
+    Isolate,
+}
+
+impl ParserOptions {
+    pub fn new() -> ParserOptions {
+        ParserOptions::default()
+    }
+
+    /// In strict mode, values the parser would otherwise tolerate are
+    /// rejected with an error.
+    ///
+    /// Targets must not contain whitespace, control characters nor malformed
+    /// percent-encoded octets.
+    ///
+    /// With the `language-tags` feature, "hreflang" values and the language of
+    /// compound values must be valid language tags.
+    pub fn strict(mut self, strict: bool) -> ParserOptions {
+        self.strict = strict;
+        self
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// ```
+    /// use linkheader::parser::{parse_with_options, AnchorPolicy, ParserOptions};
+    ///
+    /// let context = url::Url::parse("https://www.example.org/").ok();
+    /// let options = ParserOptions::new().anchor_policy(AnchorPolicy::Ignore);
+    /// let input = r#"</terms>; rel="copyright"; anchor="https://bank.example.com/""#;
+    /// let header = parse_with_options(input, context.clone(), &options).unwrap();
+    ///
+    /// assert_eq!(header.links()[0].context.url(), context.as_ref());
+    /// ```
+    pub fn anchor_policy(mut self, policy: AnchorPolicy) -> ParserOptions {
+        self.anchor_policy = policy;
+        self
+    }
+
+    /// Restricts link targets to the given schemes, e.g. to protect
+    /// consumers from `javascript:` or `data:` targets.
+    ///
+    /// Relative targets take the scheme from the link context and are allowed
+    /// when there is no context.
+    ///
+    /// ```
+    /// use linkheader::parser::{parse_with_options, ParserOptions, SchemePolicy};
+    ///
+    /// let input = r#"<javascript:alert(1)>; rel="help", <https://example.org/>; rel="home""#;
+    ///
+    /// let options = ParserOptions::new().allowed_schemes(&["http", "https"], SchemePolicy::Drop);
+    /// let header = parse_with_options(input, None, &options).unwrap();
+    /// assert_eq!(header.links().len(), 1);
+    ///
+    /// let options = ParserOptions::new().allowed_schemes(&["http", "https"], SchemePolicy::Reject);
+    /// assert!(parse_with_options(input, None, &options).is_err());
+    /// ```
+    pub fn allowed_schemes(mut self, schemes: &[&str], policy: SchemePolicy) -> ParserOptions {
+        let schemes = schemes.iter().map(|scheme| scheme.to_string()).collect();
+
+        self.allowed_schemes = Some((schemes, policy));
+        self
+    }
+}
+
+#[cfg(feature = "std")]
 pub fn parse(input: &str, context: Option<url::Url>) -> Result<Header> {
-    let rule = Rfc8288Parser::parse(Rule::header, &input)
-        .expect("unsuccessful parse")
-        .next()
-        .unwrap();
+    parse_with_options(input, context, &ParserOptions::default())
+}
+
+/// Parses a header with no known context, as `parse(input, None)` does.
+///
+/// This is the entry point without the `std` feature, where links have no
+/// context and "anchor" params are kept as ordinary params.
+///
+/// ```
+/// use linkheader::parse_without_context;
+///
+/// let header = parse_without_context(r#"</TheBook/chapter4>; rel="next""#).unwrap();
+///
+/// assert_eq!(header.links()[0].target.as_str(), "/TheBook/chapter4");
+/// ```
+pub fn parse_without_context(input: &str) -> Result<Header> {
+    parse_rule(
+        Rule::header,
+        input,
+        LinkBuilder::default(),
+        &ParserOptions::default(),
+    )
+}
+
+/// Parses a header as `parse` does but with the given options.
+///
+/// With the `tracing` feature, each call runs in a "parse" span and malformed
+/// values are reported as warnings.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(len = input.len()), err(level = "warn", Display))
+)]
+#[cfg(feature = "std")]
+pub fn parse_with_options(
+    input: &str,
+    context: Option<url::Url>,
+    options: &ParserOptions,
+) -> Result<Header> {
+    parse_rule(Rule::header, input, LinkBuilder::new(context), options)
+}
+
+/// Parses an `application/linkset` document (RFC9264 Section 4.1) with the
+/// given options. See `linkset::parse`.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(len = input.len()), err(level = "warn", Display))
+)]
+#[cfg(feature = "std")]
+pub(crate) fn parse_linkset_with_options(
+    input: &str,
+    context: Option<url::Url>,
+    options: &ParserOptions,
+) -> Result<Header> {
+    parse_rule(Rule::linkset, input, LinkBuilder::new(context), options)
+}
 
-    collect_header(rule, context)
+/// Parses the input as the given rule, building every link from a copy of
+/// `template`, i.e. a builder with the default context.
+pub(crate) fn parse_rule(
+    rule: Rule,
+    input: &str,
+    template: LinkBuilder,
+    options: &ParserOptions,
+) -> Result<Header> {
+    let pair = Rfc8288Parser::parse(rule, input)?.next().unwrap();
+
+    collect_header(pair, template, options)
 }
 
-fn collect_header(pair: Pair<Rule>, context: Option<url::Url>) -> Result<Header> {
-    ensure!(
-        pair.as_rule() == Rule::header,
-        ParserError::InvalidRule(Rule::header.into(), pair.as_rule().into())
-    );
+fn collect_header(
+    pair: Pair<Rule>,
+    template: LinkBuilder,
+    options: &ParserOptions,
+) -> Result<Header> {
+    if pair.as_rule() != Rule::header && pair.as_rule() != Rule::linkset {
+        return Err(ParserError::InvalidRule(Rule::header, pair.as_rule()).into());
+    }
 
     let mut links = vec![];
+    let mut group = 0;
 
     for inner_pair in pair.into_inner() {
         match inner_pair.as_rule() {
-            Rule::link => {
-                let link = collect_links(inner_pair, context.clone())?;
-                links.extend(link);
+            Rule::link | Rule::multiline_link => {
+                let link = collect_links(inner_pair, template.clone(), group, options)?;
+                links.extend(check_schemes(link, options)?);
+                group += 1;
             }
 
             Rule::EOI => (),
@@ -51,57 +230,91 @@ fn collect_header(pair: Pair<Rule>, context: Option<url::Url>) -> Result<Header>
         }
     }
 
-    Ok(Header { links })
+    Ok(Header::from(links))
 }
 
 /// Collects attributes and params for a set of links.
-#[derive(Debug, Clone)]
+///
+/// Without the `std` feature the link context is always absent: builders come from
+/// `LinkBuilder::default()` and "anchor" params are kept as ordinary params.
+#[derive(Debug, Clone, Default)]
 pub struct LinkBuilder {
     target: String,
+    #[cfg(feature = "std")]
     context: Option<url::Url>,
-    anchored_context: Option<url::Url>,
+    #[cfg(feature = "std")]
+    anchored_context: Option<Context>,
     relations: Vec<String>,
     title: Option<Value>,
     lang: Vec<Value>,
     media: Option<Value>,
     content_type: Option<Value>,
     params: Vec<Param>,
+    group: Option<usize>,
+    anchor_policy: AnchorPolicy,
+    /// Whether tolerated values are reported, i.e. when parsing a `Link`
+    /// header.
+    report: bool,
 }
 
 impl LinkBuilder {
+    #[cfg(feature = "std")]
     pub fn new(context: Option<url::Url>) -> LinkBuilder {
         LinkBuilder {
-            target: String::new(),
             context,
-            anchored_context: None,
-            title: None,
-            lang: vec![],
-            media: None,
-            content_type: None,
-            params: vec![],
-            relations: vec![],
+            ..LinkBuilder::default()
         }
     }
 
+    pub fn set_anchor_policy(&mut self, policy: AnchorPolicy) {
+        self.anchor_policy = policy;
+    }
+
+    /// Sets the position of the link-value shared by all built links.
+    pub fn set_group(&mut self, group: usize) {
+        self.group = Some(group);
+    }
+
     pub fn set_target(&mut self, target: &str) {
         self.target.push_str(target);
     }
 
+    /// Sets the context from the first anchor following the anchor policy.
+    /// Any other anchor is kept as a param.
+    #[cfg(feature = "std")]
     pub fn set_anchor(&mut self, value: Value) {
-        match self.anchored_context {
-            None => {
-                self.anchored_context = compose_context(&self.context, &value.to_string());
-
-                // We keep the anchor param if it is not composable with
-                // the given context to preserve information.
-                if self.anchored_context.is_none() {
-                    self.params.push(Param::new("anchor", Some(value)));
+        match (self.anchor_policy, &self.anchored_context) {
+            (AnchorPolicy::Honor, None) => {
+                self.anchored_context = Some(Context::anchored(self.context.clone(), value.text()));
+            }
+            (AnchorPolicy::Isolate, None) => {
+                self.anchored_context = Some(Context::Anchored {
+                    base: self.context.clone(),
+                    anchor: value.text().into(),
+                    resolved: None,
+                });
+            }
+            (AnchorPolicy::Ignore, None) => {
+                #[cfg(feature = "tracing")]
+                if self.report {
+                    tracing::debug!(anchor = value.text(), "anchor ignored by policy");
                 }
+
+                self.params.push(Param::new("anchor", Some(value)));
+            }
+            _ => {
+                self.duplicate("anchor");
+                self.params.push(Param::new("anchor", Some(value)));
             }
-            _ => self.params.push(Param::new("anchor", Some(value))),
         }
     }
 
+    /// Keeps the anchor as a param: the context is always absent without `std`.
+    #[cfg(not(feature = "std"))]
+    pub fn set_anchor(&mut self, value: Value) {
+        self.params.push(Param::new("anchor", Some(value)));
+    }
+
     pub fn set_title(&mut self, value: Value) {
         match &self.title {
             Some(current_value) => {
@@ -110,6 +323,10 @@ impl LinkBuilder {
                         .push(Param::new("title", Some(current_value.clone())));
                     self.title = Some(value);
                 } else {
+                    if current_value.is_compound() == value.is_compound() {
+                        self.duplicate("title");
+                    }
+
                     self.params.push(Param::new("title", Some(value)));
                 }
             }
@@ -127,6 +344,7 @@ impl LinkBuilder {
 
             self.relations.extend(values);
         } else {
+            self.duplicate("rel");
             self.params.push(Param::new("rel", Some(value)));
         }
     }
@@ -139,14 +357,20 @@ impl LinkBuilder {
     pub fn set_media(&mut self, value: Value) {
         match self.media {
             None => self.media = Some(value),
-            Some(_) => self.params.push(Param::new("media", Some(value))),
+            Some(_) => {
+                self.duplicate("media");
+                self.params.push(Param::new("media", Some(value)));
+            }
         }
     }
 
     pub fn set_type(&mut self, value: Value) {
         match self.content_type {
             None => self.content_type = Some(value),
-            Some(_) => self.params.push(Param::new("type", Some(value))),
+            Some(_) => {
+                self.duplicate("type");
+                self.params.push(Param::new("type", Some(value)));
+            }
         }
     }
 
@@ -156,18 +380,25 @@ impl LinkBuilder {
 
     pub fn build(self) -> Vec<Link> {
         let mut result = vec![];
-        let context = self.anchored_context.or(self.context);
+        #[cfg(feature = "std")]
+        let context = match self.anchored_context {
+            Some(context) => context,
+            None => self.context.into(),
+        };
+        #[cfg(not(feature = "std"))]
+        let context = Context::Absent;
 
         if self.relations.is_empty() {
             return vec![Link {
                 target: self.target.into(),
-                context: context,
+                context,
                 relation: None,
                 title: self.title,
                 lang: self.lang,
                 media: self.media,
                 content_type: self.content_type,
                 params: self.params,
+                group: self.group,
             }];
         }
 
@@ -181,30 +412,57 @@ impl LinkBuilder {
                 media: self.media.clone(),
                 content_type: self.content_type.clone(),
                 params: self.params.to_vec(),
+                group: self.group,
             });
         }
 
         result
     }
+
+    /// Reports a repeated attribute, kept as an ordinary param.
+    fn duplicate(&self, name: &str) {
+        #[cfg(feature = "tracing")]
+        if self.report {
+            tracing::warn!(param = name, "duplicate param kept as an ordinary param");
+        }
+        #[cfg(not(feature = "tracing"))]
+        let _ = (name, self.report);
+    }
 }
 
-fn collect_links(pair: Pair<Rule>, context: Option<url::Url>) -> Result<Vec<Link>> {
-    ensure!(
-        pair.as_rule() == Rule::link,
-        ParserError::InvalidRule(Rule::link, pair.as_rule())
-    );
+fn collect_links(
+    pair: Pair<Rule>,
+    mut link_builder: LinkBuilder,
+    group: usize,
+    options: &ParserOptions,
+) -> Result<Vec<Link>> {
+    if pair.as_rule() != Rule::link && pair.as_rule() != Rule::multiline_link {
+        return Err(ParserError::InvalidRule(Rule::link, pair.as_rule()).into());
+    }
 
-    let mut link_builder = LinkBuilder::new(context.clone());
+    link_builder.set_group(group);
+    link_builder.set_anchor_policy(options.anchor_policy);
+    link_builder.report = true;
 
     for inner_pair in pair.into_inner() {
         match inner_pair.as_rule() {
             Rule::target => {
+                if options.strict {
+                    check_target(inner_pair.as_str())?;
+                } else if inner_pair.as_str().contains(char::is_whitespace) {
+                    return Err(whitespace_in_target(inner_pair.as_span()).into());
+                }
+
                 link_builder.set_target(inner_pair.as_str());
             }
 
             Rule::param => {
                 let param = collect_param(inner_pair)?;
 
+                if options.strict {
+                    check_param(&param)?;
+                }
+
                 match (param.name(), param.value()) {
                     ("rel", Some(value)) => link_builder.set_rel(value.clone()),
                     ("anchor", Some(value)) => link_builder.set_anchor(value.clone()),
@@ -223,23 +481,81 @@ fn collect_links(pair: Pair<Rule>, context: Option<url::Url>) -> Result<Vec<Link
     Ok(link_builder.build())
 }
 
-/// Returns a context combined with the given anchor.
-///
-/// Note that when no context is present, if the anchor is not an absolute URL,
-/// the result is no context.
-fn compose_context(context: &Option<url::Url>, anchor: &str) -> Option<url::Url> {
-    match context {
-        Some(ctx) => ctx.join(anchor).ok(),
+/// Checks the target has no whitespace, control characters nor malformed
+/// percent-encoded octets.
+fn check_target(target: &str) -> Result<()> {
+    let malformed_pct = target.match_indices('%').any(|(idx, _)| {
+        !target
+            .get(idx + 1..idx + 3)
+            .is_some_and(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))
+    });
+
+    if malformed_pct || target.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(ParserError::InvalidTarget(target.into()).into());
+    }
+
+    Ok(())
+}
 
-        None => url::Url::parse(anchor).ok(),
+/// Whitespace in a target is a syntax error outside strict mode, where it is
+/// an invalid target (see `check_target`).
+fn whitespace_in_target(span: pest::Span) -> pest::error::Error<Rule> {
+    let variant = pest::error::ErrorVariant::CustomError {
+        message: "whitespace in target".into(),
+    };
+
+    pest::error::Error::new_from_span(variant, span)
+}
+
+/// Drops or rejects links with disallowed schemes.
+fn check_schemes(links: Vec<Link>, options: &ParserOptions) -> Result<Vec<Link>> {
+    let (schemes, policy) = match &options.allowed_schemes {
+        Some((schemes, policy)) => (schemes, policy),
+        None => return Ok(links),
+    };
+    let schemes: Vec<&str> = schemes.iter().map(|scheme| scheme.as_str()).collect();
+    let mut result = Vec::with_capacity(links.len());
+
+    for link in links {
+        if link.has_scheme_in(&schemes) {
+            result.push(link);
+        } else if *policy == SchemePolicy::Reject {
+            return Err(ParserError::DisallowedScheme(link.target.into_string()).into());
+        } else {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                target = link.target.as_str(),
+                "link dropped by scheme policy"
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+/// Checks the param value is well-formed for the param name.
+#[cfg_attr(not(feature = "language-tags"), allow(unused_variables))]
+fn check_param(param: &Param) -> Result<()> {
+    #[cfg(feature = "language-tags")]
+    {
+        if let Some(value) = param.value() {
+            if param.name() == "hreflang" {
+                crate::param::parse_language_tag(value.text())?;
+            }
+
+            if let Some(tag) = value.language_tag() {
+                tag?;
+            }
+        }
     }
+
+    Ok(())
 }
 
 fn collect_param(pair: Pair<Rule>) -> Result<Param> {
-    ensure!(
-        pair.as_rule() == Rule::param,
-        ParserError::InvalidRule(Rule::param, pair.as_rule())
-    );
+    if pair.as_rule() != Rule::param {
+        return Err(ParserError::InvalidRule(Rule::param, pair.as_rule()).into());
+    }
 
     let mut name = String::new();
     let mut value = None;
@@ -268,7 +584,7 @@ fn collect_param(pair: Pair<Rule>) -> Result<Param> {
 
                 Some(enc) => {
                     value = Some(Value::Compound {
-                        value: inner_pair.as_str().into(),
+                        value: decode_extension(enc, inner_pair.as_str()),
                         encoding: enc.clone(),
                         language: language.clone(),
                     });
@@ -292,25 +608,42 @@ fn collect_param(pair: Pair<Rule>) -> Result<Param> {
     Ok(Param::new(name, value))
 }
 
+/// Decodes a value in an extension charset known to `encoding_rs`, keeping it
+/// percent-encoded otherwise.
+#[cfg(feature = "encoding_rs")]
+fn decode_extension(encoding: &Encoding, raw: &str) -> String {
+    encoding.decode(raw).unwrap_or_else(|| raw.into())
+}
+
+#[cfg(not(feature = "encoding_rs"))]
+fn decode_extension(_encoding: &Encoding, raw: &str) -> String {
+    raw.into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Encoding, Header, Link, Param, Value};
+    use crate::Param;
+    #[cfg(feature = "std")]
+    use crate::{Context, Encoding, Header, Link, Value};
 
+    #[cfg(feature = "std")]
     #[test]
     fn single_link() {
         let input = r#"<https://example.org>"#;
         let expected = Header {
             links: vec![Link {
                 target: "https://example.org".into(),
-                context: None,
+                context: Context::Absent,
                 relation: None,
                 title: None,
                 lang: vec![],
                 media: None,
                 content_type: None,
                 params: vec![],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -318,6 +651,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn multiple_links() {
         let input =
@@ -326,25 +660,28 @@ mod tests {
             links: vec![
                 Link {
                     target: "https://example.org/3".into(),
-                    context: None,
+                    context: Context::Absent,
                     relation: Some("next".into()),
                     title: None,
                     lang: vec![],
                     media: None,
                     content_type: None,
                     params: vec![],
+                    group: Some(0),
                 },
                 Link {
                     target: "https://example.org/1".into(),
-                    context: None,
+                    context: Context::Absent,
                     relation: Some("previous".into()),
                     title: None,
                     lang: vec![],
                     media: None,
                     content_type: None,
                     params: vec![],
+                    group: Some(1),
                 },
             ],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -352,6 +689,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn link_header_field_examples_1() {
         let input =
@@ -360,14 +698,16 @@ mod tests {
         let expected = Header {
             links: vec![Link {
                 target: "http://example.com/TheBook/chapter2".into(),
-                context: None,
+                context: Context::Absent,
                 relation: Some("previous".into()),
                 title: Some("previous chapter".into()),
                 lang: vec![],
                 media: None,
                 content_type: None,
                 params: vec![],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -375,6 +715,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn link_header_field_examples_2() {
         let input = r#"</>; rel="http://example.net/foo""#;
@@ -382,14 +723,16 @@ mod tests {
         let expected = Header {
             links: vec![Link {
                 target: "/".into(),
-                context: None,
+                context: Context::Absent,
                 relation: Some("http://example.net/foo".into()),
                 title: None,
                 lang: vec![],
                 media: None,
                 content_type: None,
                 params: vec![],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -397,12 +740,17 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn link_header_field_examples_3() {
         let input = "</terms>; rel=\"copyright\"; anchor=\"#foo\"";
 
         let context = url::Url::parse("https://www.example.org/").ok();
-        let expected_context = url::Url::parse("https://www.example.org/#foo").ok();
+        let expected_context = Context::Anchored {
+            base: context.clone(),
+            anchor: "#foo".into(),
+            resolved: url::Url::parse("https://www.example.org/#foo").ok(),
+        };
 
         let expected = Header {
             links: vec![Link {
@@ -414,7 +762,9 @@ mod tests {
                 media: None,
                 content_type: None,
                 params: vec![],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, context).expect("Expect a valid header");
@@ -422,6 +772,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn link_header_field_examples_4() {
         let input = r#"</TheBook/chapter2>; rel="previous"; title*=UTF-8'de'letztes%20Kapitel, </TheBook/chapter4>; rel="next"; title*=UTF-8'de'n%c3%a4chstes%20Kapitel"#;
@@ -430,7 +781,7 @@ mod tests {
             links: vec![
                 Link {
                     target: "/TheBook/chapter2".into(),
-                    context: None,
+                    context: Context::Absent,
                     relation: Some("previous".into()),
                     title: Some(Value::Compound {
                         value: "letztes Kapitel".into(),
@@ -441,10 +792,11 @@ mod tests {
                     media: None,
                     content_type: None,
                     params: vec![],
+                    group: Some(0),
                 },
                 Link {
                     target: "/TheBook/chapter4".into(),
-                    context: None,
+                    context: Context::Absent,
                     relation: Some("next".into()),
                     title: Some(Value::Compound {
                         value: "nächstes Kapitel".into(),
@@ -455,8 +807,10 @@ mod tests {
                     media: None,
                     content_type: None,
                     params: vec![],
+                    group: Some(1),
                 },
             ],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -464,6 +818,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn link_header_field_examples_5() {
         let input = r#"<http://example.org/>; rel="start http://example.net/relation/other""#;
@@ -472,25 +827,28 @@ mod tests {
             links: vec![
                 Link {
                     target: "http://example.org/".into(),
-                    context: None,
+                    context: Context::Absent,
                     relation: Some("start".into()),
                     title: None,
                     lang: vec![],
                     media: None,
                     content_type: None,
                     params: vec![],
+                    group: Some(0),
                 },
                 Link {
                     target: "http://example.org/".into(),
-                    context: None,
+                    context: Context::Absent,
                     relation: Some("http://example.net/relation/other".into()),
                     title: None,
                     lang: vec![],
                     media: None,
                     content_type: None,
                     params: vec![],
+                    group: Some(0),
                 },
             ],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -498,6 +856,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn prefer_star_title() {
         let input = r#"</TheBook/chapter2>; rel="previous"; title="letztes Kapitel"; title*=UTF-8'de'letztes%20Kapitel"#;
@@ -505,7 +864,7 @@ mod tests {
         let expected = Header {
             links: vec![Link {
                 target: "/TheBook/chapter2".into(),
-                context: None,
+                context: Context::Absent,
                 relation: Some("previous".into()),
                 title: Some(Value::Compound {
                     value: "letztes Kapitel".into(),
@@ -516,7 +875,9 @@ mod tests {
                 media: None,
                 content_type: None,
                 params: vec![Param::new("title", Some("letztes Kapitel".into()))],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -524,6 +885,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn tolerate_extra_rel() {
         let input = r#"<http://example.org/>; rel="next"; rel="wrong""#;
@@ -531,14 +893,16 @@ mod tests {
         let expected = Header {
             links: vec![Link {
                 target: "http://example.org/".into(),
-                context: None,
+                context: Context::Absent,
                 relation: Some("next".into()),
                 title: None,
                 lang: vec![],
                 media: None,
                 content_type: None,
                 params: vec![Param::new("rel", Some("wrong".into()))],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -546,6 +910,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn preserve_context() {
         let input = r#"<http://example.org/>; rel="next""#;
@@ -555,14 +920,16 @@ mod tests {
         let expected = Header {
             links: vec![Link {
                 target: "http://example.org/".into(),
-                context: context.clone(),
+                context: Context::from(context.clone()),
                 relation: Some("next".into()),
                 title: None,
                 lang: vec![],
                 media: None,
                 content_type: None,
                 params: vec![],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, context).expect("Expect a valid header");
@@ -570,21 +937,28 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
-    fn ignore_anchor_with_no_context() {
+    fn keep_anchor_with_no_context() {
         let input = r##"<http://example.org/>; rel="next"; anchor="#foo""##;
 
         let expected = Header {
             links: vec![Link {
                 target: "http://example.org/".into(),
-                context: None,
+                context: Context::Anchored {
+                    base: None,
+                    anchor: "#foo".into(),
+                    resolved: None,
+                },
                 relation: Some("next".into()),
                 title: None,
                 lang: vec![],
                 media: None,
                 content_type: None,
-                params: vec![Param::new("anchor", Some("#foo".into()))],
+                params: vec![],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -592,6 +966,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn lang_attribute() {
         let input = r#"<https://ca.example.org>; rel="alternate"; hreflang="ca""#;
@@ -599,14 +974,16 @@ mod tests {
         let expected = Header {
             links: vec![Link {
                 target: "https://ca.example.org".into(),
-                context: None,
+                context: Context::Absent,
                 relation: Some("alternate".into()),
                 title: None,
                 lang: vec!["ca".into()],
                 media: None,
                 content_type: None,
                 params: vec![],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -614,6 +991,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn media_attribute() {
         let input = r#"<https://www.example.org>; rel="canonical"; media="screen""#;
@@ -621,14 +999,16 @@ mod tests {
         let expected = Header {
             links: vec![Link {
                 target: "https://www.example.org".into(),
-                context: None,
+                context: Context::Absent,
                 relation: Some("canonical".into()),
                 title: None,
                 lang: vec![],
                 media: Some("screen".into()),
                 content_type: None,
                 params: vec![],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -636,6 +1016,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn type_attribute() {
         let input = r#"<https://www.example.org>; rel="canonical"; type="text/html""#;
@@ -643,14 +1024,16 @@ mod tests {
         let expected = Header {
             links: vec![Link {
                 target: "https://www.example.org".into(),
-                context: None,
+                context: Context::Absent,
                 relation: Some("canonical".into()),
                 title: None,
                 lang: vec![],
                 media: None,
                 content_type: Some("text/html".into()),
                 params: vec![],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -658,12 +1041,17 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn tolerate_extra_anchor() {
         let input = "</terms>; rel=\"copyright\"; anchor=\"#foo\"; anchor=\"#bar\"";
 
         let context = url::Url::parse("https://www.example.org/").ok();
-        let expected_context = url::Url::parse("https://www.example.org/#foo").ok();
+        let expected_context = Context::Anchored {
+            base: context.clone(),
+            anchor: "#foo".into(),
+            resolved: url::Url::parse("https://www.example.org/#foo").ok(),
+        };
 
         let expected = Header {
             links: vec![Link {
@@ -675,7 +1063,9 @@ mod tests {
                 media: None,
                 content_type: None,
                 params: vec![Param::new("anchor", Some("#bar".into()))],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, context).expect("Expect a valid header");
@@ -683,6 +1073,209 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "language-tags")]
+    #[test]
+    fn strict_language_tags() {
+        let options = ParserOptions::new().strict(true);
+
+        let input = r#"<https://example.org>; hreflang="en-GB"; title*=UTF-8'de'Kapitel"#;
+        assert!(parse_with_options(input, None, &options).is_ok());
+
+        let input = r#"<https://example.org>; hreflang="en_GB""#;
+        assert!(parse_with_options(input, None, &options).is_err());
+        assert!(parse(input, None).is_ok());
+
+        let input = r#"<https://example.org>; title*=UTF-8'1234'Kapitel"#;
+        assert!(parse_with_options(input, None, &options).is_err());
+    }
+
+    #[test]
+    fn unquoted_and_valueless_params() {
+        let input = r#"</style.css>;rel=preload;as=style;nopush, </app.js>; rel=preload"#;
+        let header = parse_without_context(input).expect("Expect a valid header");
+
+        assert_eq!(header.links().len(), 2);
+        assert_eq!(header.links()[0].relation, Some("preload".into()));
+        assert_eq!(
+            header.links()[0].params,
+            vec![
+                Param::new("as", Some("style".into())),
+                Param::new("nopush", None)
+            ]
+        );
+    }
+
+    #[test]
+    fn params_end_at_separators() {
+        let header = parse_without_context("</a>; nopush, </b>").expect("Expect a valid header");
+
+        assert_eq!(header.links().len(), 2);
+        assert_eq!(header.links()[0].params, vec![Param::new("nopush", None)]);
+        assert_eq!(header.links()[1].target, "/b".into());
+
+        let header =
+            parse_without_context("</a>; as=style;crossorigin").expect("Expect a valid header");
+
+        assert_eq!(
+            header.links()[0].params,
+            vec![
+                Param::new("as", Some("style".into())),
+                Param::new("crossorigin", None)
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn isolate_anchor() {
+        let input = r#"</terms>; rel="copyright"; anchor="https://bank.example.com/""#;
+        let context = url::Url::parse("https://www.example.org/").ok();
+        let options = ParserOptions::new().anchor_policy(AnchorPolicy::Isolate);
+
+        let header = parse_with_options(input, context.clone(), &options).unwrap();
+
+        assert_eq!(
+            header.links()[0].context,
+            Context::Anchored {
+                base: context,
+                anchor: "https://bank.example.com/".into(),
+                resolved: None,
+            }
+        );
+        assert_eq!(header.to_string(), input);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn strict_targets() {
+        let options = ParserOptions::new().strict(true);
+        let cases = vec!["</a\u{7}>", "</a%2>", "</a%zz>", "</a%>"];
+
+        for input in cases {
+            assert!(parse(input, None).is_ok(), "{}", input);
+            assert!(
+                parse_with_options(input, None, &options).is_err(),
+                "{}",
+                input
+            );
+        }
+
+        assert!(parse_with_options("</a%20%C3%A0>", None, &options).is_ok());
+
+        // Whitespace is a syntax error, reported as an invalid target in
+        // strict mode.
+        assert!(parse("</a b>", None)
+            .unwrap_err()
+            .downcast::<pest::error::Error<Rule>>()
+            .is_ok());
+        assert!(parse_with_options("<", None, &options).is_err());
+        assert!(parse_with_options("<à", None, &options).is_err());
+        assert_eq!(
+            parse_with_options(r#"</a>, </a b>; rel="next""#, None, &options)
+                .unwrap_err()
+                .downcast::<ParserError>()
+                .unwrap(),
+            ParserError::InvalidTarget("/a b".into())
+        );
+        assert!(parse_with_options("</a b", None, &options)
+            .unwrap_err()
+            .downcast::<pest::error::Error<Rule>>()
+            .is_ok());
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn legacy_charsets() {
+        let input = r#"</a>; title*=iso-8859-1'en'%A3%20rates, </b>; title*=x-unknown''%A3"#;
+        let header = parse(input, None).expect("Expect a valid header");
+        let titles: Vec<_> = header
+            .iter()
+            .filter_map(|link| link.title.as_ref())
+            .collect();
+
+        assert_eq!(titles[0].text(), "£ rates");
+        assert_eq!(titles[0].raw(), "%A3%20rates");
+        assert_eq!(titles[1].text(), "%A3");
+        assert_eq!(header.to_string(), input);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn warn_on_tolerated_values() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Level, Metadata};
+
+        /// Counts warnings.
+        struct Warnings(Arc<AtomicUsize>);
+
+        impl tracing::Subscriber for Warnings {
+            fn enabled(&self, _: &Metadata) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event) {
+                if *event.metadata().level() == Level::WARN {
+                    self.0.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let input = r##"</a>; rel="next"; rel="prev"; anchor="#a"; anchor="#b""##;
+
+        tracing::subscriber::with_default(Warnings(count.clone()), || {
+            parse(input, None).unwrap();
+            assert_eq!(count.load(Ordering::SeqCst), 2);
+
+            parse("<", None).unwrap_err();
+            assert_eq!(count.load(Ordering::SeqCst), 3);
+
+            let mut builder = LinkBuilder::new(None);
+            builder.set_rel(Value::Simple("next".into()));
+            builder.set_rel(Value::Simple("prev".into()));
+            builder.build();
+            assert_eq!(count.load(Ordering::SeqCst), 3);
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn without_context() {
+        let input = r##"</a>; rel="next"; anchor="#b""##;
+
+        assert_eq!(
+            parse_without_context(input).unwrap(),
+            parse(input, None).unwrap()
+        );
+        assert!(parse_without_context("</a>, </b").is_err());
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[test]
+    fn anchor_is_a_param_without_std() {
+        let header = parse_without_context(r##"</a>; rel="next"; anchor="#b""##).unwrap();
+        let link = &header.links()[0];
+
+        assert!(link.context.is_absent());
+        assert_eq!(link.params, vec![Param::new("anchor", Some("#b".into()))]);
+        assert!(parse_without_context("<https://example.org").is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn syntax_error() {
+        assert!(parse("<https://example.org", None).is_err());
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn unicode_fest() {
         let input = "<http://example.org/\u{FE0F}>; rel=\"\u{1F383}\"";
@@ -690,14 +1283,16 @@ mod tests {
         let expected = Header {
             links: vec![Link {
                 target: "http://example.org/\u{FE0F}".into(),
-                context: None,
+                context: Context::Absent,
                 relation: Some("🎃".into()),
                 title: None,
                 lang: vec![],
                 media: None,
                 content_type: None,
                 params: vec![],
+                group: Some(0),
             }],
+            ..Header::default()
         };
 
         let actual = parse(input, None).expect("Expect a valid header");
@@ -0,0 +1,617 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Attributes for preload links (`rel=preload`, `rel=modulepreload`) and other
+//! resource hints, and a builder for `103 Early Hints` responses.
+//!
+//! See the [HTML Living Standard](https://html.spec.whatwg.org/multipage/links.html#link-type-preload).
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::param::{Param, Value};
+use crate::parser::LinkBuilder;
+use std::fmt::{self, Display};
+
+/// The request destination given by the "as" attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Destination {
+    Audio,
+    Document,
+    Embed,
+    Fetch,
+    Font,
+    Image,
+    Json,
+    Manifest,
+    Object,
+    Report,
+    Script,
+    ServiceWorker,
+    SharedWorker,
+    Style,
+    Track,
+    Video,
+    Worker,
+    Xslt,
+    Other(String),
+}
+
+impl From<&str> for Destination {
+    fn from(s: &str) -> Destination {
+        let sl = s.to_lowercase();
+
+        match &sl[..] {
+            "audio" => Destination::Audio,
+            "document" => Destination::Document,
+            "embed" => Destination::Embed,
+            "fetch" => Destination::Fetch,
+            "font" => Destination::Font,
+            "image" => Destination::Image,
+            "json" => Destination::Json,
+            "manifest" => Destination::Manifest,
+            "object" => Destination::Object,
+            "report" => Destination::Report,
+            "script" => Destination::Script,
+            "serviceworker" => Destination::ServiceWorker,
+            "sharedworker" => Destination::SharedWorker,
+            "style" => Destination::Style,
+            "track" => Destination::Track,
+            "video" => Destination::Video,
+            "worker" => Destination::Worker,
+            "xslt" => Destination::Xslt,
+            _ => Destination::Other(sl),
+        }
+    }
+}
+
+impl Destination {
+    /// Infers the destination of an asset from the extension of its path,
+    /// ignoring any query or fragment.
+    ///
+    /// ```
+    /// use linkheader::preload::Destination;
+    ///
+    /// assert_eq!(Destination::from_extension("/app.css?v=2"), Some(Destination::Style));
+    /// assert_eq!(Destination::from_extension("https://cdn.example/f.WOFF2"), Some(Destination::Font));
+    /// assert_eq!(Destination::from_extension("/about"), None);
+    /// ```
+    pub fn from_extension(path: &str) -> Option<Destination> {
+        let path = path.split(['?', '#']).next().unwrap_or(path);
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let (_, extension) = name.rsplit_once('.')?;
+
+        let destination = match &extension.to_lowercase()[..] {
+            "css" => Destination::Style,
+            "js" | "mjs" => Destination::Script,
+            "woff" | "woff2" | "ttf" | "otf" => Destination::Font,
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "avif" | "svg" | "ico" => Destination::Image,
+            "mp4" | "webm" => Destination::Video,
+            "mp3" | "ogg" | "wav" => Destination::Audio,
+            "vtt" => Destination::Track,
+            "json" => Destination::Fetch,
+            _ => return None,
+        };
+
+        Some(destination)
+    }
+
+    /// Whether the destination is always fetched in CORS mode so its preload
+    /// needs a "crossorigin" attribute to be reused.
+    fn requires_cors(&self) -> bool {
+        matches!(self, Destination::Font | Destination::Fetch)
+    }
+}
+
+impl Display for Destination {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Destination::Audio => "audio",
+            Destination::Document => "document",
+            Destination::Embed => "embed",
+            Destination::Fetch => "fetch",
+            Destination::Font => "font",
+            Destination::Image => "image",
+            Destination::Json => "json",
+            Destination::Manifest => "manifest",
+            Destination::Object => "object",
+            Destination::Report => "report",
+            Destination::Script => "script",
+            Destination::ServiceWorker => "serviceworker",
+            Destination::SharedWorker => "sharedworker",
+            Destination::Style => "style",
+            Destination::Track => "track",
+            Destination::Video => "video",
+            Destination::Worker => "worker",
+            Destination::Xslt => "xslt",
+            Destination::Other(other) => other,
+        };
+
+        write!(formatter, "{}", s)
+    }
+}
+
+/// The CORS settings given by the "crossorigin" attribute.
+///
+/// As in HTML, a missing or unknown value means `Anonymous`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossOrigin {
+    Anonymous,
+    UseCredentials,
+}
+
+impl From<&str> for CrossOrigin {
+    fn from(s: &str) -> CrossOrigin {
+        if s.eq_ignore_ascii_case("use-credentials") {
+            CrossOrigin::UseCredentials
+        } else {
+            CrossOrigin::Anonymous
+        }
+    }
+}
+
+impl Display for CrossOrigin {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrossOrigin::Anonymous => write!(formatter, "anonymous"),
+            CrossOrigin::UseCredentials => write!(formatter, "use-credentials"),
+        }
+    }
+}
+
+/// The fetch priority hint given by the "fetchpriority" attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchPriority {
+    High,
+    Low,
+    Auto,
+}
+
+impl From<&str> for FetchPriority {
+    /// Unknown values fall back to `Auto` as in HTML.
+    fn from(s: &str) -> FetchPriority {
+        match &s.to_lowercase()[..] {
+            "high" => FetchPriority::High,
+            "low" => FetchPriority::Low,
+            _ => FetchPriority::Auto,
+        }
+    }
+}
+
+impl Display for FetchPriority {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchPriority::High => write!(formatter, "high"),
+            FetchPriority::Low => write!(formatter, "low"),
+            FetchPriority::Auto => write!(formatter, "auto"),
+        }
+    }
+}
+
+/// A token of the "blocking" attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Blocking {
+    Render,
+    Other(String),
+}
+
+impl From<&str> for Blocking {
+    fn from(s: &str) -> Blocking {
+        let sl = s.to_lowercase();
+
+        match &sl[..] {
+            "render" => Blocking::Render,
+            _ => Blocking::Other(sl),
+        }
+    }
+}
+
+impl Display for Blocking {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Blocking::Render => write!(formatter, "render"),
+            Blocking::Other(other) => write!(formatter, "{}", other),
+        }
+    }
+}
+
+/// An image candidate from the "imagesrcset" attribute, e.g.
+/// `/hero-2x.png 2x`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageCandidate {
+    pub url: String,
+    /// The width (`480w`) or pixel density (`2x`) descriptor, if any.
+    pub descriptor: Option<String>,
+}
+
+impl Display for ImageCandidate {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match &self.descriptor {
+            Some(descriptor) => write!(formatter, "{} {}", self.url, descriptor),
+            None => write!(formatter, "{}", self.url),
+        }
+    }
+}
+
+/// Splits a `srcset` value into its image candidates.
+fn parse_srcset(input: &str) -> Vec<ImageCandidate> {
+    let mut result = vec![];
+    let mut rest = input;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+
+        if rest.is_empty() {
+            break;
+        }
+
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let url = &rest[..end];
+        rest = &rest[end..];
+
+        if url.ends_with(',') {
+            result.push(ImageCandidate {
+                url: url.trim_end_matches(',').into(),
+                descriptor: None,
+            });
+            continue;
+        }
+
+        let end = rest.find(',').unwrap_or(rest.len());
+        let descriptor = rest[..end].trim();
+        rest = &rest[end..];
+
+        result.push(ImageCandidate {
+            url: url.into(),
+            descriptor: if descriptor.is_empty() {
+                None
+            } else {
+                Some(descriptor.into())
+            },
+        });
+    }
+
+    result
+}
+
+/// Accessors for preload attributes.
+///
+/// ```
+/// use linkheader::parse;
+/// use linkheader::preload::{CrossOrigin, Destination};
+///
+/// let input = r#"</font.woff2>; rel="preload"; as="font"; crossorigin; integrity="sha384-abc"; nopush"#;
+/// let header = parse(input, None).unwrap();
+/// let link = &header.links()[0];
+///
+/// assert_eq!(link.destination(), Some(Destination::Font));
+/// assert_eq!(link.cross_origin(), Some(CrossOrigin::Anonymous));
+/// assert_eq!(link.integrity(), Some("sha384-abc"));
+/// assert!(link.is_nopush());
+/// ```
+impl Link {
+    /// The "as" attribute.
+    pub fn destination(&self) -> Option<Destination> {
+        self.param("as")?
+            .value()
+            .as_ref()
+            .map(|value| value.text().into())
+    }
+
+    /// The "crossorigin" attribute, `None` when absent.
+    pub fn cross_origin(&self) -> Option<CrossOrigin> {
+        let param = self.param("crossorigin")?;
+
+        Some(
+            param
+                .value()
+                .as_ref()
+                .map_or(CrossOrigin::Anonymous, |value| value.text().into()),
+        )
+    }
+
+    /// The raw "integrity" attribute, i.e. Subresource Integrity metadata.
+    pub fn integrity(&self) -> Option<&str> {
+        self.param("integrity")?
+            .value()
+            .as_ref()
+            .map(|value| value.text())
+    }
+
+    /// Whether the "nopush" attribute is present, asking servers not to push
+    /// the resource (HTTP/2 Server Push).
+    pub fn is_nopush(&self) -> bool {
+        self.param("nopush").is_some()
+    }
+
+    /// The "fetchpriority" attribute.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    /// use linkheader::preload::{Blocking, FetchPriority};
+    ///
+    /// let input = r#"</hero.png>; rel="preload"; as="image"; fetchpriority="high"; blocking="render""#;
+    /// let mut header = parse(input, None).unwrap();
+    /// let link = &mut header.links_mut()[0];
+    ///
+    /// assert_eq!(link.fetch_priority(), Some(FetchPriority::High));
+    /// assert_eq!(link.blocking(), vec![Blocking::Render]);
+    ///
+    /// link.set_fetch_priority(FetchPriority::Low);
+    ///
+    /// assert_eq!(link.to_string(), r#"</hero.png>; rel="preload"; as="image"; fetchpriority="low"; blocking="render""#);
+    /// ```
+    pub fn fetch_priority(&self) -> Option<FetchPriority> {
+        self.param("fetchpriority")?
+            .value()
+            .as_ref()
+            .map(|value| value.text().into())
+    }
+
+    pub fn set_fetch_priority(&mut self, priority: FetchPriority) {
+        self.replace_param("fetchpriority", Some(priority.to_string().into()));
+    }
+
+    /// The tokens of the "blocking" attribute.
+    pub fn blocking(&self) -> Vec<Blocking> {
+        self.param("blocking")
+            .and_then(|param| param.value().as_ref())
+            .map_or(vec![], |value| {
+                value
+                    .text()
+                    .split_whitespace()
+                    .map(Blocking::from)
+                    .collect()
+            })
+    }
+
+    pub fn set_blocking(&mut self, tokens: &[Blocking]) {
+        let tokens: Vec<String> = tokens.iter().map(|token| token.to_string()).collect();
+
+        self.replace_param("blocking", Some(tokens.join(" ").into()));
+    }
+
+    /// The image candidates of the "imagesrcset" attribute.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</hero.png>; rel="preload"; as="image"; imagesrcset="/hero.png 1x, /hero-2x.png 2x"; imagesizes="100vw""#;
+    /// let header = parse(input, None).unwrap();
+    /// let link = &header.links()[0];
+    /// let candidates = link.image_srcset();
+    ///
+    /// assert_eq!(candidates[1].url, "/hero-2x.png");
+    /// assert_eq!(candidates[1].descriptor.as_deref(), Some("2x"));
+    /// assert_eq!(link.image_sizes(), Some("100vw"));
+    /// ```
+    pub fn image_srcset(&self) -> Vec<ImageCandidate> {
+        self.param("imagesrcset")
+            .and_then(|param| param.value().as_ref())
+            .map_or(vec![], |value| parse_srcset(value.text()))
+    }
+
+    pub fn set_image_srcset(&mut self, candidates: &[ImageCandidate]) {
+        let candidates: Vec<String> = candidates.iter().map(|c| c.to_string()).collect();
+
+        self.replace_param("imagesrcset", Some(candidates.join(", ").into()));
+    }
+
+    /// The raw "imagesizes" attribute.
+    pub fn image_sizes(&self) -> Option<&str> {
+        self.param("imagesizes")?
+            .value()
+            .as_ref()
+            .map(|value| value.text())
+    }
+
+    pub fn set_image_sizes(&mut self, sizes: &str) {
+        self.replace_param("imagesizes", Some(sizes.into()));
+    }
+}
+
+/// Builds the links to send in a `103 Early Hints` response (RFC8297).
+///
+/// Every asset becomes a `rel="preload"` link with an "as" inferred from its
+/// extension. Assets with an unknown extension get no "as", so browsers skip
+/// them rather than fetching a response nothing reuses; use `preload_as` to
+/// preload them. Fonts also get a "type" and, as fonts and fetches are always
+/// requested in CORS mode, a "crossorigin" attribute.
+/// Origins become `rel="preconnect"` links, with an extra anonymous one when a
+/// CORS asset is loaded from the same origin as browsers keep separate
+/// connections for them.
+///
+/// ```
+/// use linkheader::preload::EarlyHints;
+///
+/// let header = EarlyHints::new()
+///     .preconnect("https://fonts.example")
+///     .preload("/app.css")
+///     .preload("https://fonts.example/inter.woff2")
+///     .build();
+///
+/// assert_eq!(
+///     header.to_string(),
+///     r#"<https://fonts.example>; rel="preconnect", <https://fonts.example>; rel="preconnect"; crossorigin, </app.css>; rel="preload"; as="style", <https://fonts.example/inter.woff2>; rel="preload"; type="font/woff2"; as="font"; crossorigin"#
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EarlyHints {
+    origins: Vec<String>,
+    assets: Vec<(String, Option<Destination>)>,
+}
+
+impl EarlyHints {
+    pub fn new() -> EarlyHints {
+        EarlyHints::default()
+    }
+
+    /// Adds an origin to connect to ahead of time.
+    pub fn preconnect(mut self, origin: &str) -> EarlyHints {
+        self.origins.push(origin.trim_end_matches('/').into());
+        self
+    }
+
+    /// Adds an asset to preload, inferring its destination.
+    pub fn preload(mut self, target: &str) -> EarlyHints {
+        self.assets.push((target.into(), None));
+        self
+    }
+
+    /// Adds an asset to preload with the given destination.
+    pub fn preload_as(mut self, target: &str, destination: Destination) -> EarlyHints {
+        self.assets.push((target.into(), Some(destination)));
+        self
+    }
+
+    pub fn build(&self) -> Header {
+        let assets: Vec<(&str, Option<Destination>)> = self
+            .assets
+            .iter()
+            .map(|(target, destination)| {
+                let destination = destination
+                    .clone()
+                    .or_else(|| Destination::from_extension(target));
+
+                (&target[..], destination)
+            })
+            .collect();
+        let mut builders = vec![];
+
+        for origin in &self.origins {
+            builders.push(hint(origin, "preconnect"));
+
+            let has_cors_assets = assets.iter().any(|(target, destination)| {
+                destination.as_ref().is_some_and(Destination::requires_cors)
+                    && target.starts_with(&format!("{}/", origin))
+            });
+
+            if has_cors_assets {
+                let mut builder = hint(origin, "preconnect");
+                builder.add_param(Param::new("crossorigin", None));
+                builders.push(builder);
+            }
+        }
+
+        for (target, destination) in assets {
+            let mut builder = hint(target, "preload");
+            let destination = match destination {
+                Some(destination) => destination,
+                None => {
+                    builders.push(builder);
+                    continue;
+                }
+            };
+
+            if let Some(content_type) =
+                font_type(target).filter(|_| destination == Destination::Font)
+            {
+                builder.set_type(Value::Simple(content_type.into()));
+            }
+
+            builder.add_param(Param::new("as", Some(destination.to_string().into())));
+
+            if destination.requires_cors() {
+                builder.add_param(Param::new("crossorigin", None));
+            }
+
+            builders.push(builder);
+        }
+
+        builders
+            .into_iter()
+            .enumerate()
+            .flat_map(|(group, mut builder)| {
+                builder.set_group(group);
+                builder.build()
+            })
+            .collect()
+    }
+}
+
+fn hint(target: &str, relation: &str) -> LinkBuilder {
+    let mut builder = LinkBuilder::new(None);
+    builder.set_target(target);
+    builder.set_rel(Value::Simple(relation.into()));
+
+    builder
+}
+
+/// The media type of a font by its extension.
+fn font_type(path: &str) -> Option<&'static str> {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    let (_, extension) = path.rsplit_once('.')?;
+
+    match &extension.to_lowercase()[..] {
+        "woff" => Some("font/woff"),
+        "woff2" => Some("font/woff2"),
+        "ttf" => Some("font/ttf"),
+        "otf" => Some("font/otf"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srcset_candidates() {
+        let candidates = parse_srcset(" a.png, b.png 2x,c.png,  d.png 480w ,");
+
+        assert_eq!(
+            candidates,
+            vec![
+                ImageCandidate {
+                    url: "a.png".into(),
+                    descriptor: None
+                },
+                ImageCandidate {
+                    url: "b.png".into(),
+                    descriptor: Some("2x".into())
+                },
+                ImageCandidate {
+                    url: "c.png".into(),
+                    descriptor: None
+                },
+                ImageCandidate {
+                    url: "d.png".into(),
+                    descriptor: Some("480w".into())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn early_hints_destinations() {
+        let header = EarlyHints::new()
+            .preconnect("https://api.example/")
+            .preload_as("https://api.example/data", Destination::Fetch)
+            .preload_as("/worker", Destination::Worker)
+            .build();
+
+        assert_eq!(header.by_relation("preconnect").len(), 2);
+        assert_eq!(header.links()[2].destination(), Some(Destination::Fetch));
+        assert_eq!(
+            header.links()[2].cross_origin(),
+            Some(CrossOrigin::Anonymous)
+        );
+        assert_eq!(header.links()[3].destination(), Some(Destination::Worker));
+        assert_eq!(header.links()[3].cross_origin(), None);
+    }
+
+    #[test]
+    fn early_hints_unknown_extension() {
+        let header = EarlyHints::new()
+            .preconnect("https://api.example")
+            .preload("https://api.example/data")
+            .build();
+
+        assert_eq!(
+            header.to_string(),
+            r#"<https://api.example>; rel="preconnect", <https://api.example/data>; rel="preload""#
+        );
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Python bindings through `pyo3`.
+//!
+//! The `cdylib` build of the library is a Python extension module named
+//! `linkheader`:
+//!
+//! ```python
+//! import linkheader
+//!
+//! header = linkheader.parse('</2>; rel="next"; title="Page 2"', "https://example.org/")
+//! link = header.by_relation("next")[0]
+//!
+//! link.target   # "/2"
+//! link.context  # "https://example.org/"
+//! link.title    # "Page 2"
+//! str(header)   # '</2>; rel="next"; title="Page 2"'
+//! ```
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::param::Value;
+use crate::parser;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// A parsed `Link` header.
+#[pyclass(name = "Header", module = "linkheader", frozen)]
+struct PyHeader(Header);
+
+/// A single link. Every text is returned as given; star params are decoded.
+#[pyclass(name = "Link", module = "linkheader", frozen)]
+struct PyLink(Link);
+
+/// Parses a `Link` header field value with an optional context URL.
+///
+/// Raises `ValueError` when the value or the context is invalid.
+#[pyfunction]
+#[pyo3(signature = (input, context = None))]
+fn parse(input: &str, context: Option<&str>) -> PyResult<PyHeader> {
+    let context = context
+        .map(url::Url::parse)
+        .transpose()
+        .map_err(|err| PyValueError::new_err(format!("Invalid context: {}", err)))?;
+
+    parser::parse(input, context)
+        .map(PyHeader)
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+#[pymethods]
+impl PyHeader {
+    #[getter]
+    fn links(&self) -> Vec<PyLink> {
+        self.0.iter().cloned().map(PyLink).collect()
+    }
+
+    /// The links with the given relation type, compared case-insensitively.
+    fn by_relation(&self, relation: &str) -> Vec<PyLink> {
+        self.0
+            .by_relation(relation)
+            .into_iter()
+            .cloned()
+            .map(PyLink)
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Header({:?})", self.0.to_string())
+    }
+}
+
+#[pymethods]
+impl PyLink {
+    #[getter]
+    fn target(&self) -> &str {
+        self.0.target.as_str()
+    }
+
+    #[getter]
+    fn rel(&self) -> Option<&str> {
+        self.0.relation.as_ref().map(|relation| relation.as_str())
+    }
+
+    /// The effective context URL, if known.
+    #[getter]
+    fn context(&self) -> Option<String> {
+        self.0.context.url().map(|url| url.to_string())
+    }
+
+    #[getter]
+    fn anchor(&self) -> Option<&str> {
+        self.0.context.anchor()
+    }
+
+    #[getter]
+    fn title(&self) -> Option<&str> {
+        self.0.title.as_ref().map(Value::text)
+    }
+
+    #[getter]
+    fn hreflang(&self) -> Vec<&str> {
+        self.0.lang.iter().map(Value::text).collect()
+    }
+
+    #[getter]
+    fn media(&self) -> Option<&str> {
+        self.0.media.as_ref().map(Value::text)
+    }
+
+    #[getter]
+    fn r#type(&self) -> Option<&str> {
+        self.0.content_type.as_ref().map(Value::text)
+    }
+
+    /// Every attribute and param as `(name, value)` pairs in order. Valueless
+    /// params have `None` as value.
+    #[getter]
+    fn params(&self) -> Vec<(String, Option<String>)> {
+        self.0
+            .attributes()
+            .into_iter()
+            .map(|param| {
+                let name = param.name().to_string();
+
+                (name, param.into_value().map(|value| value.text().into()))
+            })
+            .collect()
+    }
+
+    /// The first value of the given attribute or param, if any.
+    fn param(&self, name: &str) -> Option<&str> {
+        self.0.param_values(name).first().map(|value| value.text())
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Link({:?})", self.0.to_string())
+    }
+}
+
+#[pymodule]
+fn linkheader(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(parse, module)?)?;
+    module.add_class::<PyHeader>()?;
+    module.add_class::<PyLink>()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_links() {
+        let header = parse(
+            r#"</2>; rel="next"; title="Two"; hreflang="en"; nopush"#,
+            Some("https://example.org/"),
+        )
+        .unwrap();
+        let link = &header.by_relation("NEXT")[0];
+
+        assert_eq!(link.target(), "/2");
+        assert_eq!(link.context().as_deref(), Some("https://example.org/"));
+        assert_eq!(link.title(), Some("Two"));
+        assert_eq!(link.param("hreflang"), Some("en"));
+        assert_eq!(link.params().last(), Some(&("nopush".to_string(), None)));
+        assert!(parse("<", None).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Link selection by multiple criteria.
+
+use crate::header::Header;
+use crate::link::Link;
+
+/// A set of criteria a link must satisfy to be selected.
+///
+/// Every criterion is optional and all given criteria must match.
+///
+/// ```
+/// use linkheader::parse;
+/// use linkheader::query::Query;
+///
+/// let input = r#"</book.json>; rel="alternate"; type="application/json"; hreflang="de", </book.html>; rel="alternate"; type="text/html"; hreflang="de""#;
+/// let header = parse(input, None).unwrap();
+/// let query = Query::new().rel("alternate").media_type("application/json").hreflang("de");
+/// let links = header.select(&query);
+///
+/// assert_eq!(links.len(), 1);
+/// assert_eq!(links[0].target.as_str(), "/book.json");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    relation: Option<String>,
+    media_type: Option<String>,
+    hreflang: Option<String>,
+    params: Vec<(String, Option<String>)>,
+}
+
+impl Query {
+    pub fn new() -> Query {
+        Query::default()
+    }
+
+    /// Matches the relation type, compared case-insensitively.
+    pub fn rel(mut self, relation: &str) -> Query {
+        self.relation = Some(relation.into());
+        self
+    }
+
+    /// Matches the "type" attribute ignoring its parameters. A type with a
+    /// `*` subtype, e.g. `image/*`, matches any subtype.
+    pub fn media_type(mut self, media_type: &str) -> Query {
+        self.media_type = Some(media_type.into());
+        self
+    }
+
+    /// Matches any of the "hreflang" values, compared case-insensitively.
+    pub fn hreflang(mut self, lang: &str) -> Query {
+        self.hreflang = Some(lang.into());
+        self
+    }
+
+    /// Matches a param by name and, when given, by value.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    /// use linkheader::query::Query;
+    ///
+    /// let input = r#"</font.woff2>; rel="preload"; as="font"; crossorigin, </app.js>; rel="preload"; as="script""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(header.select(&Query::new().param("as", Some("font"))).len(), 1);
+    /// assert_eq!(header.select(&Query::new().param("crossorigin", None)).len(), 1);
+    /// ```
+    pub fn param(mut self, name: &str, value: Option<&str>) -> Query {
+        self.params
+            .push((name.into(), value.map(|value| value.into())));
+        self
+    }
+
+    /// Whether the link satisfies all criteria.
+    pub fn matches(&self, link: &Link) -> bool {
+        self.relation
+            .as_ref()
+            .is_none_or(|relation| link.has_relation(relation))
+            && self.media_type.as_ref().is_none_or(|media_type| {
+                link.content_type
+                    .as_ref()
+                    .is_some_and(|value| media_type_matches(media_type, value.text()))
+            })
+            && self.hreflang.as_ref().is_none_or(|lang| {
+                link.lang
+                    .iter()
+                    .any(|value| value.text().eq_ignore_ascii_case(lang))
+            })
+            && self.params.iter().all(|(name, value)| {
+                link.params.iter().any(|param| {
+                    param.name().eq_ignore_ascii_case(name)
+                        && value.as_ref().is_none_or(|value| {
+                            param
+                                .value()
+                                .as_ref()
+                                .is_some_and(|actual| actual.text() == value)
+                        })
+                })
+            })
+    }
+}
+
+/// Compares media types by essence, case-insensitively, honouring a `*`
+/// subtype in the expected one.
+fn media_type_matches(expected: &str, actual: &str) -> bool {
+    let essence = |s: &str| s.split(';').next().unwrap_or("").trim().to_lowercase();
+    let expected = essence(expected);
+    let actual = essence(actual);
+
+    match expected.strip_suffix("/*") {
+        Some(kind) => actual.split('/').next() == Some(kind),
+        None => expected == actual,
+    }
+}
+
+impl Header {
+    /// Returns the links matching the query in order.
+    pub fn select(&self, query: &Query) -> Vec<&Link> {
+        self.links()
+            .iter()
+            .filter(|link| query.matches(link))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_type_essence() {
+        assert!(media_type_matches(
+            "application/json",
+            "Application/JSON; charset=utf-8"
+        ));
+        assert!(media_type_matches("image/*", "image/webp"));
+        assert!(!media_type_matches("image/*", "text/html"));
+        assert!(!media_type_matches(
+            "application/json",
+            "application/ld+json"
+        ));
+    }
+}
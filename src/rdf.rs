@@ -0,0 +1,299 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Links as RDF triples (RFC8288 Appendix A).
+//!
+//! The JSON-LD serialisation requires the `json` feature.
+
+use crate::header::Header;
+use crate::link::Relation;
+#[cfg(feature = "json")]
+use serde_json::{json, Map, Value as Json};
+use std::fmt;
+
+/// The base URI for registered relation types (RFC8288 Appendix A.2).
+pub const RELATION_BASE: &str = "http://www.iana.org/assignments/relation/";
+
+impl Relation {
+    /// The relation type as a URI. Registered relation types are prefixed
+    /// with `RELATION_BASE` and lowercased. `None` when an extension relation
+    /// type is not a valid URI.
+    ///
+    /// ```
+    /// use linkheader::Relation;
+    ///
+    /// assert_eq!(Relation::from("Next").to_uri().unwrap().as_str(), "http://www.iana.org/assignments/relation/next");
+    /// assert_eq!(Relation::from("https://example.org/rel/x").to_uri().unwrap().as_str(), "https://example.org/rel/x");
+    /// ```
+    pub fn to_uri(&self) -> Option<url::Url> {
+        if self.is_extension() {
+            url::Url::parse(self.as_str()).ok()
+        } else {
+            url::Url::parse(&format!(
+                "{}{}",
+                RELATION_BASE,
+                self.as_str().to_lowercase()
+            ))
+            .ok()
+        }
+    }
+}
+
+/// A link as an RDF statement: the context links to the target with the
+/// relation type as predicate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Triple {
+    pub subject: url::Url,
+    pub predicate: url::Url,
+    pub object: url::Url,
+}
+
+/// Writes the triple as an N-Triples statement.
+impl fmt::Display for Triple {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "<{}> <{}> <{}> .",
+            escape(&self.subject),
+            escape(&self.predicate),
+            escape(&self.object)
+        )
+    }
+}
+
+impl Header {
+    /// The links as RDF triples, in order.
+    ///
+    /// Links with no relation type, with no absolute context or whose target
+    /// cannot be resolved are left out as RDF needs absolute IRIs.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let context = url::Url::parse("https://example.org/book/3").ok();
+    /// let header = parse(r#"</book/4>; rel="next", </license>; rel="license"; anchor="/book/""#, context).unwrap();
+    /// let triples = header.triples();
+    ///
+    /// assert_eq!(
+    ///     triples[0].to_string(),
+    ///     "<https://example.org/book/3> <http://www.iana.org/assignments/relation/next> <https://example.org/book/4> ."
+    /// );
+    /// assert_eq!(triples[1].subject.as_str(), "https://example.org/book/");
+    /// ```
+    pub fn triples(&self) -> Vec<Triple> {
+        self.iter()
+            .filter_map(|link| {
+                Some(Triple {
+                    subject: link.context.url()?.clone(),
+                    predicate: link.relation.as_ref()?.to_uri()?,
+                    object: link.resolve_target(None).ok()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Serialises the links as N-Triples, one statement per line. See
+    /// `Header::triples`.
+    pub fn to_ntriples(&self) -> NTriples {
+        NTriples(self.triples())
+    }
+
+    /// Serialises the links as Turtle, abbreviating registered relation types
+    /// with a `rel:` prefix. See `Header::triples`.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let context = url::Url::parse("https://example.org/").ok();
+    /// let header = parse(r#"</a>; rel="next", </b>; rel="https://example.org/rel/x""#, context).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.to_turtle().to_string(),
+    ///     "@prefix rel: <http://www.iana.org/assignments/relation/> .\n\n\
+    ///      <https://example.org/> rel:next <https://example.org/a> .\n\
+    ///      <https://example.org/> <https://example.org/rel/x> <https://example.org/b> .\n"
+    /// );
+    /// ```
+    pub fn to_turtle(&self) -> Turtle {
+        Turtle(self.triples())
+    }
+
+    /// Serialises the links as a JSON-LD document. See `Header::triples`.
+    ///
+    /// Every context is a node in "@graph", in order of appearance, with a
+    /// property per relation type. Registered relation types are terms of
+    /// the default vocabulary and extension relation types are written as
+    /// their URI. A relation with one target is written as an object and one
+    /// with several as an array.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    /// use serde_json::json;
+    ///
+    /// let context = url::Url::parse("https://example.org/book/3").ok();
+    /// let header = parse(r#"</book/4>; rel="next", </a>; rel="author", </b>; rel="author""#, context).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.to_jsonld(),
+    ///     json!({
+    ///         "@context": { "@vocab": "http://www.iana.org/assignments/relation/" },
+    ///         "@graph": [{
+    ///             "@id": "https://example.org/book/3",
+    ///             "next": { "@id": "https://example.org/book/4" },
+    ///             "author": [
+    ///                 { "@id": "https://example.org/a" },
+    ///                 { "@id": "https://example.org/b" },
+    ///             ],
+    ///         }],
+    ///     })
+    /// );
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_jsonld(&self) -> Json {
+        let mut nodes: Vec<Map<String, Json>> = vec![];
+
+        for triple in self.triples() {
+            let subject = Json::from(triple.subject.as_str());
+            let idx = match nodes.iter().position(|node| node["@id"] == subject) {
+                Some(idx) => idx,
+                None => {
+                    let mut node = Map::new();
+                    node.insert("@id".into(), subject);
+                    nodes.push(node);
+                    nodes.len() - 1
+                }
+            };
+            let predicate = triple.predicate.as_str();
+            let property = match predicate.strip_prefix(RELATION_BASE) {
+                Some(name) if !name.is_empty() && !name.contains(':') => name,
+                _ => predicate,
+            };
+            let object = json!({ "@id": triple.object.as_str() });
+
+            match nodes[idx].get_mut(property) {
+                Some(Json::Array(items)) => items.push(object),
+                Some(current) => *current = json!([current.take(), object]),
+                None => {
+                    nodes[idx].insert(property.into(), object);
+                }
+            }
+        }
+
+        json!({
+            "@context": { "@vocab": RELATION_BASE },
+            "@graph": nodes,
+        })
+    }
+}
+
+/// The N-Triples serialisation of a header. See `Header::to_ntriples`.
+#[derive(Debug, Clone)]
+pub struct NTriples(Vec<Triple>);
+
+impl fmt::Display for NTriples {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for triple in &self.0 {
+            writeln!(formatter, "{}", triple)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The Turtle serialisation of a header. See `Header::to_turtle`.
+#[derive(Debug, Clone)]
+pub struct Turtle(Vec<Triple>);
+
+impl fmt::Display for Turtle {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(formatter, "@prefix rel: <{}> .\n", RELATION_BASE)?;
+
+        for triple in &self.0 {
+            let predicate = triple.predicate.as_str();
+
+            write!(formatter, "<{}> ", escape(&triple.subject))?;
+
+            match predicate.strip_prefix(RELATION_BASE) {
+                Some(name) if is_local_name(name) => write!(formatter, "rel:{}", name)?,
+                _ => write!(formatter, "<{}>", escape(&triple.predicate))?,
+            }
+
+            writeln!(formatter, " <{}> .", escape(&triple.object))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escapes the characters not allowed in an IRI reference.
+fn escape(url: &url::Url) -> String {
+    url.as_str()
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' => format!("\\u{:04X}", c as u32),
+            c if c <= ' ' => format!("\\u{:04X}", c as u32),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Whether the name can be written as a prefixed name without escaping.
+fn is_local_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && !name.ends_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse;
+    #[cfg(feature = "json")]
+    use serde_json::json;
+
+    #[test]
+    fn skip_unresolvable_links() {
+        let header = parse(
+            r#"</a>; rel="next", <https://example.org/b>; rel="next""#,
+            None,
+        )
+        .unwrap();
+
+        assert!(header.triples().is_empty());
+
+        let context = url::Url::parse("https://example.org/").ok();
+        let header = parse(r#"</a?q={x}>; rel="search", </b>"#, context).unwrap();
+
+        assert_eq!(
+            header.to_ntriples().to_string(),
+            "<https://example.org/> <http://www.iana.org/assignments/relation/search> <https://example.org/a?q=\\u007Bx\\u007D> .\n"
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn jsonld_nodes_per_context() {
+        let context = url::Url::parse("https://example.org/").ok();
+        let input = r#"</a>; rel="https://example.org/rel/x", </b>; rel="item"; anchor="/list""#;
+        let header = parse(input, context).unwrap();
+        let graph = &header.to_jsonld()["@graph"];
+
+        assert_eq!(
+            graph,
+            &json!([
+                {
+                    "@id": "https://example.org/",
+                    "https://example.org/rel/x": { "@id": "https://example.org/a" },
+                },
+                {
+                    "@id": "https://example.org/list",
+                    "item": { "@id": "https://example.org/b" },
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Redaction of sensitive query parameters for logging.
+
+use crate::context::Context;
+use crate::header::Header;
+use crate::uri::UriRef;
+use std::fmt::{self, Debug, Display};
+
+/// The text replacing a redacted value.
+pub const REDACTED: &str = "REDACTED";
+
+/// A view of a header with the values of the given query parameters redacted
+/// in targets and contexts, safe to log.
+///
+/// Both `Display` and `Debug` are redacted. Parameter names are compared
+/// case-insensitively after percent-decoding.
+///
+/// ```
+/// use linkheader::parse;
+///
+/// let input = r#"<https://example.org/items?page=2&access_token=s3cr3t>; rel="next""#;
+/// let header = parse(input, None).unwrap();
+/// let redacted = header.redacted(&["access_token", "signature"]);
+///
+/// assert_eq!(
+///     redacted.to_string(),
+///     r#"<https://example.org/items?page=2&access_token=REDACTED>; rel="next""#
+/// );
+/// assert!(!format!("{:?}", redacted).contains("s3cr3t"));
+/// ```
+pub struct Redacted<'a> {
+    header: &'a Header,
+    names: Vec<String>,
+}
+
+impl Header {
+    /// Returns a redacted view of the header for logging.
+    pub fn redacted(&self, names: &[&str]) -> Redacted<'_> {
+        Redacted {
+            header: self,
+            names: names.iter().map(|name| name.to_lowercase()).collect(),
+        }
+    }
+}
+
+impl Redacted<'_> {
+    /// Returns a copy of the header with the sensitive values replaced.
+    pub fn to_header(&self) -> Header {
+        let mut header = self.header.clone();
+
+        for link in header.links_mut() {
+            link.target = self.redact_uri(&link.target);
+            link.context = match &link.context {
+                Context::Absent => Context::Absent,
+                Context::Inherited(url) => Context::Inherited(self.redact_url(url)),
+                Context::Anchored {
+                    base,
+                    anchor,
+                    resolved,
+                } => Context::Anchored {
+                    base: base.as_ref().map(|url| self.redact_url(url)),
+                    anchor: self.redact_uri(&UriRef::from(&anchor[..])).into_string(),
+                    resolved: resolved.as_ref().map(|url| self.redact_url(url)),
+                },
+            };
+        }
+
+        header
+    }
+
+    fn redact_uri(&self, uri: &UriRef) -> UriRef {
+        match uri.query() {
+            Some(query) => {
+                let input = uri.as_str();
+                let start = input.find('?').map_or(0, |idx| idx + 1);
+                let end = start + query.len();
+
+                format!(
+                    "{}{}{}",
+                    &input[..start],
+                    self.redact_query(query),
+                    &input[end..]
+                )
+                .into()
+            }
+            None => uri.clone(),
+        }
+    }
+
+    fn redact_url(&self, url: &url::Url) -> url::Url {
+        let mut url = url.clone();
+
+        if let Some(query) = url.query().map(|query| self.redact_query(query)) {
+            url.set_query(Some(&query));
+        }
+
+        url
+    }
+
+    /// Replaces the value of every sensitive pair keeping the rest of the
+    /// query as is.
+    fn redact_query(&self, query: &str) -> String {
+        query
+            .split('&')
+            .map(|pair| {
+                let name = pair.split('=').next().unwrap_or("");
+                let decoded: String = url::form_urlencoded::parse(name.as_bytes())
+                    .map(|(name, _)| name.into_owned())
+                    .collect();
+
+                if self.names.contains(&decoded.to_lowercase()) {
+                    format!("{}={}", name, REDACTED)
+                } else {
+                    pair.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+impl Display for Redacted<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.to_header(), formatter)
+    }
+}
+
+impl Debug for Redacted<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.to_header(), formatter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse;
+
+    #[test]
+    fn redact_contexts() {
+        let context = url::Url::parse("https://example.org/?Sig=abc").ok();
+        let input = r#"</a?x=1&sig=def#top>; rel="next"; anchor="?%53ig=ghi""#;
+        let header = parse(input, context).unwrap();
+        let redacted = header.redacted(&["sig"]);
+
+        assert_eq!(
+            redacted.to_string(),
+            r#"</a?x=1&sig=REDACTED#top>; rel="next"; anchor="?%53ig=REDACTED""#
+        );
+
+        let debug = format!("{:?}", redacted);
+
+        assert!(!debug.contains("abc"));
+        assert!(!debug.contains("ghi"));
+    }
+}
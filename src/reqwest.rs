@@ -0,0 +1,72 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Link headers from `reqwest` responses.
+
+use crate::error::Result;
+use crate::header::Header;
+
+/// Reads the `Link` header of a response.
+///
+/// ```no_run
+/// use linkheader::reqwest::ResponseExt;
+///
+/// # async fn run() -> Result<(), failure::Error> {
+/// let response = reqwest::get("https://api.github.com/repos/rust-lang/rust/issues").await?;
+///
+/// if let Some(header) = response.link_header()? {
+///     println!("{:?}", header.next());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait ResponseExt {
+    /// Parses and merges every `Link` field using the response URL as
+    /// context. `None` when the response has no `Link` field.
+    fn link_header(&self) -> Result<Option<Header>>;
+}
+
+impl ResponseExt for ::reqwest::Response {
+    fn link_header(&self) -> Result<Option<Header>> {
+        link_header(self.headers(), self.url())
+    }
+}
+
+pub(crate) fn link_header(
+    headers: &::http::HeaderMap,
+    url: &::reqwest::Url,
+) -> Result<Option<Header>> {
+    if !headers.contains_key(::http::header::LINK) {
+        return Ok(None);
+    }
+
+    let context = url::Url::parse(url.as_str()).ok();
+
+    Header::from_header_map(headers, context).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_from_url() {
+        let mut headers = ::http::HeaderMap::new();
+        let url = ::reqwest::Url::parse("https://example.org/items?page=2").unwrap();
+
+        assert!(link_header(&headers, &url).unwrap().is_none());
+
+        headers.insert(
+            ::http::header::LINK,
+            r#"<?page=3>; rel="next""#.parse().unwrap(),
+        );
+
+        let header = link_header(&headers, &url).unwrap().unwrap();
+        let next = header.next().unwrap().resolve_target(None).unwrap();
+
+        assert_eq!(next.as_str(), "https://example.org/items?page=3");
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Link relation types for Web service discovery (RFC8631), e.g. to find the
+//! OpenAPI description or the status page of an API.
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::query::Query;
+
+impl Header {
+    /// The `rel="service-desc"` links, i.e. machine-readable descriptions of
+    /// the service such as OpenAPI documents, in order.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</openapi.json>; rel="service-desc"; type="application/openapi+json", </openapi.yaml>; rel="service-desc"; type="application/openapi+yaml", </docs>; rel="service-doc"; type="text/html", </health>; rel="status""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(header.service_desc().len(), 2);
+    /// assert_eq!(header.service_doc()[0].target.as_str(), "/docs");
+    /// assert_eq!(header.status().unwrap().target.as_str(), "/health");
+    /// ```
+    pub fn service_desc(&self) -> Vec<&Link> {
+        self.by_relation("service-desc")
+    }
+
+    /// The first `rel="service-desc"` link with the given "type", compared
+    /// ignoring its parameters. A `*` subtype matches any subtype.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</openapi.yaml>; rel="service-desc"; type="application/openapi+yaml", </openapi.json>; rel="service-desc"; type="application/openapi+json; version=3.1""#;
+    /// let header = parse(input, None).unwrap();
+    /// let link = header.service_desc_by_type("application/openapi+json").unwrap();
+    ///
+    /// assert_eq!(link.target.as_str(), "/openapi.json");
+    /// ```
+    pub fn service_desc_by_type(&self, media_type: &str) -> Option<&Link> {
+        self.first_by_query("service-desc", media_type)
+    }
+
+    /// The `rel="service-doc"` links, i.e. human-readable documentation of
+    /// the service, in order.
+    pub fn service_doc(&self) -> Vec<&Link> {
+        self.by_relation("service-doc")
+    }
+
+    /// The first `rel="service-doc"` link with the given "type". See
+    /// `Header::service_desc_by_type`.
+    pub fn service_doc_by_type(&self, media_type: &str) -> Option<&Link> {
+        self.first_by_query("service-doc", media_type)
+    }
+
+    /// The `rel="service-meta"` links, i.e. metadata about the service such as
+    /// policies or terms of use, in order.
+    pub fn service_meta(&self) -> Vec<&Link> {
+        self.by_relation("service-meta")
+    }
+
+    /// The first `rel="status"` link, i.e. the status page or health check of
+    /// the service.
+    pub fn status(&self) -> Option<&Link> {
+        self.first_by_relation("status")
+    }
+
+    fn first_by_query(&self, relation: &str, media_type: &str) -> Option<&Link> {
+        let query = Query::new().rel(relation).media_type(media_type);
+
+        self.iter().find(|link| query.matches(link))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse;
+
+    #[test]
+    fn wildcard_type() {
+        let input = r#"</a>; rel="Service-Doc"; type="text/plain", </b>; rel="service-doc"; type="text/html""#;
+        let header = parse(input, None).unwrap();
+
+        assert_eq!(
+            header
+                .service_doc_by_type("text/*")
+                .unwrap()
+                .target
+                .as_str(),
+            "/a"
+        );
+        assert!(header.service_desc_by_type("text/*").is_none());
+        assert!(header.status().is_none());
+    }
+}
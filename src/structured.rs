@@ -0,0 +1,581 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Links as Structured Field Values (RFC9651).
+//!
+//! An alternative to the `Link` header syntax where the link set is a List:
+//! every member is a String with the target URI and the relation types,
+//! anchor and target attributes as parameters. It is also the syntax of the
+//! `Link-Template` field.
+//!
+//! ```text
+//! "/TheBook/chapter2"; rel="previous"; title="previous chapter", "/"; rel="contents"
+//! ```
+
+use crate::error::{ParserError, Result};
+use crate::header::Header;
+use crate::param::{Encoding, Param, Value};
+use crate::parser::LinkBuilder;
+use std::collections::HashSet;
+use std::fmt;
+
+/// A bare item. Numbers and byte sequences are kept as written.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum BareItem {
+    Integer(String),
+    Decimal(String),
+    String(String),
+    Token(String),
+    ByteSequence(String),
+    Boolean(bool),
+    Date(String),
+    DisplayString(String),
+}
+
+impl BareItem {
+    /// The item as text, with no quoting. `None` for booleans.
+    pub(crate) fn text(&self) -> Option<&str> {
+        match self {
+            BareItem::Integer(text)
+            | BareItem::Decimal(text)
+            | BareItem::String(text)
+            | BareItem::Token(text)
+            | BareItem::ByteSequence(text)
+            | BareItem::Date(text)
+            | BareItem::DisplayString(text) => Some(text),
+            BareItem::Boolean(_) => None,
+        }
+    }
+}
+
+pub(crate) type Parameters = Vec<(String, BareItem)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Item {
+    pub bare: BareItem,
+    pub params: Parameters,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Member {
+    Item(Item),
+    InnerList(Vec<Item>, Parameters),
+}
+
+/// Parses a link set written as a Structured Field List.
+///
+/// Members are Strings, or Display Strings, with the target or Inner Lists
+/// of them sharing the list parameters. "rel" and "anchor" work as in the `Link` header and the
+/// remaining parameters as target attributes. Display Strings are read as
+/// UTF-8 compound values, `true` as a param with no value and `false` is left
+/// out.
+///
+/// ```
+/// use linkheader::structured;
+///
+/// let context = url::Url::parse("https://example.org/TheBook/chapter3").ok();
+/// let input = r#""/TheBook/chapter2"; rel="previous"; title=%"cap%c3%adtol anterior", ("/a" "/b"; type="text/html"); rel="item""#;
+/// let header = structured::parse(input, context).unwrap();
+///
+/// assert_eq!(header.len(), 3);
+/// assert_eq!(header.prev().unwrap().title_for("ca"), Some("capítol anterior"));
+/// assert_eq!(header.by_relation("item")[1].content_type, Some("text/html".into()));
+/// ```
+pub fn parse(input: &str, context: Option<url::Url>) -> Result<Header> {
+    let mut links = vec![];
+
+    for (group, member) in parse_list(input)?.into_iter().enumerate() {
+        let (items, shared) = match member {
+            Member::Item(item) => (vec![item], vec![]),
+            Member::InnerList(items, params) => (items, params),
+        };
+
+        for item in items {
+            let target = match item.bare {
+                BareItem::String(target) | BareItem::DisplayString(target) => target,
+                _ => return Err(invalid("expected a string target")),
+            };
+            let mut builder = LinkBuilder::new(context.clone());
+            builder.set_group(group);
+            builder.set_target(&target);
+
+            for (name, value) in shared.iter().cloned().chain(item.params) {
+                let value = match value {
+                    BareItem::Boolean(false) => continue,
+                    BareItem::DisplayString(value) => Some(Value::Compound {
+                        encoding: Encoding::Utf8,
+                        language: None,
+                        value,
+                    }),
+                    value => value.text().map(Value::from),
+                };
+
+                match (&name[..], value) {
+                    ("rel", Some(value)) => builder.set_rel(Value::Simple(value.text().into())),
+                    ("anchor", Some(value)) => builder.set_anchor(value),
+                    ("title", Some(value)) => builder.set_title(value),
+                    ("hreflang", Some(value)) => builder.set_lang(value),
+                    ("media", Some(value)) => builder.set_media(value),
+                    ("type", Some(value)) => builder.set_type(value),
+                    (_, value) => builder.add_param(Param::new(name, value)),
+                }
+            }
+
+            links.extend(builder.build());
+        }
+    }
+
+    Ok(Header::from(links))
+}
+
+/// The links of a header written as a Structured Field List. See
+/// `Header::to_structured`.
+pub struct StructuredLinks<'a>(&'a Header);
+
+impl Header {
+    /// Serialises the header as a Structured Field List. See
+    /// `structured::parse`.
+    ///
+    /// Values are written as Strings or, when compound or not ASCII, as
+    /// Display Strings, losing their language. Parameter names are lowercased
+    /// and, as parameters are unique, only the first of a repeated attribute
+    /// is kept. Params whose name cannot be a key are left out.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"</TheBook/chapter2>; rel="previous"; title*=UTF-8'de'letztes%20Kapitel; title="previous chapter", </>; rel="contents""#;
+    /// let header = parse(input, None).unwrap();
+    ///
+    /// assert_eq!(
+    ///     header.to_structured().to_string(),
+    ///     r#""/TheBook/chapter2"; rel="previous"; title=%"letztes Kapitel", "/"; rel="contents""#
+    /// );
+    /// ```
+    pub fn to_structured(&self) -> StructuredLinks<'_> {
+        StructuredLinks(self)
+    }
+}
+
+impl fmt::Display for StructuredLinks<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, group) in self.0.groups().into_iter().enumerate() {
+            if idx > 0 {
+                write!(formatter, ", ")?;
+            }
+
+            let link = &group[0];
+            let relations: Vec<&str> = group
+                .iter()
+                .filter_map(|link| link.relation.as_ref())
+                .map(|relation| relation.as_str())
+                .collect();
+
+            fmt_string(formatter, link.target.as_str())?;
+
+            if !relations.is_empty() {
+                write!(formatter, "; rel=")?;
+                fmt_string(formatter, &relations.join(" "))?;
+            }
+
+            if let Some(anchor) = link.context.anchor() {
+                write!(formatter, "; anchor=")?;
+                fmt_string(formatter, anchor)?;
+            }
+
+            let mut seen: HashSet<String> = ["rel", "anchor"]
+                .iter()
+                .map(|name| name.to_string())
+                .collect();
+
+            for param in link.attributes() {
+                let name = param.name().trim_end_matches('*').to_lowercase();
+
+                if !is_key(&name) || !seen.insert(name.clone()) {
+                    continue;
+                }
+
+                write!(formatter, "; {}", name)?;
+
+                match param.value() {
+                    Some(Value::Compound { value, .. }) => {
+                        write!(formatter, "=")?;
+                        fmt_display_string(formatter, value)?;
+                    }
+                    Some(Value::Simple(value)) => {
+                        write!(formatter, "=")?;
+                        fmt_string(formatter, value)?;
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a List field value (RFC9651 Section 4.2.1).
+pub(crate) fn parse_list(input: &str) -> Result<Vec<Member>> {
+    let mut parser = Parser { input, position: 0 };
+    let mut members = vec![];
+
+    parser.skip(|c| c == b' ');
+
+    while !parser.is_empty() {
+        members.push(parser.member()?);
+        parser.skip(|c| c == b' ' || c == b'\t');
+
+        if parser.is_empty() {
+            break;
+        }
+
+        parser.expect(b',')?;
+        parser.skip(|c| c == b' ' || c == b'\t');
+
+        if parser.is_empty() {
+            return Err(invalid("trailing comma"));
+        }
+    }
+
+    Ok(members)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn is_empty(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.position += 1;
+
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<()> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(invalid(&format!(
+                "expected {:?} at {}",
+                expected as char, self.position
+            ))),
+        }
+    }
+
+    fn skip(&mut self, predicate: impl Fn(u8) -> bool) {
+        while self.peek().is_some_and(&predicate) {
+            self.position += 1;
+        }
+    }
+
+    fn take(&mut self, predicate: impl Fn(u8) -> bool) -> &str {
+        let start = self.position;
+        self.skip(predicate);
+
+        &self.input[start..self.position]
+    }
+
+    fn member(&mut self) -> Result<Member> {
+        if self.peek() != Some(b'(') {
+            return Ok(Member::Item(self.item()?));
+        }
+
+        self.position += 1;
+        let mut items = vec![];
+
+        loop {
+            self.skip(|c| c == b' ');
+
+            if self.peek() == Some(b')') {
+                self.position += 1;
+
+                return Ok(Member::InnerList(items, self.parameters()?));
+            }
+
+            items.push(self.item()?);
+
+            match self.peek() {
+                Some(b' ') | Some(b')') => {}
+                _ => return Err(invalid("expected a space or \")\" in an inner list")),
+            }
+        }
+    }
+
+    fn item(&mut self) -> Result<Item> {
+        let bare = self.bare_item()?;
+        let params = self.parameters()?;
+
+        Ok(Item { bare, params })
+    }
+
+    fn parameters(&mut self) -> Result<Parameters> {
+        let mut params: Parameters = vec![];
+
+        while self.peek() == Some(b';') {
+            self.position += 1;
+            self.skip(|c| c == b' ');
+
+            let key = self.key()?;
+            let value = if self.peek() == Some(b'=') {
+                self.position += 1;
+                self.bare_item()?
+            } else {
+                BareItem::Boolean(true)
+            };
+
+            match params.iter_mut().find(|(name, _)| *name == key) {
+                Some(param) => param.1 = value,
+                None => params.push((key, value)),
+            }
+        }
+
+        Ok(params)
+    }
+
+    fn key(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(c) if c.is_ascii_lowercase() || c == b'*' => {}
+            _ => return Err(invalid(&format!("expected a key at {}", self.position))),
+        }
+
+        Ok(self.take(is_key_char).to_string())
+    }
+
+    fn bare_item(&mut self) -> Result<BareItem> {
+        match self.peek() {
+            Some(c) if c == b'-' || c.is_ascii_digit() => self.number(),
+            Some(b'"') => self.string().map(BareItem::String),
+            Some(c) if c.is_ascii_alphabetic() || c == b'*' => Ok(BareItem::Token(
+                self.take(|c| is_tchar(c) || c == b':' || c == b'/')
+                    .to_string(),
+            )),
+            Some(b':') => {
+                self.position += 1;
+                let bytes = self
+                    .take(|c| c.is_ascii_alphanumeric() || c == b'+' || c == b'/' || c == b'=')
+                    .to_string();
+                self.expect(b':')?;
+
+                Ok(BareItem::ByteSequence(bytes))
+            }
+            Some(b'?') => {
+                self.position += 1;
+
+                match self.next() {
+                    Some(b'1') => Ok(BareItem::Boolean(true)),
+                    Some(b'0') => Ok(BareItem::Boolean(false)),
+                    _ => Err(invalid("expected a boolean")),
+                }
+            }
+            Some(b'@') => {
+                self.position += 1;
+
+                match self.number()? {
+                    BareItem::Integer(text) => Ok(BareItem::Date(text)),
+                    _ => Err(invalid("expected an integer date")),
+                }
+            }
+            Some(b'%') => {
+                self.position += 1;
+
+                self.display_string().map(BareItem::DisplayString)
+            }
+            _ => Err(invalid(&format!("expected an item at {}", self.position))),
+        }
+    }
+
+    fn number(&mut self) -> Result<BareItem> {
+        let start = self.position;
+
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+
+        let integer = self.take(|c| c.is_ascii_digit()).len();
+
+        if integer == 0 || integer > 15 {
+            return Err(invalid("expected an integer of 1 to 15 digits"));
+        }
+
+        if self.peek() != Some(b'.') {
+            return Ok(BareItem::Integer(self.input[start..self.position].into()));
+        }
+
+        self.position += 1;
+        let fraction = self.take(|c| c.is_ascii_digit()).len();
+
+        if integer > 12 || fraction == 0 || fraction > 3 {
+            return Err(invalid("expected a decimal of up to 12 and 3 digits"));
+        }
+
+        Ok(BareItem::Decimal(self.input[start..self.position].into()))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect(b'"')?;
+        let mut result = String::new();
+
+        loop {
+            match self.next() {
+                Some(b'\\') => match self.next() {
+                    Some(c @ b'"') | Some(c @ b'\\') => result.push(c as char),
+                    _ => return Err(invalid("expected an escaped '\"' or '\\'")),
+                },
+                Some(b'"') => return Ok(result),
+                Some(c) if (0x20..0x7f).contains(&c) => result.push(c as char),
+                _ => return Err(invalid("expected a string of printable ASCII")),
+            }
+        }
+    }
+
+    fn display_string(&mut self) -> Result<String> {
+        self.expect(b'"')?;
+        let mut bytes = vec![];
+
+        loop {
+            match self.next() {
+                Some(b'%') => {
+                    let hex = self.input.get(self.position..self.position + 2);
+                    let octet = hex
+                        .filter(|hex| hex.bytes().all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'f')))
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                        .ok_or_else(|| invalid("expected two lowercase hex digits"))?;
+
+                    bytes.push(octet);
+                    self.position += 2;
+                }
+                Some(b'"') => {
+                    return String::from_utf8(bytes)
+                        .map_err(|_| invalid("expected a UTF-8 display string"))
+                }
+                Some(c) if (0x20..0x7f).contains(&c) => bytes.push(c),
+                _ => return Err(invalid("expected a display string of printable ASCII")),
+            }
+        }
+    }
+}
+
+fn is_tchar(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&c)
+}
+
+fn is_key_char(c: u8) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || b"_-.*".contains(&c)
+}
+
+/// Whether the name can be written as a key.
+fn is_key(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase() || c == '*') && name.bytes().all(is_key_char)
+}
+
+/// Writes text as a String or, when it has characters other than printable
+/// ASCII, as a Display String.
+pub(crate) fn fmt_string(formatter: &mut fmt::Formatter, text: &str) -> fmt::Result {
+    if !text.bytes().all(|c| (0x20..0x7f).contains(&c)) {
+        return fmt_display_string(formatter, text);
+    }
+
+    write!(formatter, "\"")?;
+
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            write!(formatter, "\\")?;
+        }
+
+        write!(formatter, "{}", c)?;
+    }
+
+    write!(formatter, "\"")
+}
+
+fn fmt_display_string(formatter: &mut fmt::Formatter, text: &str) -> fmt::Result {
+    write!(formatter, "%\"")?;
+
+    for c in text.bytes() {
+        match c {
+            b'%' | b'"' => write!(formatter, "%{:02x}", c)?,
+            0x20..=0x7e => write!(formatter, "{}", c as char)?,
+            _ => write!(formatter, "%{:02x}", c)?,
+        }
+    }
+
+    write!(formatter, "\"")
+}
+
+fn invalid(message: &str) -> failure::Error {
+    ParserError::InvalidStructuredField(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn list_members() {
+        let members =
+            parse_list(r#"  "a"; x=1;y, (tok 1.5);z=?0 , %"caf%c3%a9"; w=@1659578233"#).unwrap();
+
+        assert_eq!(members.len(), 3);
+        assert_eq!(
+            members[0],
+            Member::Item(Item {
+                bare: BareItem::String("a".into()),
+                params: vec![
+                    ("x".into(), BareItem::Integer("1".into())),
+                    ("y".into(), BareItem::Boolean(true))
+                ],
+            })
+        );
+        assert!(
+            matches!(&members[1], Member::InnerList(items, params) if items.len() == 2 && params.len() == 1)
+        );
+        assert_eq!(
+            members[2],
+            Member::Item(Item {
+                bare: BareItem::DisplayString("café".into()),
+                params: vec![("w".into(), BareItem::Date("1659578233".into()))],
+            })
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input = r#"</a>; rel="next prefetch"; anchor="/c"; Title="A, B"; hreflang=en; hreflang=de; nopush; x!y=1, <https://example.org/ü>"#;
+        let header = parser::parse(input, None).unwrap();
+        let output = header.to_structured().to_string();
+
+        assert_eq!(
+            output,
+            r#""/a"; rel="next prefetch"; anchor="/c"; hreflang="en"; title="A, B"; nopush, %"https://example.org/%c3%bc""#
+        );
+
+        let other = parse(&output, None).unwrap();
+
+        assert_eq!(other.len(), 3);
+        assert_eq!(other.links()[1].context.anchor(), Some("/c"));
+        assert_eq!(other.links()[2].target.as_str(), "https://example.org/ü");
+    }
+
+    #[test]
+    fn invalid_lists() {
+        assert!(parse_list(r#""a","#).is_err());
+        assert!(parse_list(r#""a" "b""#).is_err());
+        assert!(parse_list(r#""a";X=1"#).is_err());
+        assert!(parse_list(r#""a\n""#).is_err());
+        assert!(parse_list("1.2345").is_err());
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Proptest strategies for property testing integrations.
+//!
+//! Generated values stick to what the parser reads back: a generated header
+//! with links serialises to a value that parses to the same links.
+//! [`invalid_header_value`] produces values a single edit away from a valid
+//! one, which the parser must reject.
+//!
+//! ```
+//! use linkheader::parse;
+//! use linkheader::testing::header;
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(header in header().prop_filter("empty", |header| !header.is_empty()))| {
+//!     prop_assert_eq!(parse(&header.to_string(), None).unwrap(), header);
+//! });
+//! ```
+
+use crate::header::Header;
+use crate::link::{Link, Relation};
+use crate::param::{Encoding, Param, Value};
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+
+const TOKEN: &str = "[a-zA-Z0-9!#$&+.^_`|~-]+";
+const TARGET: &str = "[a-z0-9/?#=&._~:%@-]*";
+const RELATION: &str = "[a-z][a-z0-9._:/-]*";
+const LANGUAGE: &str = "[a-zA-Z]{2,3}(-[a-zA-Z0-9]{2,8})?";
+const TEXT: &str = "[ !#-\\[\\]-~]*";
+const UNICODE: &str = "[ a-zA-Z0-9äéñçøß€中文日本語Ωж]+";
+
+/// Names kept as attributes, i.e. not params, by the parser.
+const RESERVED: [&str; 6] = ["rel", "anchor", "title", "hreflang", "media", "type"];
+
+/// A compound value (RFC8187 ext-value) in UTF-8 with a language tag.
+pub fn ext_value() -> impl Strategy<Value = Value> {
+    (LANGUAGE, UNICODE).prop_map(|(language, value)| Value::Compound {
+        encoding: Encoding::Utf8,
+        language: Some(language),
+        value,
+    })
+}
+
+/// Either a simple or a compound value.
+pub fn value() -> impl Strategy<Value = Value> {
+    prop_oneof![TEXT.prop_map(Value::Simple), ext_value()]
+}
+
+/// A param with an optional value. Names never clash with the link
+/// attributes.
+pub fn param() -> impl Strategy<Value = Param> {
+    (TOKEN, option::of(value())).prop_map(|(mut name, value)| {
+        if RESERVED
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(&name))
+        {
+            name.insert_str(0, "x-");
+        }
+
+        Param::new(name, value)
+    })
+}
+
+/// A link with no context nor group.
+pub fn link() -> impl Strategy<Value = Link> {
+    let simple = || option::of(TOKEN.prop_map(Value::Simple));
+
+    (
+        TARGET,
+        option::of(RELATION.prop_map(Relation::from)),
+        option::of(value()),
+        vec(LANGUAGE.prop_map(Value::Simple), 0..3),
+        simple(),
+        simple(),
+        vec(param(), 0..4),
+    )
+        .prop_map(
+            |(target, relation, title, lang, media, content_type, params)| Link {
+                target: target.into(),
+                context: Default::default(),
+                relation,
+                title,
+                lang,
+                media,
+                content_type,
+                params,
+                group: None,
+            },
+        )
+}
+
+/// A header with up to 8 links, numbered as the parser numbers link-values.
+pub fn header() -> impl Strategy<Value = Header> {
+    vec(link(), 0..8).prop_map(|mut links| {
+        for (group, link) in links.iter_mut().enumerate() {
+            link.group = Some(group);
+        }
+
+        Header::from(links)
+    })
+}
+
+/// A valid `Link` header field value.
+pub fn header_value() -> impl Strategy<Value = String> {
+    vec(link(), 1..8).prop_map(|links| Header::from(links).to_string())
+}
+
+/// A `Link` header field value broken by a single edit: an unclosed target,
+/// a missing target, an unterminated quoted string or a dangling param.
+pub fn invalid_header_value() -> impl Strategy<Value = String> {
+    (header_value(), 0..4usize).prop_map(|(value, edit)| match edit {
+        0 => format!("{}, </unclosed", value),
+        1 => format!("{}, ; rel=\"next\"", value),
+        2 => format!("{}; title=\"unterminated", value),
+        _ => format!("{}; =", value),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    proptest! {
+        #[test]
+        fn header_roundtrip(header in header().prop_filter("empty", |header| !header.is_empty())) {
+            prop_assert_eq!(parse(&header.to_string(), None).unwrap(), header);
+        }
+
+        #[test]
+        fn invalid_values_fail(valid in header_value(), invalid in invalid_header_value()) {
+            prop_assert!(parse(&valid, None).is_ok());
+            prop_assert!(parse(&invalid, None).is_err());
+        }
+    }
+}
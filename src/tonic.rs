@@ -0,0 +1,57 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Link headers from gRPC metadata.
+
+use crate::error::Result;
+use crate::header::{Header, MergePolicy};
+use crate::parser::parse;
+use ::tonic::metadata::MetadataMap;
+
+impl Header {
+    /// Parses and merges every ASCII `link` entry of the metadata, in order.
+    ///
+    /// Useful for gRPC-web or transcoded services propagating pagination as
+    /// metadata. Binary (`-bin`) entries are not `link` entries and are
+    /// ignored.
+    ///
+    /// ```
+    /// use linkheader::Header;
+    /// use tonic::metadata::MetadataMap;
+    ///
+    /// let mut metadata = MetadataMap::new();
+    /// metadata.append("link", r#"</items?page=1>; rel="prev""#.parse().unwrap());
+    /// metadata.append("link", r#"</items?page=3>; rel="next""#.parse().unwrap());
+    ///
+    /// let header = Header::from_metadata(&metadata, None).unwrap();
+    ///
+    /// assert_eq!(header.next().unwrap().target.as_str(), "/items?page=3");
+    /// ```
+    pub fn from_metadata(metadata: &MetadataMap, context: Option<url::Url>) -> Result<Header> {
+        let mut header = Header::new();
+
+        for value in metadata.get_all("link").iter() {
+            let other = parse(value.to_str()?, context.clone())?;
+
+            header.merge(other, MergePolicy::KeepAll);
+        }
+
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_link_entries() {
+        let mut metadata = MetadataMap::new();
+        metadata.insert("x-link", r#"</a>; rel="next""#.parse().unwrap());
+
+        assert!(Header::from_metadata(&metadata, None).unwrap().is_empty());
+    }
+}
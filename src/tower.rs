@@ -0,0 +1,297 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! `tower` middleware reading and writing `Link` headers.
+
+use crate::error::Result;
+use crate::header::Header;
+use crate::http::uri_context;
+use http::{HeaderValue, Request, Response};
+use pin_project_lite::pin_project;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Parses the `Link` fields of every response into a `Header` response
+/// extension.
+///
+/// The request URI is the context when it is absolute. Responses with no
+/// `Link` field or with a malformed one get no extension.
+///
+/// ```
+/// use linkheader::tower::ParseLinksLayer;
+/// use linkheader::Header;
+/// use std::convert::Infallible;
+/// use std::future::{ready, Ready};
+/// use std::task::{Context, Poll};
+/// use tower_layer::Layer;
+/// use tower_service::Service;
+///
+/// struct Upstream;
+///
+/// impl Service<http::Request<()>> for Upstream {
+///     type Response = http::Response<()>;
+///     type Error = Infallible;
+///     type Future = Ready<Result<http::Response<()>, Infallible>>;
+///
+///     fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+///         Poll::Ready(Ok(()))
+///     }
+///
+///     fn call(&mut self, _: http::Request<()>) -> Self::Future {
+///         ready(Ok(http::Response::builder().header("link", r#"<?page=2>; rel="next""#).body(()).unwrap()))
+///     }
+/// }
+///
+/// let mut service = ParseLinksLayer::new().layer(Upstream);
+/// let request = http::Request::get("https://example.org/items").body(()).unwrap();
+/// let response = futures_executor::block_on(service.call(request)).unwrap();
+/// let header = response.extensions().get::<Header>().unwrap();
+/// let next = header.next().unwrap().resolve_target(None).unwrap();
+///
+/// assert_eq!(next.as_str(), "https://example.org/items?page=2");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseLinksLayer;
+
+impl ParseLinksLayer {
+    pub fn new() -> ParseLinksLayer {
+        ParseLinksLayer
+    }
+}
+
+impl<S> Layer<S> for ParseLinksLayer {
+    type Service = ParseLinks<S>;
+
+    fn layer(&self, inner: S) -> ParseLinks<S> {
+        ParseLinks { inner }
+    }
+}
+
+/// The service created by `ParseLinksLayer`.
+#[derive(Debug, Clone)]
+pub struct ParseLinks<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for ParseLinks<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = ParseLinksFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let context = uri_context(request.uri());
+
+        ParseLinksFuture {
+            inner: self.inner.call(request),
+            context,
+        }
+    }
+}
+
+pin_project! {
+    /// The response future of `ParseLinks`.
+    #[derive(Debug)]
+    pub struct ParseLinksFuture<F> {
+        #[pin]
+        inner: F,
+        context: Option<url::Url>,
+    }
+}
+
+impl<F, ResBody, E> Future for ParseLinksFuture<F>
+where
+    F: Future<Output = std::result::Result<Response<ResBody>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let this = self.project();
+        let mut response = match this.inner.poll(cx) {
+            Poll::Ready(Ok(response)) => response,
+            other => return other,
+        };
+
+        if response.headers().contains_key(http::header::LINK) {
+            if let Ok(header) = Header::from_header_map(response.headers(), this.context.take()) {
+                response.extensions_mut().insert(header);
+            }
+        }
+
+        Poll::Ready(Ok(response))
+    }
+}
+
+/// Appends a fixed set of links, e.g. preloads or a "service-desc", to every
+/// response as an extra `Link` field.
+///
+/// ```
+/// use linkheader::parse;
+/// use linkheader::tower::AddLinksLayer;
+///
+/// let header = parse(r#"</openapi.json>; rel="service-desc"; type="application/json""#, None).unwrap();
+/// let layer = AddLinksLayer::new(&header).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct AddLinksLayer {
+    value: HeaderValue,
+}
+
+impl AddLinksLayer {
+    /// Fails if the header cannot be sent as a header value.
+    pub fn new(header: &Header) -> Result<AddLinksLayer> {
+        Ok(AddLinksLayer {
+            value: HeaderValue::try_from(header)?,
+        })
+    }
+}
+
+impl<S> Layer<S> for AddLinksLayer {
+    type Service = AddLinks<S>;
+
+    fn layer(&self, inner: S) -> AddLinks<S> {
+        AddLinks {
+            inner,
+            value: self.value.clone(),
+        }
+    }
+}
+
+/// The service created by `AddLinksLayer`.
+#[derive(Debug, Clone)]
+pub struct AddLinks<S> {
+    inner: S,
+    value: HeaderValue,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for AddLinks<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = AddLinksFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        AddLinksFuture {
+            inner: self.inner.call(request),
+            value: Some(self.value.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// The response future of `AddLinks`.
+    #[derive(Debug)]
+    pub struct AddLinksFuture<F> {
+        #[pin]
+        inner: F,
+        value: Option<HeaderValue>,
+    }
+}
+
+impl<F, ResBody, E> Future for AddLinksFuture<F>
+where
+    F: Future<Output = std::result::Result<Response<ResBody>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let this = self.project();
+        let mut response = match this.inner.poll(cx) {
+            Poll::Ready(Ok(response)) => response,
+            other => return other,
+        };
+
+        if let Some(value) = this.value.take() {
+            response.headers_mut().append(http::header::LINK, value);
+        }
+
+        Poll::Ready(Ok(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use std::convert::Infallible;
+    use std::future::{ready, Ready};
+
+    /// Responds with the given `Link` field, if any.
+    struct Upstream(Option<&'static str>);
+
+    impl Service<Request<()>> for Upstream {
+        type Response = Response<()>;
+        type Error = Infallible;
+        type Future = Ready<std::result::Result<Response<()>, Infallible>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<std::result::Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _: Request<()>) -> Self::Future {
+            let mut response = Response::builder();
+
+            if let Some(value) = self.0 {
+                response = response.header(http::header::LINK, value);
+            }
+
+            ready(Ok(response.body(()).unwrap()))
+        }
+    }
+
+    fn get<S>(service: &mut S) -> Response<()>
+    where
+        S: Service<Request<()>, Response = Response<()>, Error = Infallible>,
+    {
+        let request = Request::get("/").body(()).unwrap();
+
+        futures_executor::block_on(service.call(request)).unwrap()
+    }
+
+    #[test]
+    fn add_links_keeps_upstream_links() {
+        let header = parse(r#"</app.css>; rel="preload"; as="style""#, None).unwrap();
+        let layer = AddLinksLayer::new(&header).unwrap();
+        let mut service =
+            ParseLinksLayer::new().layer(layer.layer(Upstream(Some("</2>; rel=\"next\""))));
+        let response = get(&mut service);
+        let header = response.extensions().get::<Header>().unwrap();
+
+        assert_eq!(
+            header.to_string(),
+            r#"</2>; rel="next", </app.css>; rel="preload"; as="style""#
+        );
+    }
+
+    #[test]
+    fn no_extension_without_links() {
+        let mut service = ParseLinksLayer::new().layer(Upstream(None));
+        let response = get(&mut service);
+
+        assert!(response.extensions().get::<Header>().is_none());
+
+        let mut service = ParseLinksLayer::new().layer(Upstream(Some("nonsense")));
+        let response = get(&mut service);
+
+        assert!(response.extensions().get::<Header>().is_none());
+    }
+}
@@ -4,9 +4,716 @@
 // This file may not be copied, modified, or distributed except
 // according to those terms.
 
-#[derive(Debug, Clone, PartialEq)]
+#[cfg(feature = "std")]
+use crate::error::{ParserError, Result};
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+};
+use core::fmt::{self, Display};
+
+/// A URI reference (RFC3986 Section 4.1), either a URI or a relative
+/// reference.
+///
+/// The reference is kept as given, no validation nor normalisation is applied.
+///
+/// ```
+/// use linkheader::UriRef;
+///
+/// let uri = UriRef::from("https://example.org/TheBook/chapter2#intro");
+///
+/// assert!(uri.is_absolute());
+/// assert_eq!(uri.scheme(), Some("https"));
+/// assert_eq!(uri.authority(), Some("example.org"));
+/// assert_eq!(uri.path(), "/TheBook/chapter2");
+/// assert_eq!(uri.fragment(), Some("intro"));
+/// assert_eq!(uri.to_string(), "https://example.org/TheBook/chapter2#intro");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct UriRef(String);
 
+impl UriRef {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the reference and returns the underlying string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Whether the reference has a scheme, i.e. it is a URI as opposed to a
+    /// relative reference.
+    pub fn is_absolute(&self) -> bool {
+        self.scheme().is_some()
+    }
+
+    pub fn scheme(&self) -> Option<&str> {
+        self.components().scheme
+    }
+
+    pub fn authority(&self) -> Option<&str> {
+        self.components().authority
+    }
+
+    /// The host from the authority, if any, with no userinfo nor port.
+    pub fn host(&self) -> Option<&str> {
+        self.authority()
+            .map(|authority| split_authority(authority).1)
+    }
+
+    /// The path, possibly empty.
+    pub fn path(&self) -> &str {
+        self.components().path
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.components().query
+    }
+
+    pub fn fragment(&self) -> Option<&str> {
+        self.components().fragment
+    }
+
+    /// Parses the query as `application/x-www-form-urlencoded` name-value
+    /// pairs, empty when there is no query.
+    ///
+    /// ```
+    /// use linkheader::UriRef;
+    ///
+    /// let uri = UriRef::from("/repos?page=3&per_page=100");
+    /// let page = uri.query_pairs().find(|(name, _)| name == "page").map(|(_, value)| value);
+    ///
+    /// assert_eq!(page.as_deref(), Some("3"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn query_pairs(&self) -> url::form_urlencoded::Parse<'_> {
+        url::form_urlencoded::parse(self.query().unwrap_or("").as_bytes())
+    }
+
+    /// Converts the reference into a URL resolving it against the given base
+    /// when present.
+    ///
+    /// Fails if the reference is relative and no base is given.
+    ///
+    /// ```
+    /// use linkheader::UriRef;
+    ///
+    /// let base = url::Url::parse("http://example.com/TheBook/chapter3").unwrap();
+    /// let uri = UriRef::from("chapter4");
+    ///
+    /// assert_eq!(uri.to_url(Some(&base)).unwrap().as_str(), "http://example.com/TheBook/chapter4");
+    /// assert!(uri.to_url(None).is_err());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_url(&self, base: Option<&url::Url>) -> Result<url::Url> {
+        let url = match base {
+            Some(base) => base.join(&self.0)?,
+            None => url::Url::parse(&self.0)?,
+        };
+
+        Ok(url)
+    }
+
+    /// Returns the shortest reference that resolves to the same URL as this
+    /// one when resolved against the given base.
+    ///
+    /// Relative references are first resolved against the base. References
+    /// that do not share scheme with the base are returned absolute.
+    ///
+    /// ```
+    /// use linkheader::UriRef;
+    ///
+    /// let base = url::Url::parse("http://example.com/TheBook/chapter3").unwrap();
+    ///
+    /// let uri = UriRef::from("http://example.com/TheBook/chapter4");
+    /// assert_eq!(uri.make_relative(&base).unwrap().as_str(), "chapter4");
+    ///
+    /// let uri = UriRef::from("http://example.com/terms");
+    /// assert_eq!(uri.make_relative(&base).unwrap().as_str(), "/terms");
+    ///
+    /// let uri = UriRef::from("http://example.net/TheBook/chapter4");
+    /// assert_eq!(uri.make_relative(&base).unwrap().as_str(), "//example.net/TheBook/chapter4");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn make_relative(&self, base: &url::Url) -> Result<UriRef> {
+        let target = self.to_url(Some(base))?;
+
+        if target.scheme() != base.scheme() || target.cannot_be_a_base() || base.cannot_be_a_base()
+        {
+            return Ok(target.into_string().into());
+        }
+
+        if target.username() != base.username()
+            || target.password() != base.password()
+            || target.host() != base.host()
+            || target.port() != base.port()
+        {
+            let rest = &target.as_str()[target.scheme().len() + 1..];
+
+            return Ok(rest.into());
+        }
+
+        let mut result = relative_path(base.path(), target.path(), target.query().is_some());
+
+        if let Some(query) = target.query() {
+            if !result.is_empty() || target.query() != base.query() {
+                result.push('?');
+                result.push_str(query);
+            }
+        }
+
+        if let Some(fragment) = target.fragment() {
+            result.push('#');
+            result.push_str(fragment);
+        }
+
+        Ok(result.into())
+    }
+
+    /// Maps an IRI reference (RFC3987) to a URI reference percent-encoding
+    /// every non-ASCII character as UTF-8.
+    ///
+    /// ```
+    /// use linkheader::UriRef;
+    ///
+    /// let iri = UriRef::from("https://example.org/àbac?q=£");
+    ///
+    /// assert_eq!(iri.to_uri().as_str(), "https://example.org/%C3%A0bac?q=%C2%A3");
+    /// ```
+    pub fn to_uri(&self) -> UriRef {
+        let mut result = String::with_capacity(self.0.len());
+
+        for c in self.0.chars() {
+            if c.is_ascii() {
+                result.push(c);
+            } else {
+                let mut buffer = [0; 4];
+
+                for byte in c.encode_utf8(&mut buffer).bytes() {
+                    result.push_str(&format!("%{:02X}", byte));
+                }
+            }
+        }
+
+        UriRef(result)
+    }
+
+    /// Maps a URI reference to an IRI reference (RFC3987 Section 3.2)
+    /// decoding percent-encoded UTF-8 sequences of non-ASCII characters.
+    ///
+    /// Percent-encoded ASCII characters, invalid UTF-8 sequences and control
+    /// characters are left encoded.
+    ///
+    /// ```
+    /// use linkheader::UriRef;
+    ///
+    /// let uri = UriRef::from("https://example.org/%C3%A0bac%20%FF?q=%C2%A3");
+    ///
+    /// assert_eq!(uri.to_iri().as_str(), "https://example.org/àbac%20%FF?q=£");
+    /// ```
+    pub fn to_iri(&self) -> UriRef {
+        let mut result = String::with_capacity(self.0.len());
+        let mut rest = &self.0[..];
+
+        while let Some(idx) = rest.find('%') {
+            result.push_str(&rest[..idx]);
+            rest = &rest[idx..];
+
+            let mut bytes = vec![];
+
+            while let Some(byte) = rest
+                .get(bytes.len() * 3..bytes.len() * 3 + 3)
+                .filter(|triplet| triplet.starts_with('%'))
+                .and_then(|triplet| u8::from_str_radix(&triplet[1..], 16).ok())
+            {
+                bytes.push(byte);
+            }
+
+            if bytes.is_empty() {
+                result.push('%');
+                rest = &rest[1..];
+                continue;
+            }
+
+            result.push_str(&decode_non_ascii(&rest[..bytes.len() * 3], &bytes));
+            rest = &rest[bytes.len() * 3..];
+        }
+
+        result.push_str(rest);
+
+        UriRef(result)
+    }
+
+    /// Maps an IRI reference to a URI reference converting the host to
+    /// Punycode (IDNA) and percent-encoding any other non-ASCII character.
+    ///
+    /// Unlike `to_uri`, the result can be safely followed by clients that
+    /// only resolve ASCII hostnames. Fails if the host is not a valid
+    /// internationalized domain name.
+    ///
+    /// ```
+    /// use linkheader::UriRef;
+    ///
+    /// let iri = UriRef::from("https://bücher.example/àbac");
+    ///
+    /// assert_eq!(iri.to_ascii().unwrap().as_str(), "https://xn--bcher-kva.example/%C3%A0bac");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_ascii(&self) -> Result<UriRef> {
+        self.map_host(|host| {
+            if host.is_ascii() {
+                return Ok(host.to_string());
+            }
+
+            url::idna::domain_to_ascii(host)
+                .map_err(|_| ParserError::InvalidHost(host.into()).into())
+        })
+        .map(|uri| uri.to_uri())
+    }
+
+    /// Maps a URI reference to an IRI reference converting a Punycode host to
+    /// Unicode and decoding percent-encoded non-ASCII characters (see
+    /// `to_iri`).
+    ///
+    /// Hosts that fail to decode are kept as given.
+    ///
+    /// ```
+    /// use linkheader::UriRef;
+    ///
+    /// let uri = UriRef::from("https://xn--bcher-kva.example/%C3%A0bac");
+    ///
+    /// assert_eq!(uri.to_unicode().as_str(), "https://bücher.example/àbac");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_unicode(&self) -> UriRef {
+        let result = self.map_host(|host| {
+            let (unicode, result) = url::idna::domain_to_unicode(host);
+
+            Ok(match result {
+                Ok(()) => unicode,
+                Err(_) => host.to_string(),
+            })
+        });
+
+        result.unwrap_or_else(|_| self.clone()).to_iri()
+    }
+
+    /// Rebuilds the reference with the host transformed by the given function.
+    /// IP literals are left untouched.
+    #[cfg(feature = "std")]
+    fn map_host(&self, f: impl Fn(&str) -> Result<String>) -> Result<UriRef> {
+        let authority = match self.authority() {
+            Some(authority) => authority,
+            None => return Ok(self.clone()),
+        };
+        let (userinfo, host, port) = split_authority(authority);
+
+        if host.starts_with('[') {
+            return Ok(self.clone());
+        }
+
+        // The authority always follows the optional scheme and "//".
+        let start = self.scheme().map_or(0, |scheme| scheme.len() + 1) + 2;
+        let mut result = String::with_capacity(self.0.len());
+
+        result.push_str(&self.0[..start]);
+
+        if let Some(userinfo) = userinfo {
+            result.push_str(userinfo);
+            result.push('@');
+        }
+
+        result.push_str(&f(host)?);
+
+        if let Some(port) = port {
+            result.push(':');
+            result.push_str(port);
+        }
+
+        result.push_str(&self.0[start + authority.len()..]);
+
+        Ok(UriRef(result))
+    }
+
+    /// Normalises the reference following the syntax-based and scheme-based
+    /// normalisations from RFC3986 Section 6.2.
+    ///
+    /// * The scheme and host are lowercased.
+    /// * Default ports for well-known schemes are removed.
+    /// * Percent-encoded triplets are uppercased and unreserved characters
+    ///   decoded.
+    /// * Dot-segments are removed unless the reference is a relative-path
+    ///   reference.
+    /// * An empty path with an authority becomes `/`.
+    ///
+    /// ```
+    /// use linkheader::UriRef;
+    ///
+    /// let uri = UriRef::from("HTTP://Example.ORG:80/a/./b/../c/%7euser?q=%2f");
+    ///
+    /// assert_eq!(uri.normalize().as_str(), "http://example.org/a/c/~user?q=%2F");
+    /// ```
+    pub fn normalize(&self) -> UriRef {
+        let components = self.components();
+        let mut result = String::with_capacity(self.0.len());
+        let scheme = components.scheme.map(|scheme| scheme.to_ascii_lowercase());
+
+        if let Some(scheme) = &scheme {
+            result.push_str(scheme);
+            result.push(':');
+        }
+
+        if let Some(authority) = components.authority {
+            result.push_str("//");
+            result.push_str(&normalize_authority(authority, scheme.as_deref()));
+        }
+
+        let path = normalize_percent_encoding(components.path);
+        let path = if components.scheme.is_some()
+            || components.authority.is_some()
+            || path.starts_with('/')
+        {
+            remove_dot_segments(&path)
+        } else {
+            path
+        };
+
+        if path.is_empty() && components.authority.is_some() {
+            result.push('/');
+        } else {
+            result.push_str(&path);
+        }
+
+        if let Some(query) = components.query {
+            result.push('?');
+            result.push_str(&normalize_percent_encoding(query));
+        }
+
+        if let Some(fragment) = components.fragment {
+            result.push('#');
+            result.push_str(&normalize_percent_encoding(fragment));
+        }
+
+        UriRef(result)
+    }
+
+    /// Whether both references are equivalent once normalised.
+    ///
+    /// ```
+    /// use linkheader::UriRef;
+    ///
+    /// let a = UriRef::from("https://example.org");
+    /// let b = UriRef::from("https://EXAMPLE.org:443/");
+    ///
+    /// assert!(a.equivalent(&b));
+    /// ```
+    pub fn equivalent(&self, other: &UriRef) -> bool {
+        self == other || self.normalize() == other.normalize()
+    }
+
+    /// Splits the reference following the regular expression from RFC3986
+    /// Appendix B.
+    fn components(&self) -> Components<'_> {
+        let mut rest = &self.0[..];
+        let mut result = Components::default();
+
+        if let Some(idx) = rest.find([':', '/', '?', '#']) {
+            if rest[idx..].starts_with(':') && is_scheme(&rest[..idx]) {
+                result.scheme = Some(&rest[..idx]);
+                rest = &rest[idx + 1..];
+            }
+        }
+
+        if let Some(idx) = rest.find('#') {
+            result.fragment = Some(&rest[idx + 1..]);
+            rest = &rest[..idx];
+        }
+
+        if let Some(idx) = rest.find('?') {
+            result.query = Some(&rest[idx + 1..]);
+            rest = &rest[..idx];
+        }
+
+        if rest.starts_with("//") {
+            let end = rest[2..].find('/').map_or(rest.len(), |idx| idx + 2);
+            result.authority = Some(&rest[2..end]);
+            rest = &rest[end..];
+        }
+
+        result.path = rest;
+
+        result
+    }
+}
+
+#[derive(Debug, Default)]
+struct Components<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+/// Checks the scheme grammar: `ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )`.
+fn is_scheme(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        }
+        _ => false,
+    }
+}
+
+impl Display for UriRef {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for UriRef {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<UriRef> for String {
+    fn from(uri: UriRef) -> String {
+        uri.0
+    }
+}
+
+/// Computes the shortest relative path from the base path to the target path.
+///
+/// Both paths are expected to be absolute. The result is empty when both paths
+/// are the same and a query follows.
+#[cfg(feature = "std")]
+fn relative_path(base: &str, target: &str, has_query: bool) -> String {
+    if base == target && has_query {
+        return String::new();
+    }
+
+    let base_dirs: Vec<&str> = match base.rfind('/') {
+        Some(idx) => base[..idx].split('/').collect(),
+        None => vec![],
+    };
+    let target_segments: Vec<&str> = target.split('/').collect();
+    let (target_dirs, _) = target_segments.split_at(target_segments.len() - 1);
+    let common = base_dirs
+        .iter()
+        .zip(target_dirs.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut result = "../".repeat(base_dirs.len() - common);
+    result.push_str(&target_segments[common..].join("/"));
+
+    if result.is_empty() {
+        result.push_str("./");
+    } else if result
+        .split('/')
+        .next()
+        .is_some_and(|segment| segment.contains(':'))
+    {
+        result.insert_str(0, "./");
+    }
+
+    // Prefer the absolute path when it is shorter and not mistaken by an
+    // authority.
+    if target.len() < result.len() && !target.starts_with("//") {
+        return target.to_string();
+    }
+
+    result
+}
+
+/// Decodes a run of percent-encoded octets keeping the original triplets for
+/// anything but non-ASCII, non-control UTF-8 characters.
+fn decode_non_ascii(triplets: &str, bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(triplets.len());
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        let width = match bytes[idx] {
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => 1,
+        };
+
+        let decoded = bytes
+            .get(idx..idx + width)
+            .filter(|_| width > 1)
+            .and_then(|chunk| core::str::from_utf8(chunk).ok())
+            .filter(|chunk| !chunk.chars().any(char::is_control));
+
+        match decoded {
+            Some(chunk) => {
+                result.push_str(chunk);
+                idx += width;
+            }
+            None => {
+                result.push_str(&triplets[idx * 3..idx * 3 + 3]);
+                idx += 1;
+            }
+        }
+    }
+
+    result
+}
+
+/// Lowercases the host and drops the port when empty or the default one for
+/// the scheme.
+fn normalize_authority(authority: &str, scheme: Option<&str>) -> String {
+    let (userinfo, host, port) = split_authority(authority);
+    let mut result = String::with_capacity(authority.len());
+
+    if let Some(userinfo) = userinfo {
+        result.push_str(&normalize_percent_encoding(userinfo));
+        result.push('@');
+    }
+
+    result.push_str(&lowercase_host(&normalize_percent_encoding(host)));
+
+    if let Some(port) = port {
+        if !port.is_empty() && Some(port) != scheme.and_then(default_port) {
+            result.push(':');
+            result.push_str(port);
+        }
+    }
+
+    result
+}
+
+/// Lowercases a host but for its percent-encoded octets, which stay
+/// uppercase.
+fn lowercase_host(host: &str) -> String {
+    let mut result = String::with_capacity(host.len());
+    let mut rest = host;
+
+    while let Some(idx) = rest.find('%') {
+        result.push_str(&rest[..idx].to_lowercase());
+        rest = &rest[idx..];
+
+        let len = match rest.get(1..3) {
+            Some(hex) if hex.bytes().all(|b| b.is_ascii_hexdigit()) => 3,
+            _ => 1,
+        };
+
+        result.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+
+    result.push_str(&rest.to_lowercase());
+
+    result
+}
+
+/// Splits an authority into userinfo, host and port.
+fn split_authority(authority: &str) -> (Option<&str>, &str, Option<&str>) {
+    let (userinfo, hostport) = match authority.rfind('@') {
+        Some(idx) => (Some(&authority[..idx]), &authority[idx + 1..]),
+        None => (None, authority),
+    };
+
+    // The port can only follow the closing bracket of an IP literal.
+    let port_start = hostport
+        .rfind(':')
+        .filter(|&idx| !hostport[idx..].contains(']'));
+
+    match port_start {
+        Some(idx) => (userinfo, &hostport[..idx], Some(&hostport[idx + 1..])),
+        None => (userinfo, hostport, None),
+    }
+}
+
+fn default_port(scheme: &str) -> Option<&'static str> {
+    match scheme {
+        "http" | "ws" => Some("80"),
+        "https" | "wss" => Some("443"),
+        "ftp" => Some("21"),
+        _ => None,
+    }
+}
+
+/// Uppercases percent-encoded triplets and decodes the ones representing
+/// unreserved characters.
+fn normalize_percent_encoding(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(idx) = rest.find('%') {
+        result.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        match rest
+            .get(1..3)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            Some(byte) if is_unreserved(byte) => result.push(byte as char),
+            Some(_) => result.push_str(&rest[..3].to_uppercase()),
+            None => {
+                result.push('%');
+                rest = &rest[1..];
+                continue;
+            }
+        }
+
+        rest = &rest[3..];
+    }
+
+    result.push_str(rest);
+
+    result
+}
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'.' || byte == b'_' || byte == b'~'
+}
+
+/// Removes "." and ".." segments as defined by RFC3986 Section 5.2.4.
+fn remove_dot_segments(path: &str) -> String {
+    let mut input = path.to_string();
+    let mut output = String::with_capacity(path.len());
+
+    while !input.is_empty() {
+        if input.starts_with("../") {
+            input.replace_range(..3, "");
+        } else if input.starts_with("./") {
+            input.replace_range(..2, "");
+        } else if input.starts_with("/./") {
+            input.replace_range(..3, "/");
+        } else if input == "/." {
+            input.replace_range(.., "/");
+        } else if input.starts_with("/../") {
+            input.replace_range(..4, "/");
+            output.truncate(output.rfind('/').unwrap_or(0));
+        } else if input == "/.." {
+            input.replace_range(.., "/");
+            output.truncate(output.rfind('/').unwrap_or(0));
+        } else if input == "." || input == ".." {
+            input.clear();
+        } else {
+            let end = input[1..].find('/').map_or(input.len(), |idx| idx + 1);
+            output.push_str(&input[..end]);
+            input.replace_range(..end, "");
+        }
+    }
+
+    output
+}
+
 impl From<String> for UriRef {
     fn from(s: String) -> Self {
         UriRef(s)
@@ -18,3 +725,177 @@ impl From<&str> for UriRef {
         UriRef(s.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_reference() {
+        let uri = UriRef::from("/TheBook/chapter2?page=2");
+
+        assert!(!uri.is_absolute());
+        assert_eq!(uri.scheme(), None);
+        assert_eq!(uri.authority(), None);
+        assert_eq!(uri.path(), "/TheBook/chapter2");
+        assert_eq!(uri.query(), Some("page=2"));
+        assert_eq!(uri.fragment(), None);
+    }
+
+    #[test]
+    fn network_path_reference() {
+        let uri = UriRef::from("//example.org?q");
+
+        assert_eq!(uri.scheme(), None);
+        assert_eq!(uri.authority(), Some("example.org"));
+        assert_eq!(uri.path(), "");
+        assert_eq!(uri.query(), Some("q"));
+    }
+
+    #[test]
+    fn colon_in_first_segment() {
+        let uri = UriRef::from("./a:b");
+
+        assert_eq!(uri.scheme(), None);
+        assert_eq!(uri.path(), "./a:b");
+    }
+
+    #[test]
+    fn remove_dot_segments_examples() {
+        // RFC3986 Section 5.2.4
+        assert_eq!(remove_dot_segments("/a/b/c/./../../g"), "/a/g");
+        assert_eq!(remove_dot_segments("mid/content=5/../6"), "mid/6");
+        assert_eq!(remove_dot_segments("/../a"), "/a");
+        assert_eq!(remove_dot_segments("/a/b/.."), "/a/");
+    }
+
+    #[test]
+    fn normalize_keeps_relative_paths() {
+        let uri = UriRef::from("../a/%7Eb/./c");
+
+        assert_eq!(uri.normalize().as_str(), "../a/~b/./c");
+    }
+
+    #[test]
+    fn normalize_authority_parts() {
+        let uri = UriRef::from("https://User@[::1]:8443");
+
+        assert_eq!(uri.normalize().as_str(), "https://User@[::1]:8443/");
+
+        let uri = UriRef::from("http://Example.org:/");
+
+        assert_eq!(uri.normalize().as_str(), "http://example.org/");
+
+        let uri = UriRef::from("http://M%c3%bcnchen.%44E/");
+
+        assert_eq!(uri.normalize().as_str(), "http://m%C3%BCnchen.de/");
+    }
+
+    #[test]
+    fn equivalent_ignores_percent_case() {
+        let a = UriRef::from("/a%c3%a0");
+        let b = UriRef::from("/a%C3%A0");
+
+        assert!(a.equivalent(&b));
+        assert!(!a.equivalent(&UriRef::from("/a%C3%A1")));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn query_pairs_decoded() {
+        let uri = UriRef::from("?cursor=a%2Bb+c&empty#page=2");
+        let pairs: Vec<(String, String)> = uri
+            .query_pairs()
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect();
+
+        assert_eq!(
+            pairs,
+            vec![
+                ("cursor".into(), "a+b c".into()),
+                ("empty".into(), "".into())
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn make_relative_examples() {
+        let base = url::Url::parse("http://a/b/c/d;p?q").unwrap();
+        let cases = vec![
+            ("http://a/b/c/g", "g"),
+            ("http://a/b/c/g/", "g/"),
+            ("http://a/g", "/g"),
+            ("http://a/b/c/d;p?y", "?y"),
+            ("http://a/b/c/g?y", "g?y"),
+            ("http://a/b/c/d;p?q#s", "#s"),
+            ("http://a/b/c/d;p", "d;p"),
+            ("http://a/b/c/d;p?q", ""),
+            ("http://a/b/c/", "./"),
+            ("http://a/b/", "../"),
+            ("http://a/b/g", "../g"),
+            ("http://a/b/c/g:h", "./g:h"),
+            ("https://a/b/c/g", "https://a/b/c/g"),
+            ("g", "g"),
+        ];
+
+        for (target, expected) in cases {
+            let actual = UriRef::from(target).make_relative(&base).unwrap();
+
+            assert_eq!(actual.as_str(), expected, "{}", target);
+            assert_eq!(
+                base.join(actual.as_str()).unwrap().as_str(),
+                base.join(target).unwrap().as_str()
+            );
+        }
+    }
+
+    #[test]
+    fn iri_roundtrip() {
+        let iri = UriRef::from("http://example.org/\u{FE0F}/%25/ü");
+
+        assert_eq!(
+            iri.to_uri().as_str(),
+            "http://example.org/%EF%B8%8F/%25/%C3%BC"
+        );
+        assert_eq!(iri.to_uri().to_iri(), iri);
+    }
+
+    #[test]
+    fn to_iri_keeps_control_characters() {
+        let uri = UriRef::from("/a%C2%85b%e2%82");
+
+        assert_eq!(uri.to_iri().as_str(), "/a%C2%85b%e2%82");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn idn_keeps_userinfo_and_port() {
+        let iri = UriRef::from("http://user@bücher.example:8080/?q=ü#ü");
+        let uri = iri.to_ascii().unwrap();
+
+        assert_eq!(
+            uri.as_str(),
+            "http://user@xn--bcher-kva.example:8080/?q=%C3%BC#%C3%BC"
+        );
+        assert_eq!(uri.host(), Some("xn--bcher-kva.example"));
+        assert_eq!(uri.to_unicode(), iri);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn idn_ignores_ip_literals() {
+        let uri = UriRef::from("http://[::1]:8080/");
+
+        assert_eq!(uri.to_ascii().unwrap(), uri);
+        assert_eq!(uri.host(), Some("[::1]"));
+    }
+
+    #[test]
+    fn urn() {
+        let uri = UriRef::from("urn:isbn:0451450523");
+
+        assert_eq!(uri.scheme(), Some("urn"));
+        assert_eq!(uri.path(), "isbn:0451450523");
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A `warp` filter extracting the `Link` header of requests.
+
+use crate::error::Result;
+use crate::header::{Header, MergePolicy};
+use crate::http::decode;
+use crate::parser::parse;
+use ::warp::filters::path::FullPath;
+use ::warp::http::HeaderMap;
+use ::warp::reject::{Reject, Rejection};
+use ::warp::Filter;
+use std::future::ready;
+
+/// Extracts every `Link` field of the request merged in a single header.
+///
+/// Warp does not know the scheme and authority the service is reachable at,
+/// so the context is the given base URL with the path and query of the
+/// request. A request without `Link` fields extracts an empty header. A
+/// malformed one is rejected with `InvalidLinks`.
+///
+/// ```
+/// use linkheader::warp::links;
+///
+/// let filter = links(url::Url::parse("https://example.org/").unwrap());
+/// let request = warp::test::request()
+///     .path("/items?page=2")
+///     .header("link", r#"<?page=3>; rel="next""#);
+/// let header = futures_executor::block_on(request.filter(&filter)).unwrap();
+/// let next = header.next().unwrap().resolve_target(None).unwrap();
+///
+/// assert_eq!(next.as_str(), "https://example.org/items?page=3");
+/// ```
+pub fn links(base: url::Url) -> impl Filter<Extract = (Header,), Error = Rejection> + Clone {
+    ::warp::path::full()
+        .and(
+            ::warp::query::raw()
+                .or(::warp::any().map(String::new))
+                .unify(),
+        )
+        .and(::warp::header::headers_cloned())
+        .and_then(move |path: FullPath, query: String, headers: HeaderMap| {
+            let mut context = base.clone();
+            context.set_path(path.as_str());
+            context.set_query(if query.is_empty() { None } else { Some(&query) });
+
+            let result: std::result::Result<Header, Rejection> = from_headers(&headers, context)
+                .map_err(|err| ::warp::reject::custom(InvalidLinks(err)));
+
+            ready(result)
+        })
+}
+
+/// Parses and merges every `Link` field in the map, in order.
+fn from_headers(headers: &HeaderMap, context: url::Url) -> Result<Header> {
+    let mut header = Header::new();
+
+    for value in headers.get_all(::warp::http::header::LINK) {
+        let other = parse(&decode(value.as_bytes()), Some(context.clone()))?;
+
+        header.merge(other, MergePolicy::KeepAll);
+    }
+
+    Ok(header)
+}
+
+/// The rejection for a request with a malformed `Link` header.
+#[derive(Debug)]
+pub struct InvalidLinks(pub failure::Error);
+
+impl Reject for InvalidLinks {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reject_malformed() {
+        let filter = links(url::Url::parse("https://example.org/").unwrap());
+        let request = ::warp::test::request().header("link", "nonsense");
+        let rejection = futures_executor::block_on(request.filter(&filter)).unwrap_err();
+
+        assert!(rejection.find::<InvalidLinks>().is_some());
+
+        let request = ::warp::test::request().path("/items");
+        let header = futures_executor::block_on(request.filter(&filter)).unwrap();
+
+        assert!(header.is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! JavaScript bindings through `wasm-bindgen`.
+//!
+//! Links are exchanged as plain objects:
+//!
+//! ```js
+//! import { parse, serialize } from "linkheader";
+//!
+//! const links = parse('</2>; rel="next"; title*=UTF-8\'de\'Seite%202', "https://example.org/");
+//! // [{
+//! //   href: "/2",
+//! //   context: "https://example.org/",
+//! //   rel: "next",
+//! //   params: [{ name: "title", value: "Seite 2", language: "de" }],
+//! // }]
+//!
+//! serialize([{ href: "/", rel: "index", params: [{ name: "nopush" }] }]);
+//! // '</>; rel="index"; nopush'
+//! ```
+//!
+//! "context" is the effective context URL and "anchor" the "anchor" param as
+//! given. Both are left out when unknown. Serialising reads "anchor" and
+//! ignores "context".
+
+use crate::header::Header;
+use crate::link::Link;
+use crate::param::{Encoding, Param, Value};
+use crate::parser::{self, LinkBuilder};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct JsLink {
+    href: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    anchor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rel: Option<String>,
+    #[serde(default)]
+    params: Vec<JsParam>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct JsParam {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+}
+
+/// Parses a `Link` header field value into an array of link objects.
+#[wasm_bindgen]
+pub fn parse(input: &str, context: Option<String>) -> Result<JsValue, JsError> {
+    let context = context
+        .map(|context| url::Url::parse(&context))
+        .transpose()
+        .map_err(|err| JsError::new(&format!("Invalid context: {}", err)))?;
+    let header = parser::parse(input, context).map_err(|err| JsError::new(&err.to_string()))?;
+    let links: Vec<JsLink> = header.iter().map(to_js).collect();
+
+    Ok(serde_wasm_bindgen::to_value(&links)?)
+}
+
+/// Serialises an array of link objects as a `Link` header field value.
+#[wasm_bindgen]
+pub fn serialize(links: JsValue) -> Result<String, JsError> {
+    let links: Vec<JsLink> = serde_wasm_bindgen::from_value(links)?;
+
+    Ok(from_js(links).to_string())
+}
+
+fn to_js(link: &Link) -> JsLink {
+    let params = link
+        .attributes()
+        .into_iter()
+        .map(|param| {
+            let name = param.name().to_string();
+
+            match param.into_value() {
+                None => JsParam {
+                    name,
+                    value: None,
+                    language: None,
+                },
+                Some(Value::Simple(value)) => JsParam {
+                    name,
+                    value: Some(value),
+                    language: None,
+                },
+                Some(Value::Compound {
+                    language, value, ..
+                }) => JsParam {
+                    name,
+                    value: Some(value),
+                    language,
+                },
+            }
+        })
+        .collect();
+
+    JsLink {
+        href: link.target.to_string(),
+        context: link.context.url().map(|url| url.to_string()),
+        anchor: link.context.anchor().map(String::from),
+        rel: link
+            .relation
+            .as_ref()
+            .map(|relation| relation.as_str().into()),
+        params,
+    }
+}
+
+fn from_js(links: Vec<JsLink>) -> Header {
+    let mut result = vec![];
+
+    for (group, link) in links.into_iter().enumerate() {
+        let mut builder = LinkBuilder::new(None);
+        builder.set_group(group);
+        builder.set_target(&link.href);
+
+        if let Some(anchor) = link.anchor {
+            builder.set_anchor(Value::Simple(anchor));
+        }
+
+        if let Some(relation) = link.rel {
+            builder.set_rel(Value::Simple(relation));
+        }
+
+        for JsParam {
+            name,
+            value,
+            language,
+        } in link.params
+        {
+            let value = value.map(|value| match language {
+                None if value.is_ascii() => Value::Simple(value),
+                language => Value::Compound {
+                    encoding: Encoding::Utf8,
+                    language,
+                    value,
+                },
+            });
+
+            match (name.to_lowercase().as_str(), value) {
+                ("title", Some(value)) => builder.set_title(value),
+                ("hreflang", Some(value)) => builder.set_lang(value),
+                ("media", Some(value)) => builder.set_media(value),
+                ("type", Some(value)) => builder.set_type(value),
+                (_, value) => builder.add_param(Param::new(name, value)),
+            }
+        }
+
+        result.extend(builder.build());
+    }
+
+    Header::from(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_objects_roundtrip() {
+        let context = url::Url::parse("https://example.org/").ok();
+        let input = r#"</2>; rel="next"; title*=UTF-8'de'Seite%202; nopush, </a>; rel="item"; anchor="/list""#;
+        let header = parser::parse(input, context).unwrap();
+        let links: Vec<JsLink> = header.iter().map(to_js).collect();
+
+        assert_eq!(links[0].context.as_deref(), Some("https://example.org/"));
+        assert_eq!(links[1].anchor.as_deref(), Some("/list"));
+        assert_eq!(
+            links[0].params[0],
+            JsParam {
+                name: "title".into(),
+                value: Some("Seite 2".into()),
+                language: Some("de".into()),
+            }
+        );
+        assert_eq!(from_js(links).to_string(), header.to_string());
+    }
+}
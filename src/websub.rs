@@ -0,0 +1,114 @@
+// Copyright 2019 Arnau Siches
+//
+// Licensed under the MIT license <LICENSE or http://opensource.org/licenses/MIT>.
+// This file may not be copied, modified, or distributed except
+// according to those terms.
+
+//! WebSub discovery ([W3C Recommendation](https://www.w3.org/TR/websub/#discovery)).
+//!
+//! Publishers advertise the hubs of a topic with `rel="hub"` links and the
+//! topic URL with a `rel="self"` link.
+
+use crate::error::Result;
+use crate::header::Header;
+use crate::link::Link;
+
+/// The resolved hubs and topic advertised by a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discovery {
+    /// The hub URLs, in order. Subscribers can use any of them.
+    pub hubs: Vec<url::Url>,
+    /// The canonical topic URL to subscribe to.
+    pub topic: url::Url,
+}
+
+impl Header {
+    /// The first `rel="hub"` and `rel="self"` links, if both are present.
+    ///
+    /// Links with an "anchor" are left out as they advertise the hub of
+    /// another resource.
+    ///
+    /// ```
+    /// use linkheader::parse;
+    ///
+    /// let input = r#"<https://hub.example/>; rel="hub", <https://example.org/feed>; rel="self""#;
+    /// let header = parse(input, None).unwrap();
+    /// let (hub, topic) = header.websub().unwrap();
+    ///
+    /// assert_eq!(hub.target.as_str(), "https://hub.example/");
+    /// assert_eq!(topic.target.as_str(), "https://example.org/feed");
+    /// ```
+    pub fn websub(&self) -> Option<(&Link, &Link)> {
+        let hub = self.websub_links("hub").next()?;
+        let topic = self.websub_links("self").next()?;
+
+        Some((hub, topic))
+    }
+
+    fn websub_links(&self, relation: &'static str) -> impl Iterator<Item = &Link> {
+        self.by_relation(relation)
+            .into_iter()
+            .filter(|link| link.context.anchor().is_none())
+    }
+}
+
+/// Resolves the hubs and topic advertised by the header against the link
+/// context.
+///
+/// Returns `None` when there is no hub or no topic and fails when any of
+/// them cannot be resolved.
+///
+/// ```
+/// use linkheader::{parse, websub};
+///
+/// let context = url::Url::parse("https://example.org/feed?page=1").ok();
+/// let input = r#"<https://hub.example/>; rel="hub", <https://backup.example/>; rel="hub", </feed>; rel="self""#;
+/// let header = parse(input, context).unwrap();
+/// let discovery = websub::discover(&header).unwrap().unwrap();
+///
+/// assert_eq!(discovery.hubs.len(), 2);
+/// assert_eq!(discovery.topic.as_str(), "https://example.org/feed");
+/// ```
+pub fn discover(header: &Header) -> Result<Option<Discovery>> {
+    let (_, topic) = match header.websub() {
+        Some(pair) => pair,
+        None => return Ok(None),
+    };
+    let hubs = header
+        .websub_links("hub")
+        .map(|link| link.resolve_target(None))
+        .collect::<Result<Vec<_>>>()?;
+    let topic = topic.resolve_target(None)?;
+
+    Ok(Some(Discovery { hubs, topic }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn incomplete_discovery() {
+        let header = parse(r#"<https://hub.example/>; rel="hub""#, None).unwrap();
+
+        assert!(header.websub().is_none());
+        assert_eq!(discover(&header).unwrap(), None);
+
+        let input = r#"<https://hub.example/>; rel="hub"; anchor="/other", </feed>; rel="self""#;
+        let header = parse(input, None).unwrap();
+
+        assert!(header.websub().is_none());
+    }
+
+    #[test]
+    fn unresolvable_topic() {
+        let header = parse(
+            r#"<https://hub.example/>; rel="hub", </feed>; rel="self""#,
+            None,
+        )
+        .unwrap();
+
+        assert!(discover(&header).is_err());
+    }
+}